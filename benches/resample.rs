@@ -0,0 +1,152 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! Benchmarks of resampling a buffer of samples over many intervals.
+
+use chrono::{DateTime, TimeDelta, Utc};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use frequenz_resampling::{Resampler, ResamplingFunction, Sample};
+
+#[derive(Debug, Clone, Default, Copy, PartialEq)]
+struct BenchSample {
+    timestamp: DateTime<Utc>,
+    value: Option<f64>,
+}
+
+impl Sample for BenchSample {
+    type Value = f64;
+
+    fn new(timestamp: DateTime<Utc>, value: Option<f64>) -> Self {
+        Self { timestamp, value }
+    }
+
+    fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.value
+    }
+}
+
+/// Resamples 100 000 samples at 10 Hz to one second intervals, with a
+/// maximum age of the given number of intervals.
+fn resample(c: &mut Criterion) {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::milliseconds(100);
+    let samples = (1..=100_000)
+        .map(|i| BenchSample::new(start + step * i, Some(f64::from(i))))
+        .collect::<Vec<_>>();
+    let end = start + step * 100_000;
+
+    let mut group = c.benchmark_group("resample");
+    for max_age_in_intervals in [1, 10, 100] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(max_age_in_intervals),
+            &max_age_in_intervals,
+            |b, &max_age_in_intervals| {
+                b.iter_batched(
+                    || {
+                        let mut resampler: Resampler<f64, BenchSample> = Resampler::new(
+                            TimeDelta::seconds(1),
+                            ResamplingFunction::Average,
+                            max_age_in_intervals,
+                            start,
+                            false,
+                        );
+                        resampler.extend(samples.iter().copied());
+                        resampler
+                    },
+                    |mut resampler| black_box(resampler.resample(end)),
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+/// Resamples a window of 10 samples at a time, like a resampler of one of many
+/// channels that is resampled every interval.
+fn resample_window(c: &mut Criterion) {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::milliseconds(100);
+    let mut group = c.benchmark_group("resample_window");
+    for (name, resampling_function) in [
+        ("average", ResamplingFunction::Average),
+        ("last", ResamplingFunction::Last),
+    ] {
+        let mut resampler: Resampler<f64, BenchSample> =
+            Resampler::new(TimeDelta::seconds(1), resampling_function, 1, start, false);
+        let mut i = 0;
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                for _ in 0..10 {
+                    i += 1;
+                    resampler.push(BenchSample::new(start + step * i, Some(f64::from(i))));
+                }
+                black_box(resampler.resample(start + step * i))
+            })
+        });
+    }
+    group.finish();
+}
+
+/// Resamples 100 000 samples at 1 kHz to one second intervals, with and
+/// without the SIMD fast path.
+fn resample_large_windows(c: &mut Criterion) {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::milliseconds(1);
+    let samples = (1..=100_000)
+        .map(|i| BenchSample::new(start + step * i, Some(f64::from(i))))
+        .collect::<Vec<_>>();
+    let end = start + step * 100_000;
+
+    let mut group = c.benchmark_group("resample_large_windows");
+    for name in ["sum", "average", "min", "max"] {
+        let paths: &[bool] = if cfg!(feature = "simd") {
+            &[false, true]
+        } else {
+            &[false]
+        };
+        for &simd in paths {
+            let id = BenchmarkId::new(name, if simd { "simd" } else { "generic" });
+            group.bench_function(id, |b| {
+                b.iter_batched(
+                    || {
+                        let mut resampler = large_window_resampler(name, simd, start);
+                        resampler.extend(samples.iter().copied());
+                        resampler
+                    },
+                    |mut resampler| black_box(resampler.resample(end)),
+                    BatchSize::LargeInput,
+                )
+            });
+        }
+    }
+    group.finish();
+}
+
+/// Returns a resampler with the resampling function of the given name, which
+/// uses the SIMD fast path if `simd` is set.
+fn large_window_resampler(
+    name: &str,
+    #[allow(unused_variables)] simd: bool,
+    start: DateTime<Utc>,
+) -> Resampler<f64, BenchSample> {
+    let resampling_function = match name {
+        "sum" => ResamplingFunction::Sum,
+        "average" => ResamplingFunction::Average,
+        "min" => ResamplingFunction::Min,
+        _ => ResamplingFunction::Max,
+    };
+    let resampler = Resampler::new(TimeDelta::seconds(1), resampling_function, 1, start, false);
+    #[cfg(feature = "simd")]
+    if simd {
+        return resampler.with_simd_aggregation();
+    }
+    resampler
+}
+
+criterion_group!(benches, resample, resample_window, resample_large_windows);
+criterion_main!(benches);
@@ -0,0 +1,68 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! Resamples a live stream of samples in a background task, which sends the
+//! resampled samples whenever a window ends.
+
+use chrono::{DateTime, DurationRound, TimeDelta, Utc};
+use frequenz_resampling::{spawn_resampler, Resampler, ResamplingFunction, Sample};
+use tokio::sync::mpsc;
+
+#[derive(Debug, Clone, Default, Copy, PartialEq)]
+struct PowerSample {
+    timestamp: DateTime<Utc>,
+    value: Option<f64>,
+}
+
+impl Sample for PowerSample {
+    type Value = f64;
+
+    fn new(timestamp: DateTime<Utc>, value: Option<f64>) -> Self {
+        Self { timestamp, value }
+    }
+
+    fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.value
+    }
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let interval = TimeDelta::seconds(1);
+    let start = Utc::now().duration_trunc(interval).unwrap();
+    let resampler: Resampler<f64, PowerSample> =
+        Resampler::new(interval, ResamplingFunction::Average, 1, start, false);
+
+    let (sample_sender, sample_receiver) = mpsc::channel(16);
+    let (batch_sender, mut batch_receiver) = mpsc::channel(16);
+    let task = spawn_resampler(resampler, sample_receiver, batch_sender);
+
+    // a producer that sends a sample every 100 ms for 3 seconds
+    tokio::spawn(async move {
+        for i in 0..30 {
+            let sample = PowerSample::new(Utc::now(), Some(1000.0 + i as f64));
+            if sample_sender.send(sample).await.is_err() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+    });
+
+    while let Some(batch) = batch_receiver.recv().await {
+        for sample in batch.samples {
+            match sample.value() {
+                Some(value) => println!("{}: {:.1} W", sample.timestamp(), value),
+                None => println!("{}: no data", sample.timestamp()),
+            }
+        }
+    }
+
+    // the task returns the resampler once the producer is done, with the
+    // samples of the incomplete window still in its buffer
+    let resampler = task.await.unwrap();
+    println!("{} samples not resampled", resampler.buffer_len());
+}
@@ -0,0 +1,58 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! Resamples a batch of historical samples in one go, as done when
+//! backfilling a time series.
+
+use chrono::{DateTime, TimeDelta, Utc};
+use frequenz_resampling::{Resampler, ResamplingFunction, Sample};
+
+#[derive(Debug, Clone, Default, Copy, PartialEq)]
+struct PowerSample {
+    timestamp: DateTime<Utc>,
+    value: Option<f64>,
+}
+
+impl Sample for PowerSample {
+    type Value = f64;
+
+    fn new(timestamp: DateTime<Utc>, value: Option<f64>) -> Self {
+        Self { timestamp, value }
+    }
+
+    fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.value
+    }
+}
+
+fn main() {
+    let start = DateTime::from_timestamp(1_699_999_800, 0).unwrap();
+    let end = start + TimeDelta::hours(1);
+
+    // one sample per second, without any samples in the window ending at
+    // minute 25
+    let history = (0..3600).filter(|i| !(1201..=1500).contains(i)).map(|i| {
+        let timestamp = start + TimeDelta::seconds(i);
+        PowerSample::new(timestamp, Some(1000.0 + (i % 60) as f64))
+    });
+
+    let mut resampler: Resampler<f64, PowerSample> = Resampler::new(
+        TimeDelta::minutes(5),
+        ResamplingFunction::Average,
+        1,
+        start,
+        false,
+    );
+    resampler.extend(history);
+
+    for sample in resampler.resample(end) {
+        match sample.value() {
+            Some(value) => println!("{}: {:.1} W", sample.timestamp(), value),
+            None => println!("{}: no data", sample.timestamp()),
+        }
+    }
+}
@@ -0,0 +1,63 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! Resamples a CSV export of samples into a CSV of resampled samples, as done
+//! for quick offline analysis.
+
+use chrono::{DateTime, TimeDelta, Utc};
+use frequenz_resampling::{resample_csv, Resampler, ResamplingFunction, Sample, TimestampFormat};
+
+#[derive(Debug, Clone, Default, Copy, PartialEq)]
+struct PowerSample {
+    timestamp: DateTime<Utc>,
+    value: Option<f64>,
+}
+
+impl Sample for PowerSample {
+    type Value = f64;
+
+    fn new(timestamp: DateTime<Utc>, value: Option<f64>) -> Self {
+        Self { timestamp, value }
+    }
+
+    fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.value
+    }
+}
+
+const INPUT: &str = "\
+timestamp,value
+2024-01-01T00:00:01Z,1000
+2024-01-01T00:00:02Z,1010
+2024-01-01T00:00:04Z,
+2024-01-01T00:00:06Z,990
+2024-01-01T00:00:09Z,1005
+2024-01-01T00:00:13Z,1020
+";
+
+fn main() {
+    let start = DateTime::from_timestamp(1_704_067_200, 0).unwrap();
+    let mut resampler: Resampler<f64, PowerSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Average,
+        1,
+        start,
+        false,
+    );
+
+    // any reader and writer work, e.g. files or stdin and stdout
+    let mut output = vec![];
+    resample_csv(
+        &mut resampler,
+        INPUT.as_bytes(),
+        &mut output,
+        &TimestampFormat::Rfc3339,
+    )
+    .unwrap();
+
+    print!("{}", String::from_utf8(output).unwrap());
+}
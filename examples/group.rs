@@ -0,0 +1,69 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! Resamples the samples of many components of a site with one resampler per
+//! component, and drops the resamplers of components that stop sending data.
+
+use chrono::{DateTime, TimeDelta, Utc};
+use frequenz_resampling::{Resampler, ResamplerGroup, ResamplingFunction, Sample};
+
+#[derive(Debug, Clone, Default, Copy, PartialEq)]
+struct PowerSample {
+    timestamp: DateTime<Utc>,
+    value: Option<f64>,
+}
+
+impl Sample for PowerSample {
+    type Value = f64;
+
+    fn new(timestamp: DateTime<Utc>, value: Option<f64>) -> Self {
+        Self { timestamp, value }
+    }
+
+    fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.value
+    }
+}
+
+fn main() {
+    let start = DateTime::from_timestamp(1_704_067_200, 0).unwrap();
+    let interval = TimeDelta::seconds(5);
+
+    // the resampler of a component is created with its first sample
+    let mut group: ResamplerGroup<u64, f64, PowerSample> =
+        ResamplerGroup::new(Box::new(move |_component_id| {
+            Resampler::new(interval, ResamplingFunction::Average, 1, start, false)
+        }))
+        .with_idle_timeout(TimeDelta::seconds(10))
+        .with_expiry_callback(Box::new(|component_id, _resampler| {
+            println!("component {component_id} stopped sending data");
+        }));
+
+    for second in 1..=30 {
+        let timestamp = start + TimeDelta::seconds(second);
+        // the inverter sends data every second, the battery only until
+        // second 12
+        group.push(1, PowerSample::new(timestamp, Some(1000.0)));
+        if second <= 12 {
+            group.push(2, PowerSample::new(timestamp, Some(-500.0)));
+        }
+
+        if second % 5 == 0 {
+            let mut resampled = group.resample(timestamp).into_iter().collect::<Vec<_>>();
+            resampled.sort_by_key(|(component_id, _)| *component_id);
+            for (component_id, samples) in resampled {
+                for sample in samples {
+                    println!(
+                        "component {component_id} at {}: {:?} W",
+                        sample.timestamp(),
+                        sample.value()
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,113 @@
+# License: MIT
+# Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+"""Resamples samples from Python with the Rust resampler and back.
+
+The samples are created in Python, resampled by the Rust backend, partly with a
+Python function called back from Rust, and the results are checked against the
+same resampling done in plain Python. Only the standard library is needed.
+"""
+
+import datetime as dt
+from statistics import mean
+from typing import Optional
+
+from frequenz.resampling import Resampler, ResamplerF64, ResamplingFunction
+
+INTERVAL = dt.timedelta(seconds=5)
+STEP = dt.timedelta(seconds=1)
+START = dt.datetime(2024, 1, 1, tzinfo=dt.timezone.utc)
+
+
+def samples() -> list[tuple[dt.datetime, Optional[float]]]:
+    """Return a minute of samples every second, with some missing values.
+
+    Returns:
+        The `(timestamp, value)` tuples of the samples.
+    """
+    return [
+        (START + i * STEP, None if i % 7 == 0 else float(i % 13)) for i in range(1, 61)
+    ]
+
+
+def expected_averages(
+    data: list[tuple[dt.datetime, Optional[float]]],
+) -> list[tuple[dt.datetime, Optional[float]]]:
+    """Average the samples of every window in plain Python.
+
+    The windows include their end and are labeled with it.
+
+    Args:
+        data: The `(timestamp, value)` tuples of the samples.
+
+    Returns:
+        The averaged samples.
+    """
+    res = []
+    end = START + INTERVAL
+    while end <= data[-1][0]:
+        values = [v for ts, v in data if end - INTERVAL < ts <= end and v is not None]
+        res.append((end, mean(values) if values else None))
+        end += INTERVAL
+    return res
+
+
+def peak_to_peak(window: list[tuple[dt.datetime, Optional[float]]]) -> Optional[float]:
+    """Return the difference between the largest and smallest value of a window.
+
+    Args:
+        window: The `(timestamp, value)` tuples of the samples of the window.
+
+    Returns:
+        The difference, or `None` if the window has no values.
+    """
+    values = [v for _, v in window if v is not None]
+    return max(values) - min(values) if values else None
+
+
+def main() -> None:
+    """Resample the samples with the built-in and a custom resampling function."""
+    data = samples()
+    end = data[-1][0]
+
+    resampler = ResamplerF64(
+        INTERVAL,
+        ResamplingFunction.Average,
+        max_age_in_intervals=1,
+        start=START,
+        first_timestamp=False,
+    )
+    resampler.push_samples(data)
+    averages = resampler.resample(end)
+    expected = expected_averages(data)
+    assert len(averages) == len(expected), (averages, expected)
+    for (ts, value), (expected_ts, expected_value) in zip(averages, expected):
+        assert ts == expected_ts, (ts, expected_ts)
+        assert ts.tzinfo is not None, ts
+        if expected_value is None:
+            assert value is None, (ts, value)
+        else:
+            assert value is not None and abs(value - expected_value) < 1e-9, (
+                ts,
+                value,
+                expected_value,
+            )
+
+    resampler = Resampler(
+        INTERVAL,
+        max_age_in_intervals=1,
+        start=START,
+        first_timestamp=False,
+        custom_function=peak_to_peak,
+    )
+    for ts, value in data:
+        resampler.push_sample(timestamp=ts, value=value)
+    ranges = resampler.resample(end)
+    assert [ts for ts, _ in ranges] == [ts for ts, _ in expected], ranges
+
+    for (ts, average), (_, spread) in zip(averages, ranges):
+        print(f"{ts.isoformat()}  average={average}  range={spread}")
+
+
+if __name__ == "__main__":
+    main()
@@ -4,8 +4,10 @@
 """Frequenz Resampling Python Bindings."""
 
 from ._rust_backend import (  # noqa: F401, F403 # pylint: disable=E0401
+    LabelPosition,
     Resampler,
+    ResamplerF64,
     ResamplingFunction,
 )
 
-__all__ = ["Resampler", "ResamplingFunction"]
+__all__ = ["LabelPosition", "Resampler", "ResamplerF64", "ResamplingFunction"]
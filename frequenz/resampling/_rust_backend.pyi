@@ -1,11 +1,16 @@
 # License: MIT
 # Copyright © 2024 Frequenz Energy-as-a-Service GmbH
 
-__all__ = "Resampler", "ResamplingFunction"
+__all__ = "LabelPosition", "Resampler", "ResamplerF64", "ResamplingFunction"
 
 from datetime import datetime, timedelta
 from enum import Enum, unique
-from typing import Optional
+from typing import TYPE_CHECKING, Any, Callable, Iterable, Optional
+
+if TYPE_CHECKING:
+    import numpy as np
+    import numpy.typing as npt
+    import pandas as pd
 
 @unique
 class ResamplingFunction(Enum):
@@ -49,9 +54,42 @@ class ResamplingFunction(Enum):
             A list of all members of the enum.
         """
 
-class Resampler:
+@unique
+class LabelPosition(Enum):
     """
-    The Resampler class is used to resample a time series of samples.
+    The LabelPosition enum controls which timestamp of its window a resampled
+    sample is labeled with.
+    """
+
+    Left = 0
+    """The start of the window"""
+    Right = 1
+    """The end of the window"""
+    Center = 2
+    """The midpoint of the window"""
+
+    @staticmethod
+    def values() -> list[int]:
+        """
+        Returns a list of all values of the enum.
+
+        Returns:
+            A list of all values of the enum.
+        """
+
+    @staticmethod
+    def members() -> list[tuple[str, int]]:
+        """
+        Returns a list of all members of the enum.
+
+        Returns:
+            A list of all members of the enum.
+        """
+
+class _BaseResampler:
+    """
+    The common base class of the Resampler classes, which are used to resample a
+    time series of samples.
 
     It stores the samples in a buffer and resamples the samples in the buffer when the
     resample method is called.
@@ -61,23 +99,40 @@ class Resampler:
     def __init__(
         self,
         interval: timedelta,
-        resampling_function: ResamplingFunction,
+        resampling_function: Optional[ResamplingFunction] = None,
         *,
         max_age_in_intervals: int,
         start: datetime,
         first_timestamp: bool = True,
+        label_position: Optional[LabelPosition] = None,
+        custom_function: Optional[
+            Callable[[list[tuple[datetime, Optional[float]]]], Optional[float]]
+        ] = None,
     ):
         """
-        Initializes a new Resampler object.
+        Initializes a new resampler.
 
         Args:
             interval: The resampling interval.
-            resampling_function: The resampling function.
+            resampling_function: The resampling function. Defaults to `None`, which
+                requires a custom function.
             max_age_in_intervals: The maximum age of a sample in intervals.
             start: The start time of the resampling.
             first_timestamp: Whether the resampled timestamp should be the first
                 timestamp in the buffer or the last timestamp in the buffer.
                 Defaults to `True`.
+            label_position: The timestamp of its window a resampled sample is
+                labeled with. Which samples belong to a window still follows
+                `first_timestamp`. Defaults to `None`, which labels it with the
+                start of the window if `first_timestamp` is `True` and with its
+                end otherwise.
+            custom_function: A function that resamples the samples of a time step,
+                given as a list of `(timestamp, value)` tuples, instead of the
+                resampling function. Defaults to `None`.
+
+        Raises:
+            ValueError: If neither or both of the resampling function and the
+                custom function are given.
         """
 
     def push_sample(self, *, timestamp: datetime, value: Optional[float]) -> None:
@@ -89,6 +144,49 @@ class Resampler:
             value: The value of the sample.
         """
 
+    def push_samples(
+        self, samples: Iterable[tuple[datetime, Optional[float]]]
+    ) -> None:
+        """
+        Pushes many samples into the resampler buffer at once.
+
+        This is faster than calling `push_sample` for every sample. If any of the
+        samples is invalid, none of them are pushed.
+
+        Args:
+            samples: The `(timestamp, value)` tuples of the samples.
+        """
+
+    def push_array(
+        self,
+        timestamps: npt.ArrayLike,
+        values: npt.ArrayLike,
+    ) -> None:
+        """
+        Pushes the samples of the given arrays into the resampler buffer at once.
+
+        This avoids creating a Python object per sample, e.g. for large backfills.
+
+        Args:
+            timestamps: The timestamps of the samples, either as an integer array in
+                nanoseconds since the Unix epoch, which is cast to `int64`, or as a
+                `datetime64` array of any unit, whose naive timestamps are in UTC.
+            values: The values of the samples, which are cast to `float64`, where NaN
+                values are missing.
+        """
+
+    def push_series(self, series: Any) -> None:
+        """
+        Pushes the samples of the given pandas Series into the resampler buffer at once.
+
+        Args:
+            series: A pandas Series of values indexed by a `DatetimeIndex`, where naive
+                timestamps are in UTC and NaN or NA values are missing.
+
+        Raises:
+            TypeError: If the index of the series is not a `DatetimeIndex`.
+        """
+
     def resample(
         self, end: datetime | None = None
     ) -> list[tuple[datetime, Optional[float]]]:
@@ -101,4 +199,67 @@ class Resampler:
 
         Returns:
             A list of tuples with the resampled samples.
+
+        Raises:
+            Exception: The first exception raised by the custom function since the
+                last call.
         """
+
+    def resample_arrays(
+        self, end: datetime | None = None
+    ) -> tuple[npt.NDArray[np.int64], npt.NDArray[np.float64]]:
+        """
+        Resamples the samples in the buffer until the given end time into arrays.
+
+        Args:
+            end: The end time of the resampling. If `None` the samples in the buffer will be
+                resampled until the current date/time.
+
+        Returns:
+            The timestamps of the resampled samples in nanoseconds since the Unix epoch,
+                and their values, where missing values are NaN.
+
+        Raises:
+            Exception: The first exception raised by the custom function since the
+                last call.
+        """
+
+    def resample_to_pandas(
+        self, end: datetime | None = None
+    ) -> pd.Series | tuple[list[datetime], list[Optional[float]]]:
+        """
+        Resamples the samples in the buffer until the given end time into a pandas Series.
+
+        Args:
+            end: The end time of the resampling. If `None` the samples in the buffer will be
+                resampled until the current date/time.
+
+        Returns:
+            A pandas Series of the resampled values indexed by their timestamps in UTC,
+                where missing values are NaN. If pandas is not installed, a tuple of
+                the list of the timestamps and the list of the values, where missing
+                values are `None`.
+
+        Raises:
+            Exception: The first exception raised by the custom function since the
+                last call.
+        """
+
+class Resampler(_BaseResampler):
+    """
+    The Resampler class is used to resample a time series of samples.
+
+    It stores the samples in a buffer and resamples the samples in the buffer when the
+    resample method is called.
+    A resampler can be configured with a resampling function and a resampling interval.
+    """
+
+class ResamplerF64(_BaseResampler):
+    """
+    The ResamplerF64 class is used to resample a time series of samples with 64-bit
+    precision, e.g. cumulative energy counters that lose precision as 32-bit values.
+
+    It stores the samples in a buffer and resamples the samples in the buffer when the
+    resample method is called.
+    A resampler can be configured with a resampling function and a resampling interval.
+    """
@@ -0,0 +1,110 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! The accumulator module provides the Accumulator struct that holds the
+//! aggregate of the samples of a window, so that the samples don't have to be
+//! buffered for the resampling functions that can be computed incrementally.
+
+use std::fmt::Debug;
+
+use crate::resampler::{ResamplingFunction, Sample};
+use crate::value::ResampleValue;
+
+/// The Accumulator struct holds the aggregate of the samples that were added
+/// to a window so far.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct Accumulator<S> {
+    /// The number of samples, including None values
+    pub(crate) samples: usize,
+    /// The number of samples with a value
+    pub(crate) values: usize,
+    /// The sum, minimum or maximum of the values, depending on the resampling
+    /// function, as a sample with the timestamp of the latest added sample
+    pub(crate) value: Option<S>,
+}
+
+impl<S: Sample> Accumulator<S> {
+    /// Returns whether the given resampling function can be computed from an
+    /// accumulator.
+    pub(crate) fn supports<T>(resampling_function: &ResamplingFunction<T, S>) -> bool
+    where
+        T: ResampleValue,
+        S: Sample<Value = T>,
+    {
+        matches!(
+            resampling_function,
+            ResamplingFunction::Average
+                | ResamplingFunction::Sum
+                | ResamplingFunction::Count
+                | ResamplingFunction::Min
+                | ResamplingFunction::Max
+        )
+    }
+
+    /// Adds the given sample to the aggregate.
+    pub(crate) fn add<T>(&mut self, resampling_function: &mut ResamplingFunction<T, S>, sample: &S)
+    where
+        T: ResampleValue,
+        S: Sample<Value = T>,
+    {
+        self.samples += 1;
+        if sample.value().is_some() {
+            self.values += 1;
+        }
+        self.combine(resampling_function, sample);
+    }
+
+    /// Adds the samples of the given accumulator to the aggregate.
+    pub(crate) fn merge<T>(
+        &mut self,
+        resampling_function: &mut ResamplingFunction<T, S>,
+        other: &Accumulator<S>,
+    ) where
+        T: ResampleValue,
+        S: Sample<Value = T>,
+    {
+        self.samples += other.samples;
+        self.values += other.values;
+        if let Some(value) = &other.value {
+            self.combine(resampling_function, value);
+        }
+    }
+
+    /// Combines the aggregated value with the value of the given sample.
+    fn combine<T>(&mut self, resampling_function: &mut ResamplingFunction<T, S>, sample: &S)
+    where
+        T: ResampleValue,
+        S: Sample<Value = T>,
+    {
+        let mut sum = ResamplingFunction::Sum;
+        let function = match resampling_function {
+            ResamplingFunction::Average => &mut sum,
+            ResamplingFunction::Count => return,
+            function => function,
+        };
+        let value = match &self.value {
+            Some(value) => function.apply(&[value, sample]),
+            None => function.apply(&[sample]),
+        };
+        self.value = Some(S::new(sample.timestamp(), value));
+    }
+
+    /// Returns the result of the resampling function for the added samples.
+    pub(crate) fn result<T>(&self, resampling_function: &ResamplingFunction<T, S>) -> Option<T>
+    where
+        T: ResampleValue,
+        S: Sample<Value = T>,
+    {
+        let count = || T::from_count(self.values).unwrap_or_else(|| T::default());
+        match resampling_function {
+            ResamplingFunction::Average => self
+                .value
+                .as_ref()
+                .and_then(|sum| sum.value())
+                .and_then(|sum| sum.div_count(self.values)),
+            ResamplingFunction::Count => Some(count()),
+            _ => self.value.as_ref().and_then(|value| value.value()),
+        }
+    }
+}
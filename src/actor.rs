@@ -0,0 +1,71 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! The actor module provides a tokio task that resamples a stream of samples
+//! at every interval.
+
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::resampler::{ResampledBatch, Resampler, Sample};
+use crate::value::ResampleValue;
+
+/// Spawns a task that adds the samples received from the given channel to the
+/// resampler, and sends the resampled samples to the given channel whenever a
+/// window ends.
+///
+/// The windows that ended until the time of the clock of the resampler are
+/// resampled, see [`resample_now`][Resampler::resample_now], so that clock
+/// jumps are handled according to its
+/// [`ClockJumpPolicy`][crate::ClockJumpPolicy]. The
+/// time until the next window end is computed from that clock rather than
+/// from a fixed timer, so that the ticks don't drift from it, and missed ticks
+/// are caught up with a single batch. Batches without samples are not sent.
+/// Count-based windows are resampled as soon as they are complete.
+///
+/// The task stops when the sample channel is closed or the receiver of the
+/// batches is dropped, and returns the resampler, e.g. to resample the
+/// remaining samples.
+pub fn spawn_resampler<T, S>(
+    mut resampler: Resampler<T, S>,
+    mut samples: mpsc::Receiver<S>,
+    batches: mpsc::Sender<ResampledBatch<S>>,
+) -> JoinHandle<Resampler<T, S>>
+where
+    T: ResampleValue + Send + 'static,
+    S: Sample<Value = T> + Send + 'static,
+{
+    tokio::spawn(async move {
+        loop {
+            let next_resample_at = resampler.next_resample_at();
+            let delay =
+                next_resample_at.map(|at| (at - resampler.now()).to_std().unwrap_or_default());
+            let tick = async {
+                match delay {
+                    Some(delay) => tokio::time::sleep(delay).await,
+                    None => std::future::pending().await,
+                }
+            };
+            tokio::select! {
+                sample = samples.recv() => match sample {
+                    Some(sample) => resampler.push(sample),
+                    None => break,
+                },
+                () = tick => {}
+            }
+            // the timer may fire before the clock of the resampler reached
+            // the end of the window if they are skewed
+            if next_resample_at.is_some_and(|at| resampler.now() < at) {
+                continue;
+            }
+            let batch = ResampledBatch {
+                fingerprint: resampler.fingerprint(),
+                samples: resampler.resample_completed_now(),
+            };
+            if !batch.samples.is_empty() && batches.send(batch).await.is_err() {
+                break;
+            }
+        }
+        resampler
+    })
+}
@@ -0,0 +1,57 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! The array module provides conversions between ndarray arrays and the
+//! samples of the resampler.
+
+use chrono::{DateTime, Utc};
+use ndarray::{Array1, ArrayView1};
+use num_traits::ToPrimitive;
+
+use crate::error::ResampleError;
+use crate::resampler::{Resampler, Sample};
+use crate::value::ResampleValue;
+
+impl<T: ResampleValue + ToPrimitive, S: Sample<Value = T>> Resampler<T, S> {
+    /// Adds the samples of the given arrays of timestamps in nanoseconds since
+    /// the Unix epoch and of values like [`extend`][Extend::extend]. NaN
+    /// values are added as samples without a value. Returns an error if the
+    /// arrays have different lengths.
+    pub fn extend_from_ndarray(
+        &mut self,
+        timestamps: ArrayView1<i64>,
+        values: ArrayView1<f64>,
+    ) -> Result<(), ResampleError> {
+        if timestamps.len() != values.len() {
+            return Err(ResampleError::LengthMismatch {
+                timestamps: timestamps.len(),
+                values: values.len(),
+            });
+        }
+        for (&timestamp, &value) in timestamps.iter().zip(values.iter()) {
+            let value = Some(value).filter(|v| !v.is_nan());
+            self.push(S::new(
+                DateTime::from_timestamp_nanos(timestamp),
+                value.and_then(T::from_scalar),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Resamples the samples in the buffer like [`resample`][Self::resample],
+    /// and returns the timestamps of the resampled samples in nanoseconds
+    /// since the Unix epoch and their values, with NaN for samples without a
+    /// value.
+    pub fn resample_ndarray(&mut self, end: DateTime<Utc>) -> (Array1<i64>, Array1<f64>) {
+        let samples = self.resample(end);
+        let timestamps = samples
+            .iter()
+            .map(|s| s.timestamp().timestamp_nanos_opt().unwrap_or(i64::MAX))
+            .collect();
+        let values = samples
+            .iter()
+            .map(|s| s.value().and_then(|v| v.to_f64()).unwrap_or(f64::NAN))
+            .collect();
+        (timestamps, values)
+    }
+}
@@ -0,0 +1,41 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! The arrow module provides the ingestion of samples from Arrow arrays.
+
+use arrow_array::{Float64Array, TimestampNanosecondArray};
+use chrono::DateTime;
+
+use crate::error::ResampleError;
+use crate::resampler::{Resampler, Sample};
+use crate::value::ResampleValue;
+
+impl<T: ResampleValue, S: Sample<Value = T>> Resampler<T, S> {
+    /// Adds the samples of the given Arrow arrays like
+    /// [`extend`][Extend::extend], e.g. for batches from Parquet scans,
+    /// without collecting them into samples first. The timestamps are
+    /// nanoseconds since the Unix epoch in UTC, regardless of the timezone of
+    /// the array. Null values are added as samples without a value, and rows
+    /// with a null timestamp are skipped. Returns an error if the arrays have
+    /// different lengths.
+    pub fn extend_from_arrow(
+        &mut self,
+        timestamps: &TimestampNanosecondArray,
+        values: &Float64Array,
+    ) -> Result<(), ResampleError> {
+        if timestamps.len() != values.len() {
+            return Err(ResampleError::LengthMismatch {
+                timestamps: timestamps.len(),
+                values: values.len(),
+            });
+        }
+        for (timestamp, value) in timestamps.iter().zip(values.iter()) {
+            let Some(timestamp) = timestamp else {
+                continue;
+            };
+            let timestamp = DateTime::from_timestamp_nanos(timestamp);
+            self.push(S::new(timestamp, value.and_then(T::from_scalar)));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! The boolean module provides the BoolResampler struct that resamples
+//! samples with boolean values, e.g. of relay states or availability
+//! channels.
+
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+use crate::resampler::{epoch_align, Sample};
+
+/// The BoolAggregation enum represents the aggregations of boolean samples.
+///
+/// The value of a sample holds until the next sample, so a window also
+/// covers the value of the latest sample before it. Samples without a value
+/// mark the value as unknown until the next sample, and unknown parts of a
+/// window are ignored.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BoolAggregation {
+    /// Whether the value was true at any time in the window, as `1.0` or
+    /// `0.0`
+    #[default]
+    Any,
+    /// Whether the value was true for the whole window, as `1.0` or `0.0`
+    All,
+    /// The fraction of the window the value was true
+    DutyCycle,
+}
+
+/// The BoolResampler struct resamples samples with boolean values into
+/// samples with the [aggregation][BoolAggregation] of every window as `f64`.
+/// The resampled samples are labeled with the start of their window, and
+/// windows before the first sample have no value.
+#[derive(Debug, Clone)]
+pub struct BoolResampler<S: Sample<Value = bool>, R: Sample<Value = f64>> {
+    interval: TimeDelta,
+    aggregation: BoolAggregation,
+    /// The start of the next window to resample
+    window_start: DateTime<Utc>,
+    /// The samples of the windows that are not resampled yet, sorted by
+    /// timestamp
+    buffer: VecDeque<S>,
+    /// The value at the start of the next window, i.e. the value of the
+    /// latest sample before it
+    state: Option<bool>,
+    output: PhantomData<R>,
+}
+
+impl<S: Sample<Value = bool>, R: Sample<Value = f64>> BoolResampler<S, R> {
+    /// Creates a new BoolResampler with the given interval and aggregation,
+    /// starting at the given timestamp aligned to the interval.
+    pub fn new(interval: TimeDelta, aggregation: BoolAggregation, start: DateTime<Utc>) -> Self {
+        Self {
+            interval,
+            aggregation,
+            window_start: epoch_align(interval, start, None),
+            buffer: VecDeque::new(),
+            state: None,
+            output: PhantomData,
+        }
+    }
+
+    /// Adds a sample to the buffer. Samples before the start of the next
+    /// window are dropped.
+    pub fn push(&mut self, sample: S) {
+        if sample.timestamp() < self.window_start {
+            return;
+        }
+        let index = self
+            .buffer
+            .partition_point(|s| s.timestamp() <= sample.timestamp());
+        self.buffer.insert(index, sample);
+    }
+
+    /// Resamples all windows that end at or before the given end time, and
+    /// returns the resampled samples.
+    pub fn resample(&mut self, end: DateTime<Utc>) -> Vec<R> {
+        let mut res = vec![];
+        while self.window_start + self.interval <= end {
+            let window_end = self.window_start + self.interval;
+            let split = self.buffer.partition_point(|s| s.timestamp() < window_end);
+            let samples = self.buffer.drain(..split).collect::<Vec<_>>();
+
+            let (mut known, mut on) = (TimeDelta::zero(), TimeDelta::zero());
+            let mut any = self.state == Some(true);
+            let mut from = self.window_start;
+            for sample in &samples {
+                self.add_span(&mut known, &mut on, sample.timestamp() - from);
+                any |= sample.value() == Some(true);
+                self.state = sample.value();
+                from = sample.timestamp();
+            }
+            self.add_span(&mut known, &mut on, window_end - from);
+
+            let value = (known > TimeDelta::zero()).then(|| match self.aggregation {
+                BoolAggregation::Any => f64::from(u8::from(any)),
+                BoolAggregation::All => f64::from(u8::from(on == known)),
+                BoolAggregation::DutyCycle => on.as_seconds_f64() / known.as_seconds_f64(),
+            });
+            res.push(R::new(self.window_start, value));
+            self.window_start = window_end;
+        }
+        res
+    }
+
+    /// Adds the given duration with the current state to the known and true
+    /// durations of a window.
+    fn add_span(&self, known: &mut TimeDelta, on: &mut TimeDelta, duration: TimeDelta) {
+        match self.state {
+            Some(true) => {
+                *known += duration;
+                *on += duration;
+            }
+            Some(false) => *known += duration,
+            None => {}
+        }
+    }
+
+    /// Returns the start of the next window to resample.
+    pub fn window_start(&self) -> DateTime<Utc> {
+        self.window_start
+    }
+}
+
+impl<S: Sample<Value = bool>, R: Sample<Value = f64>> Extend<S> for BoolResampler<S, R> {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, iter: I) {
+        for sample in iter {
+            self.push(sample);
+        }
+    }
+}
@@ -0,0 +1,151 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! The bounded module provides the BoundedResampler struct that propagates
+//! backpressure to the producers of samples when the buffer is full.
+
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use chrono::{DateTime, Utc};
+use tokio::sync::Notify;
+
+use crate::error::ResampleError;
+use crate::resampler::{Resampler, Sample};
+use crate::value::ResampleValue;
+
+/// The BoundedResampler struct shares a [`Resampler`] between the tasks that
+/// produce its samples and the task that resamples them, and limits the
+/// number of buffered samples. Adding a sample to a full buffer waits until
+/// the buffer is pruned by resampling, or returns an error, so that overload
+/// becomes visible to the producers instead of growing the buffer. Samples
+/// that were added to the aggregates of the incremental aggregation count as
+/// buffered too, see [`Resampler::pending_len`].
+///
+/// Clones share the same resampler.
+#[derive(Debug)]
+pub struct BoundedResampler<T, S>
+where
+    T: ResampleValue,
+    S: Sample<Value = T>,
+{
+    shared: Arc<Shared<T, S>>,
+}
+
+#[derive(Debug)]
+struct Shared<T, S>
+where
+    T: ResampleValue,
+    S: Sample<Value = T>,
+{
+    resampler: Mutex<Resampler<T, S>>,
+    /// The maximum number of buffered samples
+    capacity: usize,
+    /// Notified whenever the buffer was pruned
+    pruned: Notify,
+}
+
+impl<T, S> Clone for BoundedResampler<T, S>
+where
+    T: ResampleValue,
+    S: Sample<Value = T>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T, S> BoundedResampler<T, S>
+where
+    T: ResampleValue,
+    S: Sample<Value = T>,
+{
+    /// Creates a wrapper that buffers at most `capacity` samples in the given
+    /// resampler.
+    pub fn new(resampler: Resampler<T, S>, capacity: usize) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                resampler: Mutex::new(resampler),
+                capacity,
+                pruned: Notify::new(),
+            }),
+        }
+    }
+
+    /// Adds a sample to the resampler, and waits for the buffer to be pruned
+    /// first if it is full. Returns an error if the sample is rejected, see
+    /// [`Resampler::try_push`].
+    pub async fn push(&self, sample: S) -> Result<(), ResampleError> {
+        loop {
+            let pruned = self.shared.pruned.notified();
+            tokio::pin!(pruned);
+            // register for notifications before checking the buffer, so that
+            // a pruning in between isn't missed
+            pruned.as_mut().enable();
+            {
+                let mut resampler = self.lock();
+                if resampler.pending_len() < self.shared.capacity {
+                    return resampler.try_push(sample);
+                }
+            }
+            pruned.await;
+        }
+    }
+
+    /// Adds a sample to the resampler, or returns a
+    /// [`BufferFull`][ResampleError::BufferFull] error without waiting if the
+    /// buffer is full.
+    pub fn try_push(&self, sample: S) -> Result<(), ResampleError> {
+        let mut resampler = self.lock();
+        if resampler.pending_len() >= self.shared.capacity {
+            return Err(ResampleError::BufferFull {
+                capacity: self.shared.capacity,
+            });
+        }
+        resampler.try_push(sample)
+    }
+
+    /// Resamples the samples in the buffer until the given end time, see
+    /// [`Resampler::resample`], and wakes up the producers that wait for the
+    /// buffer to be pruned.
+    pub fn resample(&self, end: DateTime<Utc>) -> Vec<S> {
+        let res = self.lock().resample(end);
+        self.shared.pruned.notify_waiters();
+        res
+    }
+
+    /// Resamples the samples in the buffer until now like
+    /// [`resample`][Self::resample], see [`Resampler::resample_now`].
+    pub fn resample_now(&self) -> Vec<S> {
+        let res = self.lock().resample_now();
+        self.shared.pruned.notify_waiters();
+        res
+    }
+
+    /// Returns the number of samples in the buffer.
+    pub fn buffer_len(&self) -> usize {
+        self.lock().buffer_len()
+    }
+
+    /// Returns the number of samples that are waiting to be resampled, see
+    /// [`Resampler::pending_len`].
+    pub fn pending_len(&self) -> usize {
+        self.lock().pending_len()
+    }
+
+    /// Returns the maximum number of buffered samples.
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity
+    }
+
+    /// Locks the resampler, e.g. to inspect or configure it.
+    pub fn lock(&self) -> MutexGuard<'_, Resampler<T, S>> {
+        // the resampler stays consistent if a holder of the lock panicked
+        self.shared
+            .resampler
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+}
@@ -0,0 +1,105 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! The builder module provides the ResamplerBuilder struct that is used to
+//! create a resampler with named options.
+
+use std::fmt::Debug;
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+use crate::error::ResampleError;
+use crate::resampler::{Resampler, ResamplingFunction, Sample};
+use crate::value::ResampleValue;
+
+/// The ResamplerBuilder struct creates a [`Resampler`] from named options, see
+/// [`Resampler::builder`]. Further options can be set on the built resampler
+/// with its `with_*` methods.
+#[derive(Debug)]
+pub struct ResamplerBuilder<T: ResampleValue, S: Sample<Value = T>> {
+    interval: TimeDelta,
+    resampling_function: ResamplingFunction<T, S>,
+    max_age_in_intervals: i32,
+    start: Option<DateTime<Utc>>,
+    first_timestamp: bool,
+    alignment_timestamp: Option<DateTime<Utc>>,
+}
+
+impl<T: ResampleValue, S: Sample<Value = T>> Default for ResamplerBuilder<T, S> {
+    fn default() -> Self {
+        Self {
+            interval: TimeDelta::zero(),
+            resampling_function: ResamplingFunction::default(),
+            max_age_in_intervals: 1,
+            start: None,
+            first_timestamp: true,
+            alignment_timestamp: None,
+        }
+    }
+}
+
+impl<T: ResampleValue, S: Sample<Value = T>> ResamplerBuilder<T, S> {
+    /// Sets the resampling interval. It is required.
+    pub fn interval(mut self, interval: TimeDelta) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Sets the resampling function. Defaults to
+    /// [`Average`][ResamplingFunction::Average].
+    pub fn function(mut self, resampling_function: ResamplingFunction<T, S>) -> Self {
+        self.resampling_function = resampling_function;
+        self
+    }
+
+    /// Sets the maximum age of the samples that are resampled, in intervals.
+    /// Defaults to 1.
+    pub fn max_age(mut self, max_age_in_intervals: i32) -> Self {
+        self.max_age_in_intervals = max_age_in_intervals;
+        self
+    }
+
+    /// Sets the start of the resampling, which is aligned to the interval.
+    /// Defaults to the time the resampler is built.
+    pub fn start(mut self, start: DateTime<Utc>) -> Self {
+        self.start = Some(start);
+        self
+    }
+
+    /// Sets whether the resampled samples are labeled with the start of their
+    /// interval, and the intervals include their start rather than their
+    /// end. Defaults to `true`.
+    pub fn first_timestamp(mut self, first_timestamp: bool) -> Self {
+        self.first_timestamp = first_timestamp;
+        self
+    }
+
+    /// Aligns the intervals to the given timestamp instead of the Unix epoch,
+    /// see [`with_alignment_timestamp`][Resampler::with_alignment_timestamp].
+    pub fn align_to(mut self, alignment_timestamp: DateTime<Utc>) -> Self {
+        self.alignment_timestamp = Some(alignment_timestamp);
+        self
+    }
+
+    /// Creates the resampler, or returns an error if the options are invalid,
+    /// see [`try_new`][Resampler::try_new].
+    pub fn build(self) -> Result<Resampler<T, S>, ResampleError> {
+        let start = self.start.unwrap_or_else(Utc::now);
+        let resampler = Resampler::try_new(
+            self.interval,
+            self.resampling_function,
+            self.max_age_in_intervals,
+            start,
+            self.first_timestamp,
+        )?;
+        Ok(match self.alignment_timestamp {
+            Some(alignment_timestamp) => {
+                let mut resampler = resampler.with_alignment_timestamp(alignment_timestamp);
+                // align the given start rather than the one aligned to the epoch
+                resampler.reset(start);
+                resampler
+            }
+            None => resampler,
+        })
+    }
+}
@@ -0,0 +1,55 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! The conversion module provides the unit conversions that can be applied to
+//! the values of the samples as they are added to a resampler and as they are
+//! emitted, to normalize data from heterogeneous devices in one place.
+
+/// The UnitConversion struct represents a linear conversion of values from
+/// one unit to another, i.e. `value * factor + offset`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnitConversion {
+    factor: f64,
+    offset: f64,
+}
+
+impl UnitConversion {
+    /// Converts from a unit to its kilo unit, e.g. from W to kW.
+    pub const TO_KILO: Self = Self::scale(1e-3);
+    /// Converts from a kilo unit to its base unit, e.g. from kW to W.
+    pub const FROM_KILO: Self = Self::scale(1e3);
+    /// Converts from a unit to its mega unit, e.g. from Wh to MWh.
+    pub const TO_MEGA: Self = Self::scale(1e-6);
+    /// Converts from a mega unit to its base unit, e.g. from MWh to Wh.
+    pub const FROM_MEGA: Self = Self::scale(1e6);
+
+    /// Creates a conversion that multiplies the values by the given factor.
+    pub const fn scale(factor: f64) -> Self {
+        Self::linear(factor, 0.0)
+    }
+
+    /// Creates a conversion that multiplies the values by the given factor
+    /// and adds the given offset, e.g. to convert between °C and °F.
+    pub const fn linear(factor: f64, offset: f64) -> Self {
+        Self { factor, offset }
+    }
+
+    /// Returns a conversion that applies this conversion and then the given
+    /// one.
+    pub fn then(&self, next: UnitConversion) -> Self {
+        Self::linear(
+            self.factor * next.factor,
+            self.offset * next.factor + next.offset,
+        )
+    }
+
+    /// Returns the conversion that reverts this conversion.
+    pub fn inverse(&self) -> Self {
+        Self::linear(1.0 / self.factor, -self.offset / self.factor)
+    }
+
+    /// Converts the given value.
+    pub fn convert(&self, value: f64) -> f64 {
+        value * self.factor + self.offset
+    }
+}
@@ -0,0 +1,184 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! The csv_io module provides the reading and writing of samples as CSV, e.g.
+//! for quick offline analysis.
+
+use std::fmt::{Debug, Display};
+use std::io::{Read, Write};
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use num_traits::ToPrimitive;
+
+use crate::resampler::{Resampler, Sample};
+use crate::value::ResampleValue;
+
+/// The TimestampFormat enum represents the formats of the timestamps in CSV.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum TimestampFormat {
+    /// RFC 3339 timestamps, e.g. `2024-01-01T00:00:00Z`
+    #[default]
+    Rfc3339,
+    /// Seconds since the Unix epoch, with an optional fraction
+    UnixSeconds,
+    /// Milliseconds since the Unix epoch
+    UnixMillis,
+    /// Timestamps in the given [`chrono` format][chrono::format::strftime],
+    /// which are in UTC unless the format contains an offset
+    Custom(String),
+}
+
+impl TimestampFormat {
+    /// Parses the given timestamp.
+    fn parse(&self, timestamp: &str) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Rfc3339 => DateTime::parse_from_rfc3339(timestamp)
+                .ok()
+                .map(|t| t.to_utc()),
+            Self::UnixSeconds => {
+                let seconds = timestamp.parse::<f64>().ok()?;
+                DateTime::from_timestamp_micros((seconds * 1e6).round() as i64)
+            }
+            Self::UnixMillis => DateTime::from_timestamp_millis(timestamp.parse().ok()?),
+            Self::Custom(format) => DateTime::parse_from_str(timestamp, format)
+                .map(|t| t.to_utc())
+                .or_else(|_| NaiveDateTime::parse_from_str(timestamp, format).map(|t| t.and_utc()))
+                .ok(),
+        }
+    }
+
+    /// Formats the given timestamp.
+    fn format(&self, timestamp: DateTime<Utc>) -> String {
+        match self {
+            Self::Rfc3339 => timestamp.to_rfc3339(),
+            Self::UnixSeconds => match timestamp.timestamp_subsec_micros() {
+                0 => timestamp.timestamp().to_string(),
+                _ => (timestamp.timestamp_micros() as f64 / 1e6).to_string(),
+            },
+            Self::UnixMillis => timestamp.timestamp_millis().to_string(),
+            Self::Custom(format) => timestamp.format(format).to_string(),
+        }
+    }
+}
+
+/// The CsvError enum represents the errors that can occur when reading or
+/// writing CSV.
+#[derive(Debug)]
+pub enum CsvError {
+    /// The CSV could not be read or written.
+    Csv(csv::Error),
+    /// A timestamp does not match the timestamp format.
+    InvalidTimestamp(String),
+    /// A value is not a number.
+    InvalidValue(String),
+}
+
+impl Display for CsvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Csv(e) => write!(f, "{e}"),
+            Self::InvalidTimestamp(timestamp) => write!(f, "invalid timestamp {timestamp:?}"),
+            Self::InvalidValue(value) => write!(f, "invalid value {value:?}"),
+        }
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+impl From<csv::Error> for CsvError {
+    fn from(e: csv::Error) -> Self {
+        Self::Csv(e)
+    }
+}
+
+impl<T: ResampleValue + ToPrimitive, S: Sample<Value = T>> Resampler<T, S> {
+    /// Adds the samples of the given CSV with a header row and the columns
+    /// `timestamp` and `value` like [`extend`][Extend::extend]. Empty values
+    /// are added as samples without a value. Returns the timestamp of the
+    /// latest sample, if any.
+    pub fn extend_from_csv<R: Read>(
+        &mut self,
+        reader: R,
+        format: &TimestampFormat,
+    ) -> Result<Option<DateTime<Utc>>, CsvError> {
+        let mut latest = None;
+        for record in csv::Reader::from_reader(reader).records() {
+            let record = record?;
+            let (timestamp, value) = (record.get(0).unwrap_or(""), record.get(1).unwrap_or(""));
+            let timestamp = format
+                .parse(timestamp.trim())
+                .ok_or_else(|| CsvError::InvalidTimestamp(timestamp.to_string()))?;
+            let value = match value.trim() {
+                "" => None,
+                value => Some(
+                    value
+                        .parse::<f64>()
+                        .ok()
+                        .and_then(T::from_scalar)
+                        .ok_or_else(|| CsvError::InvalidValue(value.to_string()))?,
+                ),
+            };
+            latest = latest.max(Some(timestamp));
+            self.push(S::new(timestamp, value));
+        }
+        Ok(latest)
+    }
+
+    /// Resamples the samples in the buffer like [`resample`][Self::resample],
+    /// and writes the resampled samples as CSV with a header row and the
+    /// columns `timestamp` and `value`.
+    pub fn resample_to_csv<W: Write>(
+        &mut self,
+        end: DateTime<Utc>,
+        writer: W,
+        format: &TimestampFormat,
+    ) -> Result<(), CsvError> {
+        let samples = self.resample(end);
+        write_csv(&samples, writer, format)
+    }
+}
+
+/// Writes the given samples as CSV with a header row and the columns
+/// `timestamp` and `value`.
+fn write_csv<W, T, S>(samples: &[S], writer: W, format: &TimestampFormat) -> Result<(), CsvError>
+where
+    W: Write,
+    T: ToPrimitive,
+    S: Sample<Value = T>,
+{
+    let mut writer = csv::Writer::from_writer(writer);
+    writer.write_record(["timestamp", "value"])?;
+    for sample in samples {
+        let value = sample
+            .value()
+            .and_then(|v| v.to_f64())
+            .map(|v| v.to_string())
+            .unwrap_or_default();
+        writer.write_record([format.format(sample.timestamp()), value])?;
+    }
+    writer.flush().map_err(csv::Error::from)?;
+    Ok(())
+}
+
+/// Reads samples from the given CSV, resamples them with the given resampler
+/// up to the window of the latest sample, and writes the resampled samples as
+/// CSV, see [`Resampler::extend_from_csv`] and [`Resampler::resample_to_csv`].
+pub fn resample_csv<R, W, T, S>(
+    resampler: &mut Resampler<T, S>,
+    reader: R,
+    writer: W,
+    format: &TimestampFormat,
+) -> Result<(), CsvError>
+where
+    R: Read,
+    W: Write,
+    T: ResampleValue + ToPrimitive,
+    S: Sample<Value = T>,
+{
+    let mut samples = vec![];
+    if let Some(latest) = resampler.extend_from_csv(reader, format)? {
+        samples = resampler.resample_completed(latest);
+        samples.extend(resampler.resample(resampler.next_window_end()));
+    }
+    write_csv(&samples, writer, format)
+}
@@ -0,0 +1,82 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! The dataframe module provides conversions between polars series and data
+//! frames and the samples of the resampler.
+
+use chrono::{DateTime, Utc};
+use num_traits::ToPrimitive;
+use polars::prelude::*;
+
+use crate::resampler::{Resampler, Sample};
+use crate::value::ResampleValue;
+
+impl<T: ResampleValue, S: Sample<Value = T>> Resampler<T, S> {
+    /// Adds the samples of the given series of timestamps and values like
+    /// [`extend`][Extend::extend]. The timestamps are cast to datetimes,
+    /// which are in UTC if they have no timezone, and the values to floats.
+    /// Null values are added as samples without a value, and rows with a null
+    /// timestamp are skipped.
+    pub fn extend_from_series(&mut self, timestamps: &Series, values: &Series) -> PolarsResult<()> {
+        polars_ensure!(
+            timestamps.len() == values.len(),
+            ShapeMismatch: "{} timestamps don't match {} values",
+            timestamps.len(),
+            values.len()
+        );
+        let timezone = match timestamps.dtype() {
+            DataType::Datetime(_, timezone) => timezone.clone(),
+            _ => None,
+        };
+        let timestamps = timestamps.cast(&DataType::Datetime(TimeUnit::Nanoseconds, timezone))?;
+        let values = values.cast(&DataType::Float64)?;
+        let rows = timestamps
+            .datetime()?
+            .physical()
+            .iter()
+            .zip(values.f64()?.iter());
+        for (timestamp, value) in rows {
+            let Some(timestamp) = timestamp else {
+                continue;
+            };
+            let timestamp = DateTime::from_timestamp_nanos(timestamp);
+            self.push(S::new(timestamp, value.and_then(T::from_scalar)));
+        }
+        Ok(())
+    }
+
+    /// Adds the samples of the given columns of a data frame, see
+    /// [`extend_from_series`][Self::extend_from_series].
+    pub fn extend_from_dataframe(
+        &mut self,
+        df: &DataFrame,
+        timestamp_column: &str,
+        value_column: &str,
+    ) -> PolarsResult<()> {
+        self.extend_from_series(
+            df.column(timestamp_column)?.as_materialized_series(),
+            df.column(value_column)?.as_materialized_series(),
+        )
+    }
+}
+
+impl<T: ResampleValue + ToPrimitive, S: Sample<Value = T>> Resampler<T, S> {
+    /// Resamples the samples in the buffer like [`resample`][Self::resample],
+    /// and returns the resampled samples as a data frame with a `timestamp`
+    /// column of datetimes in UTC and a `value` column of floats.
+    pub fn resample_dataframe(&mut self, end: DateTime<Utc>) -> PolarsResult<DataFrame> {
+        let samples = self.resample(end);
+        let timestamps = Int64Chunked::from_iter_values(
+            "timestamp".into(),
+            samples
+                .iter()
+                .map(|s| s.timestamp().timestamp_nanos_opt().unwrap_or(i64::MAX)),
+        )
+        .into_datetime(TimeUnit::Nanoseconds, Some(TimeZone::UTC));
+        let values = Float64Chunked::from_iter_options(
+            "value".into(),
+            samples.iter().map(|s| s.value().and_then(|v| v.to_f64())),
+        );
+        DataFrame::new(vec![timestamps.into_column(), values.into_column()])
+    }
+}
@@ -0,0 +1,115 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! The error module provides the errors that can be returned by the resampler.
+
+use std::fmt::Display;
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+use crate::group::Quota;
+
+/// The ResampleError enum represents the errors that can occur when
+/// resampling.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResampleError {
+    /// The resampling interval is not positive, or shorter than a millisecond.
+    InvalidInterval(TimeDelta),
+    /// The maximum age of the samples in intervals is negative.
+    InvalidMaxAge(i32),
+    /// The resampling was requested until an `end` that is not after the
+    /// `start` of the next interval.
+    EndNotAfterStart {
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    },
+    /// A sample was added in strict ordering mode whose `timestamp` is not
+    /// after the timestamp of the `previous` sample.
+    OutOfOrder {
+        previous: DateTime<Utc>,
+        timestamp: DateTime<Utc>,
+    },
+    /// The clock of the resampler went backwards, from `previous` to `now`.
+    ClockJump {
+        previous: DateTime<Utc>,
+        now: DateTime<Utc>,
+    },
+    /// Two resamplers with different configurations were merged, see
+    /// [`Resampler::fingerprint`][crate::Resampler::fingerprint].
+    IncompatibleConfiguration { fingerprint: u64, other: u64 },
+    /// A sample was added to a bounded resampler whose buffer holds the
+    /// maximum number of samples, see `BoundedResampler`.
+    BufferFull { capacity: usize },
+    /// Columns of `timestamps` and `values` of different lengths were added.
+    LengthMismatch { timestamps: usize, values: usize },
+    /// A sample with a NaN value was added with the `Error` NaN policy, see
+    /// [`NanPolicy`][crate::NanPolicy].
+    NotANumber { timestamp: DateTime<Utc> },
+    /// A sample was added to a resampler group that reached the `limit` of a
+    /// `quota`, see [`ResamplerGroup`][crate::ResamplerGroup].
+    QuotaExceeded { quota: Quota, limit: usize },
+    /// A state of the given `version` was restored, but only states up to the
+    /// `supported` version can be, see
+    /// [`Resampler::try_restore`][crate::Resampler::try_restore].
+    UnsupportedStateVersion { version: u32, supported: u32 },
+}
+
+impl Display for ResampleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidInterval(interval) => {
+                write!(
+                    f,
+                    "invalid resampling interval {interval}, must be at least 1ms"
+                )
+            }
+            Self::InvalidMaxAge(max_age) => {
+                write!(
+                    f,
+                    "invalid max age of {max_age} intervals, must not be negative"
+                )
+            }
+            Self::EndNotAfterStart { start, end } => {
+                write!(f, "end time {end} is not after start time {start}")
+            }
+            Self::OutOfOrder {
+                previous,
+                timestamp,
+            } => {
+                write!(
+                    f,
+                    "sample at {timestamp} is not after the previous sample at {previous}"
+                )
+            }
+            Self::ClockJump { previous, now } => {
+                write!(f, "clock jumped backwards from {previous} to {now}")
+            }
+            Self::IncompatibleConfiguration { fingerprint, other } => {
+                write!(
+                    f,
+                    "cannot merge resampler with configuration {other:#x} into one with configuration {fingerprint:#x}"
+                )
+            }
+            Self::BufferFull { capacity } => {
+                write!(f, "buffer is full with {capacity} samples")
+            }
+            Self::LengthMismatch { timestamps, values } => {
+                write!(f, "{timestamps} timestamps don't match {values} values")
+            }
+            Self::NotANumber { timestamp } => {
+                write!(f, "sample at {timestamp} has a NaN value")
+            }
+            Self::QuotaExceeded { quota, limit } => {
+                write!(f, "{quota} quota of {limit} exceeded")
+            }
+            Self::UnsupportedStateVersion { version, supported } => {
+                write!(
+                    f,
+                    "unsupported state version {version}, only versions up to {supported} are supported"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ResampleError {}
@@ -0,0 +1,156 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! The event module provides the Event sample and the EventResampler struct
+//! that are used to resample event streams, i.e. time series that only
+//! consist of timestamps.
+
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+use crate::resampler::{epoch_align, EmptyCount, Sample};
+
+/// The Event struct represents an occurrence of an event in an event stream.
+///
+/// Events only have a timestamp, so their value is the unit type. They are
+/// counted per interval by an [`EventResampler`].
+#[derive(Debug, Clone, Default, Copy, PartialEq, Eq)]
+pub struct Event {
+    timestamp: DateTime<Utc>,
+}
+
+impl Event {
+    /// Creates an event that occurred at the given timestamp.
+    pub fn at(timestamp: DateTime<Utc>) -> Self {
+        Self { timestamp }
+    }
+}
+
+impl Sample for Event {
+    type Value = ();
+
+    fn new(timestamp: DateTime<Utc>, _value: Option<()>) -> Self {
+        Self { timestamp }
+    }
+
+    fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    fn value(&self) -> Option<()> {
+        Some(())
+    }
+}
+
+/// The EventAggregation enum defines how the events of a window are
+/// aggregated by an [`EventResampler`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EventAggregation {
+    /// The number of events of the window.
+    #[default]
+    Count,
+    /// The number of events of the window per second, i.e. the count divided
+    /// by the duration of the window in seconds.
+    Rate,
+}
+
+/// The EventResampler struct resamples samples without a value, like
+/// [`Event`]s, into samples with the number of events of every window as
+/// `f64`. The resampled samples are labeled with the start of their window.
+///
+/// As events have no value, they can only be counted, or be turned into a
+/// rate with [`EventAggregation::Rate`]. Windows without events are counted
+/// as `0.0`, or have no value if the empty count is set to
+/// [`None`][EmptyCount::None].
+///
+/// Unlike [`Resampler::resample`][crate::Resampler::resample], which also
+/// emits the window that is still open at the given end time,
+/// [`resample`][Self::resample] only emits complete windows, as the count of
+/// an open window would be too low and can't be corrected afterwards.
+#[derive(Debug, Clone)]
+pub struct EventResampler<S: Sample<Value = ()>, R: Sample<Value = f64>> {
+    interval: TimeDelta,
+    aggregation: EventAggregation,
+    empty_count: EmptyCount,
+    /// The start of the next window to resample
+    window_start: DateTime<Utc>,
+    /// The number of events of the windows that are not resampled yet, by the
+    /// start of their window
+    counts: BTreeMap<DateTime<Utc>, usize>,
+    input: PhantomData<S>,
+    output: PhantomData<R>,
+}
+
+impl<S: Sample<Value = ()>, R: Sample<Value = f64>> EventResampler<S, R> {
+    /// Creates a new EventResampler with the given interval, starting at the
+    /// given timestamp aligned to the interval.
+    pub fn new(interval: TimeDelta, start: DateTime<Utc>) -> Self {
+        Self {
+            interval,
+            aggregation: EventAggregation::default(),
+            empty_count: EmptyCount::default(),
+            window_start: epoch_align(interval, start, None),
+            counts: BTreeMap::new(),
+            input: PhantomData,
+            output: PhantomData,
+        }
+    }
+
+    /// Sets how the events of a window are aggregated, see
+    /// [`EventAggregation`].
+    pub fn with_aggregation(mut self, aggregation: EventAggregation) -> Self {
+        self.aggregation = aggregation;
+        self
+    }
+
+    /// Sets the result of windows without events.
+    pub fn with_empty_count(mut self, empty_count: EmptyCount) -> Self {
+        self.empty_count = empty_count;
+        self
+    }
+
+    /// Counts an event in its window. Events before the start of the next
+    /// window are dropped.
+    pub fn push(&mut self, event: S) {
+        if event.timestamp() < self.window_start {
+            return;
+        }
+        let window_start = epoch_align(self.interval, event.timestamp(), Some(self.window_start));
+        *self.counts.entry(window_start).or_default() += 1;
+    }
+
+    /// Resamples all windows that end at or before the given end time, and
+    /// returns the resampled samples. The window that is still open at the
+    /// end time is left for a later call.
+    pub fn resample(&mut self, end: DateTime<Utc>) -> Vec<R> {
+        let mut res = vec![];
+        while self.window_start + self.interval <= end {
+            let count = self.counts.remove(&self.window_start).unwrap_or(0);
+            let value = match (count, self.empty_count) {
+                (0, EmptyCount::None) => None,
+                _ => Some(match self.aggregation {
+                    EventAggregation::Count => count as f64,
+                    EventAggregation::Rate => count as f64 / self.interval.as_seconds_f64(),
+                }),
+            };
+            res.push(R::new(self.window_start, value));
+            self.window_start += self.interval;
+        }
+        res
+    }
+
+    /// Returns the start of the next window to resample.
+    pub fn window_start(&self) -> DateTime<Utc> {
+        self.window_start
+    }
+}
+
+impl<S: Sample<Value = ()>, R: Sample<Value = f64>> Extend<S> for EventResampler<S, R> {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, iter: I) {
+        for event in iter {
+            self.push(event);
+        }
+    }
+}
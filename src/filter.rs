@@ -0,0 +1,67 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! The filter module provides the low-pass filters that can be applied to the
+//! samples before they are resampled, to avoid aliasing when resampling high
+//! frequency data to a slower interval.
+
+use std::collections::VecDeque;
+use std::f64::consts::PI;
+
+use chrono::TimeDelta;
+
+/// The LowPassFilter enum represents the different low-pass filters that can be
+/// applied to the values of the samples as they are added to a resampler.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LowPassFilter {
+    /// A finite impulse response filter with the given coefficients. The first
+    /// coefficient is applied to the newest value.
+    Fir(Vec<f64>),
+    /// A first order infinite impulse response filter with the given
+    /// smoothing factor between 0 and 1. The smaller the factor, the stronger
+    /// the smoothing.
+    Iir(f64),
+}
+
+impl LowPassFilter {
+    /// Creates a FIR filter that averages the given number of values.
+    pub fn moving_average(len: usize) -> Self {
+        Self::Fir(vec![1.0 / len.max(1) as f64; len.max(1)])
+    }
+
+    /// Creates a first order IIR filter with the given cutoff frequency in Hz,
+    /// for input data with the given interval.
+    pub fn first_order(cutoff_frequency: f64, input_interval: TimeDelta) -> Self {
+        let dt = input_interval.as_seconds_f64();
+        let rc = 1.0 / (2.0 * PI * cutoff_frequency);
+        Self::Iir(dt / (rc + dt))
+    }
+
+    /// Filters the given value, given the history of the previous values (FIR)
+    /// or outputs (IIR) of the filter, and updates the history.
+    pub(crate) fn filter(&self, history: &mut VecDeque<f64>, value: f64) -> f64 {
+        match self {
+            Self::Fir(coefficients) => {
+                // start with a history filled with the first value
+                if history.is_empty() {
+                    history.resize(coefficients.len(), value);
+                }
+                history.pop_back();
+                history.push_front(value);
+                coefficients
+                    .iter()
+                    .zip(history.iter())
+                    .map(|(c, v)| c * v)
+                    .sum()
+            }
+            Self::Iir(alpha) => {
+                let output = history
+                    .front()
+                    .map_or(value, |previous| previous + alpha * (value - previous));
+                history.clear();
+                history.push_front(output);
+                output
+            }
+        }
+    }
+}
@@ -0,0 +1,389 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! The group module provides the ResamplerGroup struct that manages the
+//! resamplers of many channels under one object.
+
+use std::collections::HashMap;
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use log::warn;
+
+use crate::error::ResampleError;
+use crate::resampler::{Resampler, Sample};
+use crate::value::ResampleValue;
+
+pub type ResamplerFactory<K, T, S> = Box<dyn Fn(&K) -> Resampler<T, S> + Send + Sync>;
+pub type ExpiryCallback<K, T, S> = Box<dyn FnMut(K, Resampler<T, S>) + Send + Sync>;
+
+/// The Quota enum represents the limits of a [`ResamplerGroup`], e.g. of a
+/// tenant of a shared resampling service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Quota {
+    /// The maximum number of keys, see
+    /// [`with_max_keys`][ResamplerGroup::with_max_keys]
+    Keys,
+    /// The maximum number of buffered samples of a key, see
+    /// [`with_max_pending_per_key`][ResamplerGroup::with_max_pending_per_key]
+    PendingPerKey,
+    /// The maximum number of buffered samples of all keys, see
+    /// [`with_max_pending`][ResamplerGroup::with_max_pending]
+    Pending,
+}
+
+impl Display for Quota {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Keys => "key",
+                Self::PendingPerKey => "per-key sample",
+                Self::Pending => "sample",
+            }
+        )
+    }
+}
+
+/// The QuotaStats struct contains counters of the samples a
+/// [`ResamplerGroup`] rejected or dropped because of its quotas so far, see
+/// [`quota_stats`][ResamplerGroup::quota_stats].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QuotaStats {
+    /// The number of samples of new keys that were rejected because the group
+    /// had the maximum number of keys
+    pub rejected_keys: u64,
+    /// The number of samples that were rejected because their key had the
+    /// maximum number of buffered samples
+    pub rejected_per_key: u64,
+    /// The number of samples that were rejected because the group had the
+    /// maximum number of buffered samples
+    pub rejected_pending: u64,
+    /// The number of resampled samples that were dropped because their key
+    /// exceeded the maximum number of resampled samples per resampling
+    pub dropped_outputs: u64,
+}
+
+/// The resampler of a key together with the time it was last active.
+struct Entry<T, S>
+where
+    T: ResampleValue,
+    S: Sample<Value = T>,
+{
+    resampler: Resampler<T, S>,
+    /// The timestamp of the latest added sample, or the first end time the
+    /// resampler was resampled until if no sample was added
+    last_seen: Option<DateTime<Utc>>,
+    /// The latest sample that was resampled by the group
+    latest: Option<S>,
+}
+
+impl<T, S> Debug for Entry<T, S>
+where
+    T: ResampleValue,
+    S: Sample<Value = T>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.resampler.fmt(f)
+    }
+}
+
+/// The ResamplerGroup struct manages a resampler per key, e.g. per component
+/// of a site. The resampler of a key is created by the factory when the first
+/// sample is added for it. Resamplers of keys that stop producing data can be
+/// dropped after an idle timeout, see
+/// [`with_idle_timeout`][Self::with_idle_timeout].
+pub struct ResamplerGroup<K, T: ResampleValue, S: Sample<Value = T>> {
+    factory: ResamplerFactory<K, T, S>,
+    resamplers: HashMap<K, Entry<T, S>>,
+    /// How long a resampler is kept without new samples. If None, resamplers
+    /// are kept until they are removed.
+    idle_timeout: Option<TimeDelta>,
+    /// The callback that is called for every resampler that expired
+    expiry_callback: Option<ExpiryCallback<K, T, S>>,
+    /// The maximum number of keys
+    max_keys: Option<usize>,
+    /// The maximum number of buffered samples of a key
+    max_pending_per_key: Option<usize>,
+    /// The maximum number of buffered samples of all keys
+    max_pending: Option<usize>,
+    /// The maximum number of resampled samples of a key per resampling
+    max_output_per_key: Option<usize>,
+    /// The number of buffered samples of all keys, see
+    /// [`Resampler::pending_len`]
+    pending: usize,
+    quota_stats: QuotaStats,
+}
+
+impl<K: Debug, T: ResampleValue, S: Sample<Value = T>> Debug for ResamplerGroup<K, T, S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResamplerGroup")
+            .field("resamplers", &self.resamplers)
+            .field("idle_timeout", &self.idle_timeout)
+            .field("max_keys", &self.max_keys)
+            .field("max_pending_per_key", &self.max_pending_per_key)
+            .field("max_pending", &self.max_pending)
+            .field("max_output_per_key", &self.max_output_per_key)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<K: Eq + Hash + Clone, T: ResampleValue, S: Sample<Value = T>> ResamplerGroup<K, T, S> {
+    /// Creates a new ResamplerGroup that creates the resampler of a key with
+    /// the given factory.
+    pub fn new(factory: ResamplerFactory<K, T, S>) -> Self {
+        Self {
+            factory,
+            resamplers: HashMap::new(),
+            idle_timeout: None,
+            expiry_callback: None,
+            max_keys: None,
+            max_pending_per_key: None,
+            max_pending: None,
+            max_output_per_key: None,
+            pending: 0,
+            quota_stats: QuotaStats::default(),
+        }
+    }
+
+    /// Sets how long a resampler is kept without new samples, e.g. so that
+    /// the resamplers of decommissioned components don't accumulate. Idle
+    /// resamplers are dropped by [`resample`][Self::resample] once the time
+    /// since their latest sample exceeds the timeout at the end time.
+    pub fn with_idle_timeout(mut self, idle_timeout: TimeDelta) -> Self {
+        self.idle_timeout = Some(idle_timeout);
+        self
+    }
+
+    /// Sets a callback that is called with the key and the resampler of every
+    /// resampler that is dropped after the idle timeout.
+    pub fn with_expiry_callback(mut self, callback: ExpiryCallback<K, T, S>) -> Self {
+        self.expiry_callback = Some(callback);
+        self
+    }
+
+    /// Sets the maximum number of keys. Samples of new keys are rejected
+    /// while the group has that many resamplers.
+    pub fn with_max_keys(mut self, max_keys: usize) -> Self {
+        self.max_keys = Some(max_keys);
+        self
+    }
+
+    /// Sets the maximum number of buffered samples of a key, see
+    /// [`Resampler::pending_len`]. Samples of a key are rejected while its
+    /// resampler buffers that many samples, so that a single noisy key can't
+    /// exhaust the quota of the group.
+    pub fn with_max_pending_per_key(mut self, max_pending: usize) -> Self {
+        self.max_pending_per_key = Some(max_pending);
+        self
+    }
+
+    /// Sets the maximum number of buffered samples of all keys, see
+    /// [`Resampler::pending_len`]. Samples are rejected while the resamplers
+    /// of the group buffer that many samples together.
+    pub fn with_max_pending(mut self, max_pending: usize) -> Self {
+        self.max_pending = Some(max_pending);
+        self
+    }
+
+    /// Sets the maximum number of resampled samples of a key that
+    /// [`resample`][Self::resample] returns, e.g. to limit the output rate
+    /// when catching up after an outage. Only the latest resampled samples
+    /// are returned, and the others are dropped.
+    pub fn with_max_output_per_key(mut self, max_output: usize) -> Self {
+        self.max_output_per_key = Some(max_output);
+        self
+    }
+
+    /// Adds a sample to the resampler of the given key, which is created if
+    /// it doesn't exist yet. Samples that exceed a quota are dropped and a
+    /// warning is logged, see [`try_push`][Self::try_push].
+    pub fn push(&mut self, key: K, sample: S) {
+        if let Err(e) = self.try_push(key, sample) {
+            warn!("{e}");
+        }
+    }
+
+    /// Adds a sample to the resampler of the given key like
+    /// [`push`][Self::push], but returns an error if the sample exceeds a
+    /// quota of the group or is rejected by the resampler, see
+    /// [`Resampler::try_push`].
+    pub fn try_push(&mut self, key: K, sample: S) -> Result<(), ResampleError> {
+        self.check_quotas(&key)?;
+        let entry = self.entry(key);
+        entry.last_seen = entry.last_seen.max(Some(sample.timestamp()));
+        let before = entry.resampler.pending_len();
+        let res = entry.resampler.try_push(sample);
+        // the sample may have been dropped, or have completed windows that
+        // were resampled on push
+        let after = entry.resampler.pending_len();
+        self.pending = (self.pending + after).saturating_sub(before);
+        res
+    }
+
+    /// Adds samples to the resampler of the given key, which is created if it
+    /// doesn't exist yet, see [`push`][Self::push].
+    pub fn extend<I: IntoIterator<Item = S>>(&mut self, key: K, samples: I) {
+        for sample in samples {
+            self.push(key.clone(), sample);
+        }
+    }
+
+    /// Returns an error if a sample of the given key exceeds a quota, and
+    /// counts the rejection.
+    fn check_quotas(&mut self, key: &K) -> Result<(), ResampleError> {
+        let key_pending = self
+            .resamplers
+            .get(key)
+            .map(|entry| entry.resampler.pending_len());
+        let exceeded = match key_pending {
+            None => self
+                .max_keys
+                .filter(|max| self.resamplers.len() >= *max)
+                .map(|max| (Quota::Keys, max)),
+            Some(pending) => self
+                .max_pending_per_key
+                .filter(|max| pending >= *max)
+                .map(|max| (Quota::PendingPerKey, max)),
+        }
+        .or_else(|| {
+            self.max_pending
+                .filter(|max| self.pending >= *max)
+                .map(|max| (Quota::Pending, max))
+        });
+        let Some((quota, limit)) = exceeded else {
+            return Ok(());
+        };
+        match quota {
+            Quota::Keys => self.quota_stats.rejected_keys += 1,
+            Quota::PendingPerKey => self.quota_stats.rejected_per_key += 1,
+            Quota::Pending => self.quota_stats.rejected_pending += 1,
+        }
+        Err(ResampleError::QuotaExceeded { quota, limit })
+    }
+
+    /// Returns the counters of the samples that were rejected or dropped
+    /// because of the quotas so far.
+    pub fn quota_stats(&self) -> QuotaStats {
+        self.quota_stats
+    }
+
+    /// Returns the number of buffered samples of all keys, see
+    /// [`Resampler::pending_len`].
+    pub fn pending_len(&self) -> usize {
+        self.pending
+    }
+
+    /// Resamples the samples of all resamplers until the given end time, and
+    /// returns the resampled samples by key. Afterwards, the resamplers that
+    /// were idle for longer than the idle timeout are dropped.
+    pub fn resample(&mut self, end: DateTime<Utc>) -> HashMap<K, Vec<S>> {
+        let mut dropped = 0;
+        let res = self
+            .resamplers
+            .iter_mut()
+            .map(|(key, entry)| {
+                entry.last_seen.get_or_insert(end);
+                let mut resampled = entry.resampler.resample(end);
+                if let Some(latest) = resampled.last() {
+                    entry.latest = Some(latest.clone());
+                }
+                if let Some(max_output) = self.max_output_per_key {
+                    let excess = resampled.len().saturating_sub(max_output);
+                    resampled.drain(..excess);
+                    dropped += excess as u64;
+                }
+                (key.clone(), resampled)
+            })
+            .collect();
+        self.quota_stats.dropped_outputs += dropped;
+        // the resamplers may also have been changed through resampler_mut
+        self.pending = self
+            .resamplers
+            .values()
+            .map(|entry| entry.resampler.pending_len())
+            .sum();
+        if let Some(idle_timeout) = self.idle_timeout {
+            self.expire(end - idle_timeout);
+        }
+        res
+    }
+
+    /// Drops the resamplers without samples after the given time.
+    fn expire(&mut self, idle_since: DateTime<Utc>) {
+        let expired = self
+            .resamplers
+            .iter()
+            .filter(|(_, entry)| entry.last_seen.is_some_and(|last| last < idle_since))
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        for key in expired {
+            if let Some(entry) = self.resamplers.remove(&key) {
+                self.pending = self.pending.saturating_sub(entry.resampler.pending_len());
+                if let Some(callback) = &mut self.expiry_callback {
+                    callback(key, entry.resampler);
+                }
+            }
+        }
+    }
+
+    /// Returns the resampler of the given key, if any.
+    pub fn get(&self, key: &K) -> Option<&Resampler<T, S>> {
+        self.resamplers.get(key).map(|entry| &entry.resampler)
+    }
+
+    /// Returns the latest sample that was resampled by
+    /// [`resample`][Self::resample] for the given key, if any.
+    pub fn latest(&self, key: &K) -> Option<&S> {
+        self.resamplers.get(key)?.latest.as_ref()
+    }
+
+    /// Returns the latest resampled sample of every key, see
+    /// [`latest`][Self::latest].
+    pub fn latest_all(&self) -> impl Iterator<Item = (&K, &S)> {
+        self.resamplers
+            .iter()
+            .filter_map(|(key, entry)| Some((key, entry.latest.as_ref()?)))
+    }
+
+    /// Returns the resampler of the given key, which is created if it doesn't
+    /// exist yet regardless of the quotas.
+    pub fn resampler_mut(&mut self, key: K) -> &mut Resampler<T, S> {
+        &mut self.entry(key).resampler
+    }
+
+    /// Returns the entry of the given key, which is created if it doesn't
+    /// exist yet.
+    fn entry(&mut self, key: K) -> &mut Entry<T, S> {
+        let factory = &self.factory;
+        self.resamplers.entry(key).or_insert_with_key(|key| Entry {
+            resampler: factory(key),
+            last_seen: None,
+            latest: None,
+        })
+    }
+
+    /// Removes the resampler of the given key and returns it, if any.
+    pub fn remove(&mut self, key: &K) -> Option<Resampler<T, S>> {
+        let entry = self.resamplers.remove(key)?;
+        self.pending = self.pending.saturating_sub(entry.resampler.pending_len());
+        Some(entry.resampler)
+    }
+
+    /// Returns the keys of the resamplers.
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.resamplers.keys()
+    }
+
+    /// Returns the number of resamplers.
+    pub fn len(&self) -> usize {
+        self.resamplers.len()
+    }
+
+    /// Returns whether there are no resamplers.
+    pub fn is_empty(&self) -> bool {
+        self.resamplers.is_empty()
+    }
+}
@@ -0,0 +1,103 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! The interval module provides the resampling intervals that can be used
+//! instead of a fixed duration, e.g. to resample to calendar months.
+
+use chrono::{DateTime, Datelike, Months, NaiveDate, NaiveTime, TimeDelta, TimeZone, Utc};
+
+use crate::resampler::epoch_align;
+
+/// The ResamplingInterval enum represents the time step between resampled
+/// samples, either as a fixed duration or in calendar units. Calendar
+/// intervals are aligned to the calendar in UTC or in a given timezone, with
+/// weeks starting on Monday, and take the varying lengths of months and of
+/// days with DST transitions into account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResamplingInterval {
+    /// A fixed duration, aligned to the Unix epoch
+    Fixed(TimeDelta),
+    /// The given number of days, starting at midnight
+    Days(u32),
+    /// The given number of weeks, starting on Monday at midnight
+    Weeks(u32),
+    /// The given number of months, starting on the first day of the month at
+    /// midnight
+    Months(u32),
+}
+
+impl ResamplingInterval {
+    /// Returns the start of the interval that contains the given timestamp.
+    pub fn align(&self, timestamp: DateTime<Utc>) -> DateTime<Utc> {
+        self.align_in(timestamp, &Utc)
+    }
+
+    /// Returns the start of the interval that contains the given timestamp,
+    /// for calendar intervals in the given timezone. Fixed intervals are
+    /// always aligned to the Unix epoch.
+    pub fn align_in<Tz: TimeZone>(&self, timestamp: DateTime<Utc>, tz: &Tz) -> DateTime<Utc> {
+        let date = timestamp.with_timezone(tz).date_naive();
+        let start = match *self {
+            Self::Fixed(interval) => return epoch_align(interval, timestamp, None),
+            Self::Days(days) => {
+                let days_since_epoch = (date - NaiveDate::default()).num_days();
+                NaiveDate::default()
+                    + TimeDelta::days(days_since_epoch.div_euclid(days.max(1) as i64))
+                        * days.max(1) as i32
+            }
+            Self::Weeks(weeks) => {
+                // the Unix epoch was a Thursday, so the first Monday after it
+                // is the origin of the weeks
+                let monday = NaiveDate::default() + TimeDelta::days(4);
+                let days = 7 * weeks.max(1) as i64;
+                monday + TimeDelta::days((date - monday).num_days().div_euclid(days) * days)
+            }
+            Self::Months(months) => {
+                let months_since_epoch = (date.year() - 1970) * 12 + date.month0() as i32;
+                let aligned =
+                    months_since_epoch.div_euclid(months.max(1) as i32) * months.max(1) as i32;
+                NaiveDate::from_ymd_opt(
+                    1970 + aligned.div_euclid(12),
+                    aligned.rem_euclid(12) as u32 + 1,
+                    1,
+                )
+                .unwrap_or(date)
+            }
+        };
+        start_of_day(start, tz)
+    }
+
+    /// Returns the start of the interval after the one starting at the given
+    /// timestamp.
+    pub fn next(&self, start: DateTime<Utc>) -> DateTime<Utc> {
+        self.next_in(start, &Utc)
+    }
+
+    /// Returns the start of the interval after the one starting at the given
+    /// timestamp, for calendar intervals in the given timezone.
+    pub fn next_in<Tz: TimeZone>(&self, start: DateTime<Utc>, tz: &Tz) -> DateTime<Utc> {
+        let date = start.with_timezone(tz).date_naive();
+        let next = match *self {
+            Self::Fixed(interval) => return start + interval,
+            Self::Days(days) => date + TimeDelta::days(days.max(1) as i64),
+            Self::Weeks(weeks) => date + TimeDelta::weeks(weeks.max(1) as i64),
+            Self::Months(months) => date
+                .checked_add_months(Months::new(months.max(1)))
+                .unwrap_or(date),
+        };
+        start_of_day(next, tz)
+    }
+}
+
+/// Returns the start of the given day in the given timezone, which is the
+/// first valid local time of the day if midnight is skipped by a DST
+/// transition.
+fn start_of_day<Tz: TimeZone>(date: NaiveDate, tz: &Tz) -> DateTime<Utc> {
+    let midnight = date.and_time(NaiveTime::MIN);
+    (0..24)
+        .find_map(|hour| {
+            tz.from_local_datetime(&(midnight + TimeDelta::hours(hour)))
+                .earliest()
+        })
+        .map_or_else(|| midnight.and_utc(), |start| start.with_timezone(&Utc))
+}
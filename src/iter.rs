@@ -0,0 +1,124 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! The iter module provides the ResampleExt trait that resamples iterators of
+//! samples lazily, e.g. for batch jobs over historical data.
+
+use std::collections::VecDeque;
+use std::fmt::Debug;
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+use crate::resampler::{Resampler, ResamplingFunction, Sample};
+use crate::value::ResampleValue;
+
+/// The ResampleExt trait adds methods to resample iterators of samples that
+/// are sorted by timestamp.
+pub trait ResampleExt<T, S>: Iterator<Item = S> + Sized
+where
+    T: ResampleValue,
+    S: Sample<Value = T>,
+{
+    /// Resamples the samples to the given interval with the given resampling
+    /// function, starting at the given start, which is aligned to the
+    /// interval. The resampled samples are labeled with the start of their
+    /// interval, see [`Resampler::new`].
+    fn resample(
+        self,
+        interval: TimeDelta,
+        resampling_function: ResamplingFunction<T, S>,
+        start: DateTime<Utc>,
+    ) -> Resampled<Self, T, S> {
+        self.resample_with(Resampler::new(
+            interval,
+            resampling_function,
+            1,
+            start,
+            true,
+        ))
+    }
+
+    /// Resamples the samples with the given resampler, e.g. to use further
+    /// options.
+    fn resample_with(self, resampler: Resampler<T, S>) -> Resampled<Self, T, S> {
+        Resampled {
+            samples: self,
+            resampler,
+            pending: VecDeque::new(),
+            pushed: false,
+            done: false,
+        }
+    }
+}
+
+impl<I, T, S> ResampleExt<T, S> for I
+where
+    I: Iterator<Item = S>,
+    T: ResampleValue,
+    S: Sample<Value = T>,
+{
+}
+
+/// The Resampled struct is an iterator over the resampled samples of an
+/// iterator of samples, see [`ResampleExt`]. A window is resampled as soon as
+/// the first sample after it is read, and the window of the last sample once
+/// the samples are exhausted.
+#[derive(Debug)]
+pub struct Resampled<I, T, S>
+where
+    T: ResampleValue,
+    S: Sample<Value = T>,
+{
+    samples: I,
+    resampler: Resampler<T, S>,
+    /// The resampled samples that were not returned yet
+    pending: VecDeque<S>,
+    /// Whether any sample was read
+    pushed: bool,
+    /// Whether the samples are exhausted
+    done: bool,
+}
+
+impl<I, T, S> Resampled<I, T, S>
+where
+    T: ResampleValue,
+    S: Sample<Value = T>,
+{
+    /// Returns the resampler, e.g. to inspect its statistics.
+    pub fn resampler(&self) -> &Resampler<T, S> {
+        &self.resampler
+    }
+}
+
+impl<I, T, S> Iterator for Resampled<I, T, S>
+where
+    I: Iterator<Item = S>,
+    T: ResampleValue,
+    S: Sample<Value = T>,
+{
+    type Item = S;
+
+    fn next(&mut self) -> Option<S> {
+        loop {
+            if let Some(sample) = self.pending.pop_front() {
+                return Some(sample);
+            }
+            if self.done {
+                return None;
+            }
+            match self.samples.next() {
+                Some(sample) => {
+                    self.pushed = true;
+                    self.pending.extend(self.resampler.push_and_emit(sample));
+                }
+                None => {
+                    self.done = true;
+                    if self.pushed {
+                        let end = self.resampler.next_window_end();
+                        self.pending.extend(self.resampler.resample(end));
+                    }
+                }
+            }
+        }
+    }
+}
@@ -10,7 +10,7 @@ given interval.
 ## Usage
 
 An instance of the [`Resampler`] can be created with the
-[`new`][Resampler::new] method.
+[`builder`][Resampler::builder] or the [`new`][Resampler::new] method.
 Raw data can be added to the resampler either through the
 [`push`][Resampler::push] or [`extend`][Resampler::extend] methods, and the
 [`resample`][Resampler::resample] method resamples the data that was added to
@@ -43,8 +43,14 @@ impl Sample for TestSample {
 }
 
 let start = DateTime::from_timestamp(0, 0).unwrap();
-let mut resampler: Resampler<f64, TestSample> =
-    Resampler::new(TimeDelta::seconds(5), ResamplingFunction::Average, 1, start, false);
+let mut resampler: Resampler<f64, TestSample> = Resampler::builder()
+    .interval(TimeDelta::seconds(5))
+    .function(ResamplingFunction::Average)
+    .max_age(1)
+    .start(start)
+    .first_timestamp(false)
+    .build()
+    .unwrap();
 
 let step = TimeDelta::seconds(1);
 let data = vec![
@@ -73,7 +79,54 @@ assert_eq!(resampled, expected);
 ```
 */
 
+mod accumulator;
+#[cfg(feature = "tokio")]
+mod actor;
+#[cfg(feature = "ndarray")]
+mod array;
+#[cfg(feature = "arrow")]
+mod arrow;
+mod boolean;
+#[cfg(feature = "tokio")]
+mod bounded;
+mod builder;
+mod conversion;
+#[cfg(feature = "csv")]
+mod csv_io;
+#[cfg(feature = "polars")]
+mod dataframe;
+mod error;
+mod event;
+mod filter;
+mod group;
+mod interval;
+mod iter;
+mod line_protocol;
+mod lttb;
+mod observer;
+mod outlier;
+#[cfg(feature = "rayon")]
+mod parallel;
+#[cfg(feature = "parquet")]
+mod parquet_writer;
+#[cfg(feature = "complex")]
+mod phasor;
+#[cfg(feature = "prometheus")]
+mod prometheus;
+#[cfg(feature = "uom")]
+mod quantity;
 mod resampler;
+mod savitzky_golay;
+#[cfg(feature = "serde")]
+mod serde_sample;
+#[cfg(feature = "simd")]
+mod simd;
+#[cfg(feature = "async")]
+mod sink;
+mod smoothing;
+mod state;
+mod time_axis;
+mod value;
 
 #[cfg(test)]
 mod tests;
@@ -81,4 +134,46 @@ mod tests;
 #[cfg(feature = "python")]
 mod python;
 
-pub use resampler::{Resampler, ResamplingFunction, Sample};
+#[cfg(feature = "tokio")]
+pub use actor::spawn_resampler;
+pub use boolean::{BoolAggregation, BoolResampler};
+#[cfg(feature = "tokio")]
+pub use bounded::BoundedResampler;
+pub use builder::ResamplerBuilder;
+pub use conversion::UnitConversion;
+#[cfg(feature = "csv")]
+pub use csv_io::{resample_csv, CsvError, TimestampFormat};
+pub use error::ResampleError;
+pub use event::{Event, EventAggregation, EventResampler};
+pub use filter::LowPassFilter;
+pub use group::{ExpiryCallback, Quota, QuotaStats, ResamplerFactory, ResamplerGroup};
+pub use interval::ResamplingInterval;
+pub use iter::{ResampleExt, Resampled};
+pub use line_protocol::{LineProtocolError, LineProtocolSelector, TimestampPrecision};
+pub use lttb::lttb;
+pub use observer::ResamplerObserver;
+pub use outlier::{OutlierFilter, ValidityFilter};
+#[cfg(feature = "rayon")]
+pub use parallel::resample_all;
+#[cfg(feature = "parquet")]
+pub use parquet_writer::ParquetWriter;
+#[cfg(feature = "complex")]
+pub use phasor::Phasor;
+#[cfg(feature = "uom")]
+pub use quantity::Quantity;
+pub use resampler::{
+    Clock, ClockJumpPolicy, Closed, DropCallback, DropReason, DuplicatePolicy, EmissionPolicy,
+    EmptyCount, GapPolicy, InputIntervalDetection, Interpolate, LabelPosition, NanPolicy,
+    OverflowPolicy, QualitySample, RateOf, ResampledBatch, ResampledWindow, Resampler,
+    ResamplerStats, ResamplingFunction, Sample, SampleTransform, SourceAggregate, SourcePriority,
+    SourceSample, WindowCallback, WindowSpec,
+};
+pub use savitzky_golay::SavitzkyGolay;
+#[cfg(feature = "serde")]
+pub use serde_sample::SerdeSample;
+#[cfg(feature = "async")]
+pub use sink::ResamplerSink;
+pub use smoothing::{Smoother, SmootherState};
+pub use state::ResamplerState;
+pub use time_axis::{AxisSample, TimeAxis};
+pub use value::{ArrayValue, ResampleValue};
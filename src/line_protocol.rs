@@ -0,0 +1,247 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! The line_protocol module provides the parsing of InfluxDB line protocol
+//! points into samples, so that collectors that already speak line protocol
+//! can resample the points before forwarding them.
+
+use std::fmt::{Debug, Display};
+
+use chrono::{DateTime, Utc};
+
+use crate::resampler::{Resampler, Sample};
+use crate::value::ResampleValue;
+
+/// The TimestampPrecision enum represents the precisions of the timestamps
+/// of line protocol points.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimestampPrecision {
+    /// Nanoseconds since the Unix epoch
+    #[default]
+    Nanoseconds,
+    /// Microseconds since the Unix epoch
+    Microseconds,
+    /// Milliseconds since the Unix epoch
+    Milliseconds,
+    /// Seconds since the Unix epoch
+    Seconds,
+}
+
+impl TimestampPrecision {
+    /// Converts the given timestamp in this precision.
+    fn timestamp(&self, timestamp: i64) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Nanoseconds => Some(DateTime::from_timestamp_nanos(timestamp)),
+            Self::Microseconds => DateTime::from_timestamp_micros(timestamp),
+            Self::Milliseconds => DateTime::from_timestamp_millis(timestamp),
+            Self::Seconds => DateTime::from_timestamp(timestamp, 0),
+        }
+    }
+}
+
+/// The LineProtocolError enum represents the errors that can occur when
+/// parsing line protocol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LineProtocolError {
+    /// A line is not a valid line protocol point.
+    Syntax(String),
+    /// The timestamp of a point is not an integer in the timestamp
+    /// precision.
+    InvalidTimestamp(String),
+    /// The selected field of a point is not a number.
+    InvalidValue(String),
+}
+
+impl Display for LineProtocolError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Syntax(line) => write!(f, "invalid line protocol point {line:?}"),
+            Self::InvalidTimestamp(timestamp) => write!(f, "invalid timestamp {timestamp:?}"),
+            Self::InvalidValue(value) => write!(f, "invalid value {value:?}"),
+        }
+    }
+}
+
+impl std::error::Error for LineProtocolError {}
+
+/// The LineProtocolSelector struct selects the points of a measurement, and
+/// the field of these points that is converted into samples.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineProtocolSelector {
+    measurement: String,
+    field: String,
+    tags: Vec<(String, String)>,
+    precision: TimestampPrecision,
+}
+
+impl LineProtocolSelector {
+    /// Creates a selector for the given field of the points of the given
+    /// measurement, with timestamps in nanoseconds.
+    pub fn new(measurement: impl Into<String>, field: impl Into<String>) -> Self {
+        Self {
+            measurement: measurement.into(),
+            field: field.into(),
+            tags: vec![],
+            precision: TimestampPrecision::default(),
+        }
+    }
+
+    /// Only selects the points that have the given tag value.
+    pub fn with_tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.tags.push((key.into(), value.into()));
+        self
+    }
+
+    /// Sets the precision of the timestamps of the points.
+    pub fn with_precision(mut self, precision: TimestampPrecision) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Parses the given line protocol point, and returns the sample of the
+    /// selected field, or `None` if the point is not selected or is a
+    /// comment. Points without a timestamp get the given timestamp.
+    pub fn parse<T, S>(
+        &self,
+        line: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Option<S>, LineProtocolError>
+    where
+        T: ResampleValue,
+        S: Sample<Value = T>,
+    {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(None);
+        }
+        let syntax = || LineProtocolError::Syntax(line.to_string());
+
+        let sections = split_unescaped(line, ' ');
+        let (series, fields, timestamp) = match sections.as_slice() {
+            [series, fields] => (*series, *fields, None),
+            [series, fields, timestamp] => (*series, *fields, Some(*timestamp)),
+            _ => return Err(syntax()),
+        };
+
+        let mut series = split_unescaped(series, ',').into_iter();
+        if series.next().map(unescape) != Some(self.measurement.clone()) {
+            return Ok(None);
+        }
+        let tags = series
+            .map(|tag| split_key_value(tag).ok_or_else(syntax))
+            .collect::<Result<Vec<_>, _>>()?;
+        if !self.tags.iter().all(|tag| tags.contains(tag)) {
+            return Ok(None);
+        }
+
+        let mut value = None;
+        for field in split_unescaped(fields, ',') {
+            let (key, field_value) = split_key_value(field).ok_or_else(syntax)?;
+            if key == self.field {
+                value = Some(field_value);
+            }
+        }
+        let Some(value) = value else {
+            return Ok(None);
+        };
+        let invalid_value = || LineProtocolError::InvalidValue(value.clone());
+        let value = if let Some(value) = value.strip_suffix('i') {
+            value
+                .parse::<i64>()
+                .ok()
+                .and_then(|v| T::from_scalar(v as f64))
+        } else if let Some(value) = value.strip_suffix('u') {
+            value
+                .parse::<u64>()
+                .ok()
+                .and_then(|v| T::from_scalar(v as f64))
+        } else {
+            value.parse().ok().and_then(T::from_scalar)
+        }
+        .ok_or_else(invalid_value)?;
+
+        let timestamp = match timestamp {
+            Some(timestamp) => timestamp
+                .parse()
+                .ok()
+                .and_then(|t| self.precision.timestamp(t))
+                .ok_or_else(|| LineProtocolError::InvalidTimestamp(timestamp.to_string()))?,
+            None => now,
+        };
+        Ok(Some(S::new(timestamp, Some(value))))
+    }
+}
+
+/// Splits the given string at the separators that are neither escaped nor
+/// quoted.
+fn split_unescaped(s: &str, separator: char) -> Vec<&str> {
+    let mut parts = vec![];
+    let (mut start, mut escaped, mut quoted) = (0, false, false);
+    for (i, c) in s.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => quoted = !quoted,
+            c if c == separator && !quoted => {
+                parts.push(&s[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+/// Splits the given tag or field at the first unescaped `=` and unescapes
+/// the key and the value.
+fn split_key_value(s: &str) -> Option<(String, String)> {
+    let mut escaped = false;
+    let (i, _) = s.char_indices().find(|&(_, c)| match c {
+        _ if escaped => {
+            escaped = false;
+            false
+        }
+        '\\' => {
+            escaped = true;
+            false
+        }
+        c => c == '=',
+    })?;
+    let key = unescape(&s[..i]);
+    (!key.is_empty()).then(|| (key, unescape(&s[i + 1..])))
+}
+
+/// Removes the backslashes of the escaped characters.
+fn unescape(s: &str) -> String {
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.extend(chars.next()),
+            c => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+impl<T: ResampleValue, S: Sample<Value = T>> Resampler<T, S> {
+    /// Adds the selected samples of the given line protocol points like
+    /// [`extend`][Extend::extend], see [`LineProtocolSelector::parse`].
+    /// Points without a timestamp get the [current time][Self::now]. Returns
+    /// the number of added samples.
+    pub fn extend_from_line_protocol(
+        &mut self,
+        points: &str,
+        selector: &LineProtocolSelector,
+    ) -> Result<usize, LineProtocolError> {
+        let now = self.now();
+        let samples = points
+            .lines()
+            .filter_map(|line| selector.parse(line, now).transpose())
+            .collect::<Result<Vec<S>, _>>()?;
+        let count = samples.len();
+        self.extend(samples);
+        Ok(count)
+    }
+}
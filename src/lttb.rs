@@ -0,0 +1,75 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! The lttb module provides the Largest-Triangle-Three-Buckets downsampling
+//! algorithm, that selects representative samples of a time series, e.g. for
+//! plotting.
+
+use num_traits::ToPrimitive;
+
+use crate::Sample;
+
+/// Downsamples the given samples to at most `threshold` samples with the
+/// Largest-Triangle-Three-Buckets algorithm.
+///
+/// In contrast to resampling, the returned samples are a selection of the
+/// given samples, which keeps the shape of the time series, including peaks,
+/// visually faithful. The first and the last sample are always kept. Samples
+/// without a value are skipped and the given samples are expected to be
+/// sorted by timestamp.
+pub fn lttb<S>(samples: &[S], threshold: usize) -> Vec<S>
+where
+    S: Sample,
+    S::Value: ToPrimitive,
+{
+    let Some(origin) = samples.first().map(|s| s.timestamp()) else {
+        return vec![];
+    };
+    let points = samples
+        .iter()
+        .filter_map(|s| {
+            let x = (s.timestamp() - origin).as_seconds_f64();
+            s.value().and_then(|v| v.to_f64()).map(|y| (x, y, s))
+        })
+        .collect::<Vec<_>>();
+    let n = points.len();
+    if threshold >= n {
+        return points.into_iter().map(|(_, _, s)| s.clone()).collect();
+    }
+    if threshold < 3 {
+        return [points.first(), points.last()]
+            .into_iter()
+            .flatten()
+            .take(threshold)
+            .map(|(_, _, s)| (*s).clone())
+            .collect();
+    }
+
+    let bucket_size = (n - 2) as f64 / (threshold - 2) as f64;
+    let bucket_start = |bucket: usize| ((bucket as f64 * bucket_size) as usize + 1).min(n - 1);
+    let mut selected = Vec::with_capacity(threshold);
+    selected.push(points[0].2.clone());
+    let mut previous = 0;
+    for bucket in 0..threshold - 2 {
+        // the average of the next bucket is the third corner of the triangle
+        let next = if bucket == threshold - 3 {
+            &points[n - 1..]
+        } else {
+            &points[bucket_start(bucket + 1)..bucket_start(bucket + 2)]
+        };
+        let (avg_x, avg_y) = next.iter().fold((0.0, 0.0), |(x, y), p| (x + p.0, y + p.1));
+        let (avg_x, avg_y) = (avg_x / next.len() as f64, avg_y / next.len() as f64);
+
+        let (prev_x, prev_y, _) = points[previous];
+        let area = |(x, y, _): &(f64, f64, &S)| {
+            ((prev_x - avg_x) * (y - prev_y) - (prev_x - x) * (avg_y - prev_y)).abs()
+        };
+        let best = (bucket_start(bucket)..bucket_start(bucket + 1))
+            .max_by(|&a, &b| area(&points[a]).total_cmp(&area(&points[b])))
+            .unwrap_or(bucket_start(bucket));
+        selected.push(points[best].2.clone());
+        previous = best;
+    }
+    selected.push(points[n - 1].2.clone());
+    selected
+}
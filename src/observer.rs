@@ -0,0 +1,25 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! The observer module provides the ResamplerObserver trait that is notified
+//! of the events of a resampler, e.g. to record metrics without depending on a
+//! specific metrics library.
+
+use crate::resampler::{DropReason, ResampledWindow};
+
+/// The ResamplerObserver trait is implemented by types that are notified of
+/// the events of a [`Resampler`][crate::Resampler], see
+/// [`with_observer`][crate::Resampler::with_observer]. All methods do nothing
+/// by default.
+pub trait ResamplerObserver<S>: Send + Sync {
+    /// Called for every emitted resampled sample, including corrections.
+    fn on_window_emitted(&mut self, _window: &ResampledWindow<S>) {}
+
+    /// Called for every added sample that the resampler discards, together
+    /// with the reason.
+    fn on_sample_dropped(&mut self, _sample: &S, _reason: DropReason) {}
+
+    /// Called when samples are removed from the buffer after resampling, with
+    /// the number of removed and of remaining samples.
+    fn on_buffer_pruned(&mut self, _pruned: usize, _buffered: usize) {}
+}
@@ -0,0 +1,109 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! The outlier module provides the outlier filters that can be applied to the
+//! samples of each interval before they are resampled, and the validity filter
+//! that is applied to the samples as they are added, to keep spikes from
+//! faulty sensors out of the aggregates.
+
+/// The OutlierFilter enum represents the different methods to detect outliers
+/// among the values of the samples of an interval.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutlierFilter {
+    /// Rejects values that are more than the given number of standard
+    /// deviations away from the mean.
+    ZScore(f64),
+    /// Rejects values that are more than the given multiple of the
+    /// interquartile range below the first or above the third quartile, e.g.
+    /// 1.5 for Tukey's fences.
+    Iqr(f64),
+}
+
+impl OutlierFilter {
+    /// Returns for each of the given values whether it is an outlier.
+    pub(crate) fn outliers(&self, values: &[f64]) -> Vec<bool> {
+        if values.is_empty() {
+            return vec![];
+        }
+        let (low, high) = match self {
+            Self::ZScore(threshold) => {
+                let n = values.len() as f64;
+                let mean = values.iter().sum::<f64>() / n;
+                let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+                let deviation = threshold * variance.sqrt();
+                (mean - deviation, mean + deviation)
+            }
+            Self::Iqr(factor) => {
+                let mut sorted = values.to_vec();
+                sorted.sort_by(f64::total_cmp);
+                let q1 = quantile(&sorted, 0.25);
+                let q3 = quantile(&sorted, 0.75);
+                let fence = factor * (q3 - q1);
+                (q1 - fence, q3 + fence)
+            }
+        };
+        values.iter().map(|v| *v < low || *v > high).collect()
+    }
+}
+
+/// The ValidityFilter struct rejects samples whose values are not plausible,
+/// e.g. a broken sensor reporting `1e308`. By default, it rejects infinite
+/// values. NaN values are handled by the [`NanPolicy`][crate::NanPolicy].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ValidityFilter {
+    min: Option<f64>,
+    max: Option<f64>,
+    allow_infinite: bool,
+}
+
+impl Default for ValidityFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ValidityFilter {
+    /// Creates a validity filter that rejects infinite values.
+    pub fn new() -> Self {
+        Self {
+            min: None,
+            max: None,
+            allow_infinite: false,
+        }
+    }
+
+    /// Rejects values below the given minimum.
+    pub fn with_min(mut self, min: f64) -> Self {
+        self.min = Some(min);
+        self
+    }
+
+    /// Rejects values above the given maximum.
+    pub fn with_max(mut self, max: f64) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    /// Sets whether infinite values within the minimum and maximum are
+    /// accepted. Defaults to `false`.
+    pub fn with_infinite(mut self, allow_infinite: bool) -> Self {
+        self.allow_infinite = allow_infinite;
+        self
+    }
+
+    /// Returns whether the given value is plausible.
+    pub(crate) fn is_valid(&self, value: f64) -> bool {
+        (self.allow_infinite || !value.is_infinite())
+            && self.min.is_none_or(|min| value >= min || value.is_nan())
+            && self.max.is_none_or(|max| value <= max || value.is_nan())
+    }
+}
+
+/// Returns the given quantile of the given sorted values, interpolating
+/// linearly between the closest ranks.
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let rank = q * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
@@ -0,0 +1,32 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! The parallel module provides helpers to resample many channels in
+//! parallel.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use chrono::{DateTime, Utc};
+use rayon::prelude::*;
+
+use crate::resampler::{Resampler, Sample};
+use crate::value::ResampleValue;
+
+/// Resamples all given resamplers until the given end time in parallel, see
+/// [`Resampler::resample`], and returns the resampled samples by the key of
+/// their resampler.
+pub fn resample_all<K, T, S>(
+    resamplers: &mut HashMap<K, Resampler<T, S>>,
+    end: DateTime<Utc>,
+) -> HashMap<K, Vec<S>>
+where
+    K: Eq + Hash + Clone + Send + Sync,
+    T: ResampleValue + Send,
+    S: Sample<Value = T> + Send,
+{
+    resamplers
+        .par_iter_mut()
+        .map(|(key, resampler)| (key.clone(), resampler.resample(end)))
+        .collect()
+}
@@ -0,0 +1,117 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! The parquet_writer module provides the ParquetWriter struct that archives
+//! resampled windows to Parquet files.
+
+use std::io::Write;
+use std::sync::Arc;
+
+use arrow_array::{Float64Array, RecordBatch, StringArray, TimestampNanosecondArray, UInt64Array};
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use num_traits::ToPrimitive;
+use parquet::arrow::ArrowWriter;
+use parquet::errors::Result;
+
+use crate::resampler::{ResampledWindow, Sample};
+
+/// The ParquetWriter struct appends the resampled windows of one or many
+/// channels to a Parquet file, e.g. to downsample and archive data. Every
+/// window is a row with the following columns:
+///
+/// - `channel`: the name of the channel
+/// - `timestamp`: the timestamp of the resampled sample
+/// - `window_start` and `window_end`: the bounds of the window
+/// - `value`: the resampled value, null if there is none
+/// - `sample_count` and `none_count`: the number of samples in the window,
+///   and the number of them without a value
+///
+/// The timestamps are nanoseconds since the Unix epoch in UTC.
+pub struct ParquetWriter<W: Write + Send> {
+    writer: ArrowWriter<W>,
+    schema: SchemaRef,
+}
+
+impl<W: Write + Send> std::fmt::Debug for ParquetWriter<W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ParquetWriter")
+            .field("schema", &self.schema)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<W: Write + Send> ParquetWriter<W> {
+    /// Creates a writer that writes a Parquet file to the given writer.
+    pub fn try_new(writer: W) -> Result<Self> {
+        let timestamp = DataType::Timestamp(TimeUnit::Nanosecond, Some("UTC".into()));
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("channel", DataType::Utf8, false),
+            Field::new("timestamp", timestamp.clone(), false),
+            Field::new("window_start", timestamp.clone(), false),
+            Field::new("window_end", timestamp, false),
+            Field::new("value", DataType::Float64, true),
+            Field::new("sample_count", DataType::UInt64, false),
+            Field::new("none_count", DataType::UInt64, false),
+        ]));
+        Ok(Self {
+            writer: ArrowWriter::try_new(writer, schema.clone(), None)?,
+            schema,
+        })
+    }
+
+    /// Appends the given resampled windows of the given channel.
+    pub fn write<S>(&mut self, channel: &str, windows: &[ResampledWindow<S>]) -> Result<()>
+    where
+        S: Sample,
+        S::Value: ToPrimitive,
+    {
+        self.write_channels(windows.iter().map(|window| (channel, window)))
+    }
+
+    /// Appends the given resampled windows, each with the name of its
+    /// channel, e.g. for the output of a [`ResamplerGroup`][crate::ResamplerGroup].
+    pub fn write_channels<'a, S, C, I>(&mut self, windows: I) -> Result<()>
+    where
+        S: Sample + 'a,
+        S::Value: ToPrimitive,
+        C: AsRef<str>,
+        I: IntoIterator<Item = (C, &'a ResampledWindow<S>)>,
+    {
+        let nanos = |timestamp: chrono::DateTime<chrono::Utc>| {
+            timestamp.timestamp_nanos_opt().unwrap_or(i64::MAX)
+        };
+        let (mut channels, mut timestamps, mut starts, mut ends) = (vec![], vec![], vec![], vec![]);
+        let (mut values, mut sample_counts, mut none_counts) = (vec![], vec![], vec![]);
+        for (channel, window) in windows {
+            channels.push(channel.as_ref().to_string());
+            timestamps.push(nanos(window.sample.timestamp()));
+            starts.push(nanos(window.window_start));
+            ends.push(nanos(window.window_end));
+            values.push(window.sample.value().and_then(|v| v.to_f64()));
+            sample_counts.push(window.sample_count as u64);
+            none_counts.push(window.none_count as u64);
+        }
+        if channels.is_empty() {
+            return Ok(());
+        }
+        let batch = RecordBatch::try_new(
+            self.schema.clone(),
+            vec![
+                Arc::new(StringArray::from(channels)),
+                Arc::new(TimestampNanosecondArray::from(timestamps).with_timezone("UTC")),
+                Arc::new(TimestampNanosecondArray::from(starts).with_timezone("UTC")),
+                Arc::new(TimestampNanosecondArray::from(ends).with_timezone("UTC")),
+                Arc::new(Float64Array::from(values)),
+                Arc::new(UInt64Array::from(sample_counts)),
+                Arc::new(UInt64Array::from(none_counts)),
+            ],
+        )?;
+        self.writer.write(&batch)
+    }
+
+    /// Writes the footer of the Parquet file, and returns the underlying
+    /// writer.
+    pub fn close(self) -> Result<W> {
+        self.writer.into_inner()
+    }
+}
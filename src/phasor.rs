@@ -0,0 +1,82 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! The phasor module provides the Phasor struct that allows complex values,
+//! e.g. voltage and current phasors from PMUs, to be resampled.
+
+use std::cmp::Ordering;
+
+use num_complex::Complex;
+
+use crate::value::ResampleValue;
+
+/// The Phasor struct is a complex value that can be resampled.
+///
+/// The `Average` resampling function averages the phasors as complex numbers,
+/// and `Min` and `Max` select the phasor with the smallest or largest
+/// magnitude. Phasors with the same magnitude are ordered by their phase
+/// angle.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Phasor(pub Complex<f64>);
+
+impl Phasor {
+    /// Creates a phasor from its real and imaginary parts.
+    pub fn new(re: f64, im: f64) -> Self {
+        Self(Complex::new(re, im))
+    }
+
+    /// Creates a phasor from its magnitude and phase angle in radians.
+    pub fn from_polar(magnitude: f64, phase: f64) -> Self {
+        Self(Complex::from_polar(magnitude, phase))
+    }
+
+    /// Returns the magnitude of the phasor.
+    pub fn magnitude(&self) -> f64 {
+        self.0.norm()
+    }
+
+    /// Returns the phase angle of the phasor in radians.
+    pub fn phase(&self) -> f64 {
+        self.0.arg()
+    }
+}
+
+impl From<Complex<f64>> for Phasor {
+    fn from(value: Complex<f64>) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Phasor> for Complex<f64> {
+    fn from(value: Phasor) -> Self {
+        value.0
+    }
+}
+
+impl PartialOrd for Phasor {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self == other {
+            return Some(Ordering::Equal);
+        }
+        match self.magnitude().partial_cmp(&other.magnitude())? {
+            Ordering::Equal => self.phase().partial_cmp(&other.phase()),
+            ordering => Some(ordering),
+        }
+    }
+}
+
+impl std::iter::Sum for Phasor {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        Self(iter.map(|phasor| phasor.0).sum())
+    }
+}
+
+impl ResampleValue for Phasor {
+    fn from_scalar(value: f64) -> Option<Self> {
+        Some(Self::new(value, 0.0))
+    }
+
+    fn div_scalar(self, divisor: f64) -> Option<Self> {
+        Some(Self(self.0 / divisor))
+    }
+}
@@ -0,0 +1,82 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! The prometheus module provides the rendering of the latest resampled
+//! values of a resampler group in the Prometheus text exposition format, so
+//! that the group can be scraped directly by Prometheus.
+
+use std::fmt::{Display, Write};
+use std::hash::Hash;
+
+use num_traits::ToPrimitive;
+
+use crate::group::ResamplerGroup;
+use crate::resampler::Sample;
+use crate::value::ResampleValue;
+
+impl<K: Eq + Hash + Clone + Display, T: ResampleValue + ToPrimitive, S: Sample<Value = T>>
+    ResamplerGroup<K, T, S>
+{
+    /// Renders the [latest resampled sample][Self::latest] of every key as a
+    /// sample of the gauge with the given metric name in the Prometheus text
+    /// exposition format. The key is rendered as the `key` label, the
+    /// characters that are not allowed in metric names are replaced by `_`,
+    /// and samples without a value are rendered as `NaN`.
+    pub fn render_prometheus(&self, metric: &str) -> String {
+        let mut samples = self
+            .latest_all()
+            .map(|(key, sample)| (key.to_string(), sample))
+            .collect::<Vec<_>>();
+        if samples.is_empty() {
+            return String::new();
+        }
+        samples.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let name = metric_name(metric);
+        let mut output = format!("# TYPE {name} gauge\n");
+        for (key, sample) in samples {
+            let value = match sample.value().and_then(|v| v.to_f64()) {
+                Some(v) if v == f64::INFINITY => "+Inf".to_string(),
+                Some(v) if v == f64::NEG_INFINITY => "-Inf".to_string(),
+                Some(v) => v.to_string(),
+                None => "NaN".to_string(),
+            };
+            let timestamp = sample.timestamp().timestamp_millis();
+            let key = label_value(&key);
+            // writing to a string can't fail
+            let _ = writeln!(output, "{name}{{key=\"{key}\"}} {value} {timestamp}");
+        }
+        output
+    }
+}
+
+/// Returns the given label value with backslashes, double quotes and line
+/// feeds escaped.
+fn label_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Returns the given name with the characters that are not allowed in
+/// Prometheus metric names replaced by `_`.
+fn metric_name(name: &str) -> String {
+    let mut name = name
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | ':' => c,
+            _ => '_',
+        })
+        .collect::<String>();
+    if !name.starts_with(|c: char| !c.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+    name
+}
@@ -1,24 +1,35 @@
-use crate::{resampler::Resampler, ResamplingFunction, Sample};
+use crate::{resampler::Resampler, LabelPosition, ResampleValue, ResamplingFunction, Sample};
 use chrono::{DateTime, TimeDelta, Utc};
-use pyo3::{exceptions::PyValueError, prelude::*};
-use std::fmt::Display;
+use num_traits::ToPrimitive;
+use pyo3::{
+    buffer::PyBuffer,
+    exceptions::{PyImportError, PyTypeError, PyValueError},
+    prelude::*,
+    types::{IntoPyDict, PyByteArray, PyList},
+};
+use std::fmt::{Debug, Display};
+use std::sync::{Arc, Mutex};
+
+/// The first exception raised by a custom resampling function since it was
+/// last raised by `resample`.
+type CallbackError = Arc<Mutex<Option<PyErr>>>;
 
 #[derive(Default, Clone, Debug, Copy)]
-struct PythonSample {
+struct PythonSample<T> {
     timestamp: DateTime<Utc>,
-    value: Option<f32>,
+    value: Option<T>,
 }
 
-impl PythonSample {
-    fn to_tuple(self) -> (DateTime<Utc>, Option<f32>) {
+impl<T: Copy> PythonSample<T> {
+    fn to_tuple(self) -> (DateTime<Utc>, Option<T>) {
         (self.timestamp, self.value)
     }
 }
 
-impl Sample for PythonSample {
-    type Value = f32;
+impl<T: Default + Copy + Debug> Sample for PythonSample<T> {
+    type Value = T;
 
-    fn new(timestamp: DateTime<Utc>, value: Option<f32>) -> Self {
+    fn new(timestamp: DateTime<Utc>, value: Option<T>) -> Self {
         Self { timestamp, value }
     }
 
@@ -26,7 +37,7 @@ impl Sample for PythonSample {
         self.timestamp
     }
 
-    fn value(&self) -> Option<f32> {
+    fn value(&self) -> Option<T> {
         self.value
     }
 }
@@ -144,7 +155,9 @@ impl Display for ResamplingFunctionF32 {
     }
 }
 
-impl From<ResamplingFunctionF32> for ResamplingFunction<f32, PythonSample> {
+impl<T: ResampleValue + Copy> From<ResamplingFunctionF32>
+    for ResamplingFunction<T, PythonSample<T>>
+{
     fn from(resampling_function: ResamplingFunctionF32) -> Self {
         match resampling_function {
             ResamplingFunctionF32::Average => ResamplingFunction::Average,
@@ -159,54 +172,453 @@ impl From<ResamplingFunctionF32> for ResamplingFunction<f32, PythonSample> {
     }
 }
 
-/// The Resampler class for f32 values.
-#[pyclass(name = "Resampler")]
-struct ResamplerF32 {
-    inner: Resampler<f32, PythonSample>,
+#[pyclass(eq, eq_int, name = "LabelPosition")]
+#[derive(Clone, Debug, Copy, PartialEq)]
+enum PythonLabelPosition {
+    Left,
+    Right,
+    Center,
 }
 
 #[pymethods]
-impl ResamplerF32 {
+impl PythonLabelPosition {
     #[new]
-    #[pyo3(signature = (interval, resampling_function, *, max_age_in_intervals, start, first_timestamp=true))]
-    fn new(
-        interval: TimeDelta,
-        resampling_function: ResamplingFunctionF32,
-        max_age_in_intervals: i32,
-        start: DateTime<Utc>,
-        first_timestamp: bool,
-    ) -> Self {
-        Self {
-            inner: Resampler::new(
-                interval,
-                resampling_function.into(),
-                max_age_in_intervals,
-                start,
-                first_timestamp,
-            ),
+    fn new(value: i32) -> PyResult<Self> {
+        value.try_into()
+    }
+
+    #[staticmethod]
+    fn values() -> Vec<i32> {
+        vec![
+            Self::Left.value(),
+            Self::Right.value(),
+            Self::Center.value(),
+        ]
+    }
+
+    #[staticmethod]
+    fn members() -> Vec<(String, i32)> {
+        vec![
+            (Self::Left.to_string(), Self::Left.value()),
+            (Self::Right.to_string(), Self::Right.value()),
+            (Self::Center.to_string(), Self::Center.value()),
+        ]
+    }
+
+    fn __str__(&self) -> String {
+        format!("{}.{}", "LabelPosition", self)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<{}: {}>", self.__str__(), self.value())
+    }
+
+    #[getter]
+    fn name(&self) -> String {
+        self.to_string()
+    }
+
+    #[getter]
+    fn value(&self) -> i32 {
+        match self {
+            Self::Left => 0,
+            Self::Right => 1,
+            Self::Center => 2,
         }
     }
+}
 
-    #[pyo3(signature = (*, timestamp, value))]
-    fn push_sample(&mut self, timestamp: DateTime<Utc>, value: Option<f32>) {
-        self.inner.push(PythonSample::new(timestamp, value));
+impl TryFrom<i32> for PythonLabelPosition {
+    type Error = PyErr;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Left),
+            1 => Ok(Self::Right),
+            2 => Ok(Self::Center),
+            _ => Err(PyValueError::new_err("Invalid label position")),
+        }
     }
+}
 
-    #[pyo3(signature = (end=None))]
-    fn resample(&mut self, end: Option<DateTime<Utc>>) -> Vec<(DateTime<Utc>, Option<f32>)> {
-        match end {
-            Some(end) => self.inner.resample(end),
-            None => self.inner.resample_now(),
+impl Display for PythonLabelPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                PythonLabelPosition::Left => "Left",
+                PythonLabelPosition::Right => "Right",
+                PythonLabelPosition::Center => "Center",
+            }
+        )
+    }
+}
+
+impl From<PythonLabelPosition> for LabelPosition {
+    fn from(label_position: PythonLabelPosition) -> Self {
+        match label_position {
+            PythonLabelPosition::Left => LabelPosition::Left,
+            PythonLabelPosition::Right => LabelPosition::Right,
+            PythonLabelPosition::Center => LabelPosition::Center,
         }
-        .into_iter()
-        .map(PythonSample::to_tuple)
+    }
+}
+
+/// Wraps the given Python callable into a custom resampling function, which
+/// gets the samples as a list of `(timestamp, value)` tuples. If the callable
+/// raises an exception, it is stored in the given error and the resampled
+/// value is None.
+fn wrap_custom_function<T>(
+    function: PyObject,
+    error: CallbackError,
+) -> ResamplingFunction<T, PythonSample<T>>
+where
+    T: ResampleValue
+        + Copy
+        + Send
+        + Sync
+        + 'static
+        + for<'py> IntoPyObject<'py>
+        + for<'py> FromPyObject<'py>,
+{
+    ResamplingFunction::Custom(Box::new(move |samples: &[&PythonSample<T>]| {
+        Python::with_gil(|py| {
+            PyList::new(py, samples.iter().map(|s| s.to_tuple()))
+                .and_then(|samples| function.call1(py, (samples,)))
+                .and_then(|value| value.extract::<Option<T>>(py))
+                .unwrap_or_else(|e| {
+                    if let Ok(mut error) = error.lock() {
+                        error.get_or_insert(e);
+                    }
+                    None
+                })
+        })
+    }))
+}
+
+/// Extracts the samples from the given iterable of `(timestamp, value)`
+/// tuples.
+fn extract_samples<T>(samples: &Bound<'_, PyAny>) -> PyResult<Vec<PythonSample<T>>>
+where
+    T: Default + Copy + Debug + for<'py> FromPyObject<'py>,
+{
+    samples
+        .try_iter()?
+        .map(|sample| {
+            let (timestamp, value) = sample?.extract::<(DateTime<Utc>, Option<T>)>()?;
+            Ok(PythonSample::new(timestamp, value))
+        })
         .collect()
+}
+
+/// Extracts the timestamps in nanoseconds since the Unix epoch from the given
+/// array of integers of any type, which is cast to `int64`, or from the given
+/// `datetime64` array of any unit, whose naive timestamps are in UTC.
+fn extract_timestamps(timestamps: &Bound<'_, PyAny>) -> PyResult<PyBuffer<i64>> {
+    let is_datetime = match timestamps.getattr("dtype") {
+        Ok(dtype) => dtype.getattr("kind")?.extract::<String>()? == "M",
+        Err(_) => false,
+    };
+    if is_datetime {
+        timestamps
+            .call_method1("astype", ("datetime64[ns]",))?
+            .call_method1("view", ("int64",))?
+            .extract()
+    } else {
+        as_contiguous_array(timestamps, "int64")?.extract()
+    }
+}
+
+/// Extracts the values from the given array of numbers of any type, which is
+/// cast to `float64`.
+fn extract_values(values: &Bound<'_, PyAny>) -> PyResult<PyBuffer<f64>> {
+    as_contiguous_array(values, "float64")?.extract()
+}
+
+/// Converts the given array-like object into a contiguous NumPy array of the
+/// given type, without copying it if it already is one.
+fn as_contiguous_array<'py>(array: &Bound<'py, PyAny>, dtype: &str) -> PyResult<Bound<'py, PyAny>> {
+    let kwargs = [("dtype", dtype)].into_py_dict(array.py())?;
+    array
+        .py()
+        .import("numpy")?
+        .call_method("ascontiguousarray", (array,), Some(&kwargs))
+}
+
+/// Extracts the samples from the given arrays of timestamps, see
+/// [`extract_timestamps`], and of values, where NaN values are missing.
+fn extract_array_samples<T: ResampleValue + Copy>(
+    py: Python<'_>,
+    timestamps: PyBuffer<i64>,
+    values: PyBuffer<f64>,
+) -> PyResult<Vec<PythonSample<T>>> {
+    let timestamps = timestamps.to_vec(py)?;
+    let values = values.to_vec(py)?;
+    if timestamps.len() != values.len() {
+        return Err(PyValueError::new_err(
+            "timestamps and values must have the same length",
+        ));
+    }
+    Ok(timestamps
+        .into_iter()
+        .zip(values)
+        .map(|(timestamp, value)| {
+            let value = (!value.is_nan()).then(|| T::from_scalar(value)).flatten();
+            PythonSample::new(DateTime::from_timestamp_nanos(timestamp), value)
+        })
+        .collect())
+}
+
+/// Extracts the samples from the given pandas Series of values indexed by
+/// timestamps, where naive timestamps are in UTC and missing values are NaN or
+/// NA.
+fn extract_series_samples<T: ResampleValue + Copy>(
+    series: &Bound<'_, PyAny>,
+) -> PyResult<Vec<PythonSample<T>>> {
+    let py = series.py();
+    let index = series.getattr("index")?;
+    if !index.is_instance(&py.import("pandas")?.getattr("DatetimeIndex")?)? {
+        return Err(PyTypeError::new_err(
+            "the index of the series must be a DatetimeIndex",
+        ));
+    }
+    let timestamps = index.getattr("asi8")?.extract()?;
+    let kwargs = [("dtype", "float64")].into_py_dict(py)?;
+    kwargs.set_item("na_value", f64::NAN)?;
+    let values = series
+        .call_method("to_numpy", (), Some(&kwargs))?
+        .extract()?;
+    extract_array_samples(py, timestamps, values)
+}
+
+/// Imports pandas, or returns None if it is not installed.
+fn import_pandas(py: Python<'_>) -> PyResult<Option<Bound<'_, PyModule>>> {
+    match py.import("pandas") {
+        Ok(pandas) => Ok(Some(pandas)),
+        Err(e) if e.is_instance_of::<PyImportError>(py) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Converts the given samples into a pandas Series of their values indexed by
+/// their timestamps in UTC, where missing values are NaN.
+fn to_pandas<'py, T: ToPrimitive + Copy>(
+    pandas: &Bound<'py, PyModule>,
+    samples: Vec<PythonSample<T>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let py = pandas.py();
+    let (timestamps, values) = to_arrays(py, samples)?;
+    let timestamps = timestamps.call_method1("view", ("datetime64[ns]",))?;
+    let kwargs = [("utc", true)].into_py_dict(py)?;
+    let index = pandas.call_method("to_datetime", (timestamps,), Some(&kwargs))?;
+    let kwargs = [("index", index)].into_py_dict(py)?;
+    pandas.call_method("Series", (values,), Some(&kwargs))
+}
+
+/// Converts the given samples into a tuple of the list of their timestamps and
+/// the list of their values, for when pandas is not installed.
+fn to_lists<'py, T>(py: Python<'py>, samples: Vec<PythonSample<T>>) -> PyResult<Bound<'py, PyAny>>
+where
+    T: Copy + for<'a> IntoPyObject<'a>,
+{
+    let (timestamps, values): (Vec<_>, Vec<_>) =
+        samples.into_iter().map(PythonSample::to_tuple).unzip();
+    Ok((timestamps, values).into_pyobject(py)?.into_any())
+}
+
+/// Converts the given samples into NumPy arrays of their timestamps in
+/// nanoseconds since the Unix epoch and of their values, where missing values
+/// are NaN.
+fn to_arrays<'py, T: ToPrimitive + Copy>(
+    py: Python<'py>,
+    samples: Vec<PythonSample<T>>,
+) -> PyResult<(Bound<'py, PyAny>, Bound<'py, PyAny>)> {
+    let numpy = py.import("numpy")?;
+    let timestamps = samples
+        .iter()
+        .map(|s| {
+            s.timestamp
+                .timestamp_nanos_opt()
+                .map(i64::to_ne_bytes)
+                .ok_or_else(|| PyValueError::new_err("timestamp out of range"))
+        })
+        .collect::<PyResult<Vec<_>>>()?
+        .concat();
+    let values = samples
+        .iter()
+        .flat_map(|s| {
+            let value = s.value.and_then(|v| v.to_f64()).unwrap_or(f64::NAN);
+            value.to_ne_bytes()
+        })
+        .collect::<Vec<_>>();
+    let array = |bytes: &[u8], dtype: &str| {
+        numpy.call_method1("frombuffer", (PyByteArray::new(py, bytes), dtype))
+    };
+    Ok((array(&timestamps, "int64")?, array(&values, "float64")?))
+}
+
+/// Raises the exception of a custom resampling function, if any.
+fn raise_error(error: &CallbackError) -> PyResult<()> {
+    match error.lock().ok().and_then(|mut error| error.take()) {
+        Some(e) => Err(e),
+        None => Ok(()),
     }
 }
 
+/// The common base class of the Resampler classes, so that they can share
+/// their type hints.
+#[pyclass(subclass, name = "_BaseResampler")]
+struct BaseResampler;
+
+/// Defines a Resampler class with the given Python name for the given value
+/// type.
+macro_rules! python_resampler {
+    ($(#[$attr:meta])* $name:ident, $py_name:literal, $value:ty) => {
+        $(#[$attr])*
+        #[pyclass(extends = BaseResampler, name = $py_name)]
+        struct $name {
+            inner: Resampler<$value, PythonSample<$value>>,
+            error: CallbackError,
+        }
+
+        #[pymethods]
+        impl $name {
+            #[new]
+            #[pyo3(signature = (interval, resampling_function=None, *, max_age_in_intervals, start, first_timestamp=true, label_position=None, custom_function=None))]
+            fn new(
+                interval: TimeDelta,
+                resampling_function: Option<ResamplingFunctionF32>,
+                max_age_in_intervals: i32,
+                start: DateTime<Utc>,
+                first_timestamp: bool,
+                label_position: Option<PythonLabelPosition>,
+                custom_function: Option<PyObject>,
+            ) -> PyResult<(Self, BaseResampler)> {
+                let error = CallbackError::default();
+                let resampling_function = match (resampling_function, custom_function) {
+                    (Some(resampling_function), None) => resampling_function.into(),
+                    (None, Some(function)) => wrap_custom_function(function, error.clone()),
+                    _ => {
+                        return Err(PyValueError::new_err(
+                            "either resampling_function or custom_function must be given",
+                        ))
+                    }
+                };
+                let mut inner = Resampler::try_new(
+                    interval,
+                    resampling_function,
+                    max_age_in_intervals,
+                    start,
+                    first_timestamp,
+                )
+                .map_err(|e| PyValueError::new_err(e.to_string()))?;
+                if let Some(label_position) = label_position {
+                    inner = inner.with_label_position(label_position.into());
+                }
+                Ok((Self { inner, error }, BaseResampler))
+            }
+
+            #[pyo3(signature = (*, timestamp, value))]
+            fn push_sample(&mut self, timestamp: DateTime<Utc>, value: Option<$value>) {
+                self.inner.push(PythonSample::new(timestamp, value));
+            }
+
+            fn push_samples(&mut self, samples: &Bound<'_, PyAny>) -> PyResult<()> {
+                self.inner.extend(extract_samples(samples)?);
+                Ok(())
+            }
+
+            fn push_array(
+                &mut self,
+                py: Python<'_>,
+                timestamps: &Bound<'_, PyAny>,
+                values: &Bound<'_, PyAny>,
+            ) -> PyResult<()> {
+                let timestamps = extract_timestamps(timestamps)?;
+                let values = extract_values(values)?;
+                self.inner
+                    .extend(extract_array_samples(py, timestamps, values)?);
+                Ok(())
+            }
+
+            #[pyo3(signature = (end=None))]
+            fn resample(
+                &mut self,
+                end: Option<DateTime<Utc>>,
+            ) -> PyResult<Vec<(DateTime<Utc>, Option<$value>)>> {
+                let resampled = self.resample_samples(end)?;
+                Ok(resampled.into_iter().map(PythonSample::to_tuple).collect())
+            }
+
+            #[pyo3(signature = (end=None))]
+            fn resample_arrays<'py>(
+                &mut self,
+                py: Python<'py>,
+                end: Option<DateTime<Utc>>,
+            ) -> PyResult<(Bound<'py, PyAny>, Bound<'py, PyAny>)> {
+                to_arrays(py, self.resample_samples(end)?)
+            }
+
+            fn push_series(&mut self, series: &Bound<'_, PyAny>) -> PyResult<()> {
+                self.inner.extend(extract_series_samples(series)?);
+                Ok(())
+            }
+
+            #[pyo3(signature = (end=None))]
+            fn resample_to_pandas<'py>(
+                &mut self,
+                py: Python<'py>,
+                end: Option<DateTime<Utc>>,
+            ) -> PyResult<Bound<'py, PyAny>> {
+                // import pandas first, so that the samples aren't lost if the
+                // import fails for another reason than pandas missing
+                match import_pandas(py)? {
+                    Some(pandas) => to_pandas(&pandas, self.resample_samples(end)?),
+                    None => to_lists(py, self.resample_samples(end)?),
+                }
+            }
+        }
+
+        impl $name {
+            /// Resamples the samples in the buffer until the given end time, or
+            /// until now if it is None.
+            fn resample_samples(
+                &mut self,
+                end: Option<DateTime<Utc>>,
+            ) -> PyResult<Vec<PythonSample<$value>>> {
+                let resampled = match end {
+                    Some(end) => self.inner.resample(end),
+                    None => self.inner.resample_now(),
+                };
+                raise_error(&self.error)?;
+                Ok(resampled)
+            }
+        }
+    };
+}
+
+python_resampler!(
+    /// The Resampler class for f32 values.
+    ResamplerF32,
+    "Resampler",
+    f32
+);
+
+python_resampler!(
+    /// The Resampler class for f64 values, e.g. for cumulative energy counters
+    /// that lose precision as f32.
+    ResamplerF64,
+    "ResamplerF64",
+    f64
+);
+
 #[pymodule]
 fn _rust_backend(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<BaseResampler>()?;
     m.add_class::<ResamplerF32>()?;
+    m.add_class::<ResamplerF64>()?;
     m.add_class::<ResamplingFunctionF32>()?;
+    m.add_class::<PythonLabelPosition>()?;
     Ok(())
 }
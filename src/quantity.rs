@@ -0,0 +1,68 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! The quantity module provides the Quantity struct that adapts the
+//! quantities of the `uom` crate to the ResampleValue trait.
+
+use std::fmt::Debug;
+use std::iter::Sum;
+
+use crate::value::ResampleValue;
+
+/// The Quantity struct wraps a quantity of the [`uom`] crate, e.g.
+/// `uom::si::f64::Power`, so that it can be resampled without being converted
+/// to `f64` first. The quantities are resampled in their base units. A count
+/// is not a quantity of the same kind, so `Count` is not supported.
+///
+/// The wrapper is needed because [`ResampleValue`] is implemented for all
+/// types that can be divided by themselves, which the quantities of `uom`
+/// might be in the future, so it can't be implemented for them directly.
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd)]
+pub struct Quantity<Q>(pub Q);
+
+impl<Q> Quantity<Q> {
+    /// Returns the wrapped quantity.
+    pub fn into_inner(self) -> Q {
+        self.0
+    }
+}
+
+impl<Q> From<Q> for Quantity<Q> {
+    fn from(quantity: Q) -> Self {
+        Self(quantity)
+    }
+}
+
+impl<Q: Sum> Sum for Quantity<Q> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        Self(iter.map(|q| q.0).sum())
+    }
+}
+
+impl<D, U, V> ResampleValue for Quantity<uom::si::Quantity<D, U, V>>
+where
+    D: uom::si::Dimension + ?Sized,
+    D::Kind: uom::marker::Add + uom::marker::Div,
+    U: uom::si::Units<V> + ?Sized,
+    V: uom::num::Float + uom::Conversion<V> + Sum + Default + Debug,
+{
+    fn from_scalar(value: f64) -> Option<Self> {
+        Some(Self(uom::si::Quantity {
+            dimension: std::marker::PhantomData,
+            units: std::marker::PhantomData,
+            value: V::from(value)?,
+        }))
+    }
+
+    fn from_count(_count: usize) -> Option<Self> {
+        None
+    }
+
+    fn div_scalar(self, divisor: f64) -> Option<Self> {
+        Some(Self(self.0 / V::from(divisor)?))
+    }
+
+    fn is_nan(&self) -> bool {
+        self.0.value.is_nan()
+    }
+}
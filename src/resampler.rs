@@ -6,13 +6,130 @@
 
 use chrono::{DateTime, TimeDelta, Utc};
 use log::warn;
-use num_traits::FromPrimitive;
+use num_traits::{PrimInt, ToPrimitive};
+use std::collections::{BTreeMap, VecDeque};
 use std::fmt::Debug;
-use std::ops::Div;
 
 use itertools::Itertools;
 
+use crate::accumulator::Accumulator;
+use crate::builder::ResamplerBuilder;
+use crate::conversion::UnitConversion;
+use crate::error::ResampleError;
+use crate::filter::LowPassFilter;
+use crate::interval::ResamplingInterval;
+use crate::observer::ResamplerObserver;
+use crate::outlier::{OutlierFilter, ValidityFilter};
+use crate::savitzky_golay::SavitzkyGolay;
+#[cfg(feature = "simd")]
+use crate::simd::Aggregation;
+use crate::smoothing::{Smoother, SmootherState};
+use crate::state::{ResamplerState, STATE_VERSION};
+use crate::time_axis::TimeAxis;
+use crate::value::ResampleValue;
+
 pub type CustomResamplingFunction<S, T> = Box<dyn FnMut(&[&S]) -> Option<T> + Send + Sync>;
+pub type CustomCarryResamplingFunction<S, T> =
+    Box<dyn FnMut(&[&S], &[&S]) -> Option<T> + Send + Sync>;
+pub type SampleTransform<S> = Box<dyn FnMut(&S) -> Option<S> + Send + Sync>;
+pub type Clock = Box<dyn Fn() -> DateTime<Utc> + Send + Sync>;
+pub type DropCallback<S> = Box<dyn FnMut(&S, DropReason) + Send + Sync>;
+pub type SourcePriority = Box<dyn Fn(u64) -> u32 + Send + Sync>;
+pub type WindowCallback<S> = Box<dyn FnMut(S) + Send + Sync>;
+#[cfg(feature = "simd")]
+type SimdAggregateFn<S> = fn(&[&S], Aggregation) -> Option<Option<f64>>;
+
+/// Wraps a [`SampleTransform`] so that it can be stored in the resampler.
+struct Transform<S>(SampleTransform<S>);
+
+impl<S> Debug for Transform<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Transform")
+    }
+}
+
+/// Wraps a [`Clock`] so that it can be stored in the resampler.
+struct ClockFn(Clock);
+
+impl Debug for ClockFn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Clock")
+    }
+}
+
+/// Wraps a [`DropCallback`] so that it can be stored in the resampler.
+struct DropFn<S>(DropCallback<S>);
+
+impl<S> Debug for DropFn<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DropCallback")
+    }
+}
+
+/// Wraps a [`WindowCallback`] so that it can be stored in the resampler.
+struct WindowFn<S>(WindowCallback<S>);
+
+impl<S> Debug for WindowFn<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "WindowCallback")
+    }
+}
+
+/// Wraps a [`SourcePriority`] of the sources of samples so that it can be
+/// stored in the resampler.
+struct PriorityFn<S>(Box<dyn Fn(&S) -> u32 + Send + Sync>);
+
+impl<S> Debug for PriorityFn<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SourcePriority")
+    }
+}
+
+/// Wraps a [`ResamplerObserver`] so that it can be stored in the resampler.
+struct Observer<S>(Box<dyn ResamplerObserver<S>>);
+
+impl<S> Debug for Observer<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ResamplerObserver")
+    }
+}
+
+/// The DropReason enum represents why a sample that was added to the
+/// resampler is discarded, see
+/// [`with_drop_callback`][Resampler::with_drop_callback].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropReason {
+    /// The input transform returned `None` for the sample.
+    Filtered,
+    /// The sample is not after the previous sample in strict ordering mode.
+    OutOfOrder,
+    /// The sample arrived after the watermark passed its window, and outside
+    /// of the correction horizon.
+    Late,
+    /// The sample is older than the retention edge of the next window, i.e. it
+    /// is not aggregated into any window that is still to be resampled.
+    Expired,
+    /// The value of the sample is NaN, and the [`NanPolicy`] is `Error`.
+    NotANumber,
+    /// The value of the sample was rejected by the validity filter, see
+    /// [`Resampler::with_validity_filter`].
+    Invalid,
+}
+
+/// The NanPolicy enum controls how samples with a NaN value are handled when
+/// they are added, before they are aggregated.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum NanPolicy {
+    /// NaN values are aggregated, so that the `Sum`, `Average`, `Min` and
+    /// `Max` of their windows are NaN.
+    #[default]
+    Propagate,
+    /// NaN values are added as samples without a value.
+    Ignore,
+    /// Samples with a NaN value are dropped by [`Resampler::push`] with a
+    /// warning, and returned as an error by [`Resampler::try_push`].
+    Error,
+}
 
 /// The Sample trait represents a single sample in a time series.
 pub trait Sample: Clone + Debug + Default {
@@ -22,13 +139,29 @@ pub trait Sample: Clone + Debug + Default {
     fn value(&self) -> Option<Self::Value>;
 }
 
+/// The QualitySample trait extends samples with a quality flag, e.g. the
+/// quality bits delivered by the upstream protocol, so that samples of bad
+/// quality can be excluded from the aggregation, see
+/// [`Resampler::with_quality_filter`].
+pub trait QualitySample: Sample {
+    /// Returns whether the sample is of good quality.
+    fn is_good(&self) -> bool;
+}
+
+/// The SourceSample trait extends samples with the ID of their source, e.g.
+/// the meter that measured them, so that the resampled windows of a channel
+/// that merges several sources can be attributed to them, see
+/// [`Resampler::with_source_attribution`], or be resampled from the best
+/// source only, see [`Resampler::with_source_priority`].
+pub trait SourceSample: Sample {
+    /// Returns the ID of the source of the sample.
+    fn source(&self) -> u64;
+}
+
 /// The ResamplingFunction enum represents the different resampling functions
 /// that can be used to resample a channel.
 #[derive(Default)]
-pub enum ResamplingFunction<
-    T: Div<Output = T> + std::iter::Sum + Default + Debug,
-    S: Sample<Value = T>,
-> {
+pub enum ResamplingFunction<T: ResampleValue, S: Sample<Value = T>> {
     /// Calculates the average of all samples in the time step (ignoring None
     /// values)
     #[default]
@@ -53,55 +186,149 @@ pub enum ResamplingFunction<
     Coalesce,
     /// Counts the number of samples in the time step (ignoring None values)
     Count,
+    /// Uses the sample whose timestamp is closest to the resampled timestamp,
+    /// if it is not further away than the given tolerance (ignoring None
+    /// values). The sample can also be outside of the time step, as long as
+    /// it is still in the buffer.
+    Nearest(TimeDelta),
+    /// Divides the count or the sum of all samples in the time step (ignoring
+    /// None values) by the duration of the time step in seconds, e.g. to get
+    /// events per second or to get W from Ws. If the input data starts within
+    /// the time step, only the covered part of the time step is used.
+    Rate(RateOf),
+    /// Fits a polynomial to the last samples with a value by least squares
+    /// and evaluates it at the resampled timestamp (Savitzky-Golay smoothing).
+    SavitzkyGolay(SavitzkyGolay<T>),
     /// A custom resampling function that takes a closure that takes a slice of
     /// samples and returns an optional value.
     Custom(CustomResamplingFunction<S, T>),
+    /// A custom resampling function like `Custom`, whose closure takes the
+    /// samples that were carried over from previous time steps due to
+    /// `max_age_in_intervals` and the samples of the time step itself as two
+    /// separate slices, e.g. for stateful aggregations that should skip or
+    /// weight repeated samples.
+    CustomWithCarry(CustomCarryResamplingFunction<S, T>),
+}
+
+/// The RateOf enum selects the per-interval value that the
+/// [`Rate`][ResamplingFunction::Rate] resampling function divides by the
+/// duration of the interval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateOf {
+    /// The number of samples (ignoring None values)
+    Count,
+    /// The sum of all samples (ignoring None values)
+    Sum,
+}
+
+/// The OverflowPolicy enum controls how the `Sum` of integer values that
+/// doesn't fit into the value type is handled, see
+/// [`Resampler::with_overflow_policy`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// The sum results in None.
+    #[default]
+    Checked,
+    /// The sum saturates at the minimum or maximum of the value type.
+    Saturating,
+}
+
+/// The integer arithmetic of the value type for overflow-checked
+/// aggregation, set by [`Resampler::with_overflow_policy`].
+#[derive(Debug, Clone, Copy)]
+struct IntegerOps<T> {
+    policy: OverflowPolicy,
+    /// Converts a value to the accumulator type
+    to_i128: fn(&T) -> Option<i128>,
+    /// Converts an aggregate back to the value type according to the policy
+    from_i128: fn(i128, OverflowPolicy) -> Option<T>,
+}
+
+/// The EmptyCount enum controls the result of the
+/// [`Count`][ResamplingFunction::Count] resampling function for intervals
+/// without any non-None samples.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EmptyCount {
+    /// Empty intervals are counted as `Some(0)`.
+    #[default]
+    Zero,
+    /// Empty intervals result in `None`, like all other resampling functions.
+    None,
 }
 
-impl<
-        T: Div<Output = T> + std::iter::Sum + PartialOrd + FromPrimitive + Default + Debug,
-        S: Sample<Value = T>,
-    > ResamplingFunction<T, S>
-{
+impl<T: ResampleValue, S: Sample<Value = T>> ResamplingFunction<T, S> {
+    /// Applies the resampling function to the given samples.
+    ///
+    /// As there is no resampled timestamp to compare with, `Nearest` uses the
+    /// last sample. Use [`apply_at`][Self::apply_at] instead to get the sample
+    /// closest to a timestamp. As there is no time step either, `Rate` uses
+    /// the time span between the first and the last sample. Use
+    /// [`apply_over`][Self::apply_over] instead to set the duration.
+    /// `SavitzkyGolay` is evaluated at the timestamp of the last sample, and
+    /// `CustomWithCarry` gets all samples as samples of the time step itself.
     pub fn apply(&mut self, samples: &[&S]) -> Option<T> {
         match self {
             Self::Average => Self::Sum
                 .apply(samples)
-                .and_then(|sum| Self::Count.apply(samples).map(|count| sum.div(count))),
+                .and_then(|sum| sum.div_count(samples.iter().filter_map(|s| s.value()).count())),
             Self::Sum => samples.iter().filter_map(|s| s.value()).sum1(),
-            Self::Max => samples.iter().filter_map(|s| s.value()).max_by(|a, b| {
-                a.partial_cmp(b).unwrap_or_else(|| {
-                    if a.partial_cmp(&T::default()).is_some() {
-                        std::cmp::Ordering::Greater
-                    } else {
-                        std::cmp::Ordering::Less
-                    }
-                })
-            }),
-            Self::Min => samples.iter().filter_map(|s| s.value()).min_by(|a, b| {
-                a.partial_cmp(b).unwrap_or_else(|| {
-                    if a.partial_cmp(&T::default()).is_some() {
-                        std::cmp::Ordering::Less
-                    } else {
-                        std::cmp::Ordering::Greater
-                    }
-                })
-            }),
+            Self::Max => T::max_of(samples.iter().filter_map(|s| s.value())),
+            Self::Min => T::min_of(samples.iter().filter_map(|s| s.value())),
             Self::First => samples.first().and_then(|s| s.value()),
             Self::Last => samples.last().and_then(|s| s.value()),
             Self::Coalesce => samples.iter().find_map(|s| s.value()),
             Self::Count => Some(
-                T::from_usize(samples.iter().filter_map(|s| s.value()).count())
+                T::from_count(samples.iter().filter_map(|s| s.value()).count())
                     .unwrap_or_else(|| T::default()),
             ),
+            Self::Nearest(_) => samples.last().and_then(|s| s.value()),
+            Self::Rate(_) => {
+                let first = samples.first()?.timestamp();
+                let last = samples.last()?.timestamp();
+                self.apply_over(samples, last - first)
+            }
+            Self::SavitzkyGolay(config) => config.smooth_at(samples, samples.last()?.timestamp()),
             Self::Custom(f) => f.as_mut()(samples),
+            Self::CustomWithCarry(f) => f.as_mut()(&[], samples),
+        }
+    }
+
+    /// Applies the resampling function to the given samples of a time step
+    /// with the given duration.
+    pub fn apply_over(&mut self, samples: &[&S], duration: TimeDelta) -> Option<T> {
+        match self {
+            Self::Rate(of) => {
+                if duration <= TimeDelta::zero() {
+                    return None;
+                }
+                let total = match of {
+                    RateOf::Count => Self::Count.apply(samples),
+                    RateOf::Sum => Self::Sum.apply(samples),
+                };
+                total.and_then(|total| total.div_scalar(duration.as_seconds_f64()))
+            }
+            _ => self.apply(samples),
+        }
+    }
+
+    /// Applies the resampling function to the given samples for a resampled
+    /// sample at the given timestamp.
+    pub fn apply_at(&mut self, samples: &[&S], timestamp: DateTime<Utc>) -> Option<T> {
+        match self {
+            Self::Nearest(tolerance) => samples
+                .iter()
+                .map(|s| ((s.timestamp() - timestamp).abs(), s))
+                .filter(|(distance, _)| distance <= tolerance)
+                .filter_map(|(distance, s)| s.value().map(|value| (distance, value)))
+                .min_by_key(|(distance, _)| *distance)
+                .map(|(_, value)| value),
+            Self::SavitzkyGolay(config) => config.smooth_at(samples, timestamp),
+            _ => self.apply(samples),
         }
     }
 }
 
-impl<T: Div<Output = T> + std::iter::Sum + Default + Debug, S: Sample<Value = T>> Debug
-    for ResamplingFunction<T, S>
-{
+impl<T: ResampleValue, S: Sample<Value = T>> Debug for ResamplingFunction<T, S> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Average => write!(f, "Average"),
@@ -112,29 +339,310 @@ impl<T: Div<Output = T> + std::iter::Sum + Default + Debug, S: Sample<Value = T>
             Self::Last => write!(f, "Last"),
             Self::Coalesce => write!(f, "Coalesce"),
             Self::Count => write!(f, "Count"),
+            Self::Nearest(tolerance) => write!(f, "Nearest({tolerance})"),
+            Self::Rate(of) => write!(f, "Rate({of:?})"),
+            Self::SavitzkyGolay(config) => write!(f, "{config:?}"),
             Self::Custom(_) => write!(f, "Custom"),
+            Self::CustomWithCarry(_) => write!(f, "CustomWithCarry"),
+        }
+    }
+}
+
+/// The Interpolate enum controls how intervals without samples of their own
+/// are resampled when the resampling interval is smaller than the interval of
+/// the input data.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Interpolate {
+    /// Intervals are resampled from the samples within `max_age_in_intervals`,
+    /// which repeats the last value.
+    #[default]
+    None,
+    /// Intervals are linearly interpolated between the surrounding samples
+    /// with a value. If there is no sample with a value on either side yet,
+    /// the interval is resampled as with `None`.
+    Linear,
+}
+
+/// The GapPolicy enum controls the value of resampled samples for intervals
+/// without any samples with a value.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GapPolicy {
+    /// Empty intervals are resampled to `None`.
+    #[default]
+    None,
+    /// Empty intervals get the last resampled value.
+    ForwardFill,
+    /// Empty intervals get a value of zero.
+    ZeroFill,
+    /// Empty intervals are linearly interpolated between the surrounding
+    /// samples with a value. If there is no sample with a value on either side
+    /// yet, the interval is resampled to `None`.
+    LinearInterpolate,
+}
+
+/// The WindowSpec enum controls how the windows of samples that are resampled
+/// together are delimited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowSpec {
+    /// Windows of the given duration on a fixed time grid.
+    Time(TimeDelta),
+    /// Windows of the given number of samples. A window is resampled once it
+    /// is complete, and its resampled sample is labeled according to the
+    /// [`LabelPosition`] between the timestamps of its first and last sample.
+    /// The `window_start` and `window_end` of a [`ResampledWindow`] are the
+    /// timestamps of its first and last sample.
+    Count(usize),
+    /// Windows of the given duration on a fixed time grid, that are closed
+    /// early whenever they reach the given number of samples, whichever comes
+    /// first. Windows that are closed early are resampled like `Count`
+    /// windows, and the rest of the interval continues as a new window up to
+    /// its end on the grid.
+    TimeOrCount(TimeDelta, usize),
+}
+
+/// The LabelPosition enum controls which timestamp of its window a resampled
+/// sample is labeled with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LabelPosition {
+    /// The start of the window
+    Left,
+    /// The end of the window
+    Right,
+    /// The midpoint of the window
+    Center,
+}
+
+impl LabelPosition {
+    /// Returns the label of the window from `start` to `end`.
+    pub fn label(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            Self::Left => start,
+            Self::Right => end,
+            Self::Center => start + (end - start) / 2,
         }
     }
 }
 
+/// The Closed enum controls which side of its interval a window includes, i.e.
+/// whether a sample exactly on a boundary belongs to the earlier or the later
+/// window, like `closed` of pandas' `resample`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Closed {
+    /// Windows include their start, so boundary samples belong to the later
+    /// window.
+    Left,
+    /// Windows include their end, so boundary samples belong to the earlier
+    /// window.
+    Right,
+}
+
+/// The InputIntervalDetection enum controls how the interval of the input
+/// data is detected, which `max_age_in_intervals` refers to if it is longer
+/// than the resampling interval.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum InputIntervalDetection {
+    /// The interval is detected once, when the first samples are resampled.
+    #[default]
+    Once,
+    /// The interval is re-detected with every added sample, as the median of
+    /// the intervals between the given number of latest consecutive samples,
+    /// e.g. for devices that change their reporting rate.
+    RollingMedian(usize),
+}
+
+/// The ClockJumpPolicy enum controls how
+/// [`resample_now`][Resampler::resample_now] handles the clock going
+/// backwards, e.g. because of an NTP correction.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ClockJumpPolicy {
+    /// Nothing is resampled until the clock catches up with the latest time
+    /// it reported.
+    #[default]
+    Hold,
+    /// The resampling continues from the interval of the new time, but the
+    /// intervals that were already emitted are not resampled again.
+    Realign,
+    /// [`try_resample_now`][Resampler::try_resample_now] returns a
+    /// [`ClockJump`][ResampleError::ClockJump] error.
+    Error,
+}
+
+/// The DuplicatePolicy enum controls how resampled samples are handled that
+/// are not newer than the last emitted sample, e.g. because the resampling
+/// start was moved back.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// Duplicates are emitted like any other resampled sample.
+    #[default]
+    Emit,
+    /// Duplicates are not emitted.
+    Suppress,
+    /// Duplicates are emitted, with the
+    /// [`duplicate`][ResampledWindow::duplicate] flag set.
+    Flag,
+}
+
+/// The EmissionPolicy enum controls when the resampled samples are produced.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EmissionPolicy {
+    /// The samples are resampled by calls to [`resample`][Resampler::resample]
+    /// and its variants only.
+    #[default]
+    Manual,
+    /// Adding a sample additionally resamples the windows that end before
+    /// it, and delivers the resampled samples to the
+    /// [`window callback`][Resampler::with_window_callback] and the
+    /// [`observer`][Resampler::with_observer], e.g. for event-driven
+    /// pipelines. This assumes that the samples are added in order, as later
+    /// samples for the resampled windows are late.
+    OnPush,
+}
+
+/// The ResampledWindow struct contains a resampled sample together with the
+/// metadata of the window it was aggregated from.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ResampledWindow<S> {
+    /// The start of the window
+    pub window_start: DateTime<Utc>,
+    /// The end of the window
+    pub window_end: DateTime<Utc>,
+    /// The number of samples that were aggregated, including None values
+    pub sample_count: usize,
+    /// The number of aggregated samples with a None value
+    pub none_count: usize,
+    /// The number of samples of bad quality that were excluded from the
+    /// aggregation, see [`Resampler::with_quality_filter`]
+    pub bad_count: usize,
+    /// Whether the sample is not newer than a previously emitted sample, see
+    /// [`DuplicatePolicy::Flag`]
+    pub duplicate: bool,
+    /// Whether the sample is a correction of a previously emitted sample,
+    /// recomputed because late samples arrived for its window, see
+    /// [`with_correction_horizon`][Resampler::with_correction_horizon]
+    pub correction: bool,
+    /// The resampled sample
+    pub sample: S,
+    /// The aggregates of the samples of every source, sorted by source, see
+    /// [`Resampler::with_source_attribution`]
+    pub sources: Vec<SourceAggregate<S>>,
+}
+
+/// The SourceAggregate struct contains the aggregate of the samples of one
+/// source in a resampled window.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SourceAggregate<S> {
+    /// The ID of the source
+    pub source: u64,
+    /// The number of samples of the source that were aggregated, including
+    /// None values
+    pub sample_count: usize,
+    /// The aggregate of the samples of the source
+    pub sample: S,
+}
+
+/// The counts of the samples of a resampled window, and the aggregates of
+/// its sources, see [`ResampledWindow`].
+struct WindowCounts<S> {
+    sample_count: usize,
+    none_count: usize,
+    bad_count: usize,
+    sources: Vec<SourceAggregate<S>>,
+}
+
+/// The ResampledBatch struct contains the resampled samples of a call to
+/// [`resample_batch`][Resampler::resample_batch], together with the
+/// [`fingerprint`][Resampler::fingerprint] of the configuration they were
+/// resampled with.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ResampledBatch<S> {
+    /// The fingerprint of the configuration of the resampler
+    pub fingerprint: u64,
+    /// The resampled samples
+    pub samples: Vec<S>,
+}
+
+/// The ResamplerStats struct contains counters of the samples and windows a
+/// [`Resampler`] processed so far, see [`stats`][Resampler::stats], e.g. to
+/// monitor data loss.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ResamplerStats {
+    /// The number of samples that were added, including dropped samples
+    pub samples_pushed: u64,
+    /// The number of samples that were older than the maximum age when they
+    /// were added
+    pub expired_samples: u64,
+    /// The number of samples that were rejected in strict ordering mode
+    /// because they were not after the previous sample
+    pub out_of_order_samples: u64,
+    /// The number of samples that arrived after their window was finalized
+    pub late_samples: u64,
+    /// The number of samples that were rejected as outliers
+    pub rejected_outliers: u64,
+    /// The number of samples that were rejected by the validity filter
+    pub invalid_samples: u64,
+    /// The number of emitted resampled samples, excluding corrections
+    pub windows_emitted: u64,
+    /// The number of resampled windows without any samples with a value,
+    /// whether or not they were emitted
+    pub empty_windows: u64,
+}
+
 /// The Resampler struct is used to resample a time series of samples. It stores
 /// the samples in a buffer and resamples the samples in the buffer when the
 /// resample method is called. A resampler can be configured with a resampling
 /// function and a resampling interval.
 #[derive(Debug, Default)]
-pub struct Resampler<
-    T: Div<Output = T> + std::iter::Sum + PartialOrd + FromPrimitive + Default + Debug,
-    S: Sample<Value = T>,
-> {
+pub struct Resampler<T: ResampleValue, S: Sample<Value = T>> {
     /// The time step between each resampled sample
     interval: TimeDelta,
     /// The resampling functions to use for each channel
     resampling_function: ResamplingFunction<T, S>,
+    /// The calendar interval, if the duration of the intervals varies. The
+    /// interval is then updated to the duration of the current one.
+    calendar_interval: Option<ResamplingInterval>,
+    /// The timestamp that fixed intervals are aligned to. If None, they are
+    /// aligned to the Unix epoch.
+    alignment_timestamp: Option<DateTime<Utc>>,
+    /// The timezone that calendar intervals are aligned in. If None, they are
+    /// aligned in UTC.
+    #[cfg(feature = "chrono-tz")]
+    timezone: Option<chrono_tz::Tz>,
+    /// The interval set by `set_interval`, until the start of the resampling
+    /// is aligned to it. The interval is then the duration of the transitional
+    /// window up to the next aligned start.
+    pending_interval: Option<TimeDelta>,
+    /// The interval set by `set_interval` while there were accumulated
+    /// samples, until the windows they belong to are resampled
+    deferred_interval: Option<TimeDelta>,
+    /// The number of samples of each window, if the windows are count-based
+    count_window: Option<usize>,
+    /// The maximum number of samples of each time-based window, after which
+    /// it is closed early
+    max_window_count: Option<usize>,
+    /// The time step between the starts of consecutive intervals. If None,
+    /// it is the interval, i.e. the intervals don't overlap.
+    hop: Option<TimeDelta>,
     /// The buffer that stores the samples
-    buffer: Vec<S>,
+    buffer: VecDeque<S>,
+    /// The highest number of samples in the buffer so far
+    buffer_high_watermark: usize,
+    /// The aggregates of the samples of the open windows by their start, for
+    /// the incremental aggregation instead of the buffer
+    accumulators: BTreeMap<DateTime<Utc>, Accumulator<S>>,
+    /// Whether the samples are aggregated incrementally when the
+    /// configuration supports it, see
+    /// [`with_incremental_aggregation`][Resampler::with_incremental_aggregation]
+    incremental_aggregation: bool,
     /// Resample the data in the buffer that is not older than max_age_in_intervals. Number of
     /// intervals. If set to 0, all samples are skipped.
     max_age_in_intervals: i32,
+    /// The maximum age of the samples that are resampled, relative to the end
+    /// of their interval. If set, it replaces `max_age_in_intervals`.
+    max_age: Option<TimeDelta>,
     /// The start time of the resampling.
     start: DateTime<Utc>,
     /// The timestamp of the first sample in the buffer. If None, the timestamp
@@ -142,6 +650,13 @@ pub struct Resampler<
     input_start: Option<DateTime<Utc>>,
     /// The interval between the first and the second sample in the buffer
     input_interval: Option<TimeDelta>,
+    /// How the interval of the input data is detected
+    input_interval_detection: InputIntervalDetection,
+    /// The timestamp of the latest sample that was added to the buffer
+    last_input_at: Option<DateTime<Utc>>,
+    /// The latest intervals between consecutive samples, for the
+    /// `RollingMedian` input interval detection
+    input_deltas: VecDeque<TimeDelta>,
     /// Whether the resampled timestamp should be the first timestamp (if
     /// `first_timestamp` is `true`) or the last timestamp (if
     /// `first_timestamp` is `false`) in the buffer.
@@ -152,15 +667,129 @@ pub struct Resampler<
     /// timestamp of the last sample in the buffer and the aggregation will
     /// be done with the samples that are `interval` in the past.
     first_timestamp: bool,
+    /// The position of the resampled timestamp within its window. If None, it
+    /// follows `first_timestamp`.
+    label_position: Option<LabelPosition>,
+    /// The side of its interval that a window includes. If None, it is the
+    /// left side if `first_timestamp` is `true` and the right side otherwise.
+    closed: Option<Closed>,
+    /// The result of the `Count` resampling function for intervals without
+    /// any non-None samples.
+    empty_count: EmptyCount,
+    /// How intervals without samples of their own are resampled
+    interpolate: Interpolate,
+    /// Converts values to `f64` for the numeric features of the resampler.
+    /// Set by the configuration methods that need it.
+    to_f64: Option<fn(&T) -> Option<f64>>,
+    /// The integer arithmetic for overflow-checked `Sum` and `Average`, if
+    /// configured
+    integer_ops: Option<IntegerOps<T>>,
+    /// The value of resampled samples for intervals without any samples with
+    /// a value
+    gap_policy: GapPolicy,
+    /// The last resampled sample with a value that was not filled in by the
+    /// gap policy
+    last_resampled: Option<S>,
+    /// The maximum duration the last value is carried forward by the
+    /// `ForwardFill` gap policy. If None, it is carried forward indefinitely.
+    max_fill: Option<TimeDelta>,
+    /// The timestamp of the earliest sample that was added to the resampler
+    first_sample_at: Option<DateTime<Utc>>,
+    /// The duration that the `Sum` and `Count` resampling functions are
+    /// normalized to. If None, they are not normalized.
+    reference_interval: Option<TimeDelta>,
+    /// Whether resampled samples without a value are emitted
+    emit_empty_windows: bool,
+    /// The low-pass filter that is applied to the values of the samples as
+    /// they are added
+    low_pass_filter: Option<LowPassFilter>,
+    /// The previous values or outputs of the low-pass filter
+    filter_history: VecDeque<f64>,
+    /// The smoother that is applied to the resampled samples before they are
+    /// emitted
+    smoother: Option<Smoother>,
+    /// The state of the smoother after the last emitted sample
+    smoother_state: Option<SmootherState>,
+    /// The outlier filter that is applied to the samples of each interval
+    /// before they are resampled
+    outlier_filter: Option<OutlierFilter>,
+    /// The number of samples that were rejected as outliers
+    rejected_outliers: u64,
+    /// The conversion that is applied to the values of the samples as they
+    /// are added
+    ingress_conversion: Option<UnitConversion>,
+    /// The conversion that is applied to the values of the resampled samples
+    /// before they are emitted
+    egress_conversion: Option<UnitConversion>,
+    /// How resampled samples that are not newer than the last emitted sample
+    /// are handled
+    duplicate_policy: DuplicatePolicy,
+    /// Whether adding samples resamples the windows that end before them
+    emission_policy: EmissionPolicy,
+    /// The timestamp of the last emitted sample
+    last_emitted: Option<DateTime<Utc>>,
+    /// The transform that is applied to the samples as they are added
+    input_transform: Option<Transform<S>>,
+    /// The transform that is applied to the resampled samples before they are
+    /// emitted
+    output_transform: Option<Transform<S>>,
+    /// The priority of the source of a sample, lower is better. If set, only
+    /// the samples of the best source with a value are resampled per window.
+    source_priority: Option<PriorityFn<S>>,
+    /// The clock that is used by `resample_now`. If None, the system clock is
+    /// used.
+    clock: Option<ClockFn>,
+    /// How `resample_now` handles the clock going backwards
+    clock_jump_policy: ClockJumpPolicy,
+    /// The latest time the clock reported to `resample_now`
+    last_now: Option<DateTime<Utc>>,
+    /// Whether samples that are not after the previous sample are rejected
+    strict_ordering: bool,
+    /// How samples with a NaN value are handled when they are added
+    nan_policy: NanPolicy,
+    /// The filter that rejects implausible values when they are added
+    validity_filter: Option<ValidityFilter>,
+    /// Returns whether a sample is of good quality. If set, samples of bad
+    /// quality are excluded from the aggregation.
+    quality: Option<fn(&S) -> bool>,
+    /// Returns the source of a sample. If set, the samples of every source are
+    /// also aggregated separately.
+    source: Option<fn(&S) -> u64>,
+    /// The timestamp of the latest sample that was added
+    last_pushed: Option<DateTime<Utc>>,
+    /// How long windows are kept open for late samples after their end
+    allowed_lateness: Option<TimeDelta>,
+    /// The latest end of the resampling minus the allowed lateness, up to
+    /// which windows are finalized
+    watermark: Option<DateTime<Utc>>,
+    /// The number of samples that were dropped because they arrived after
+    /// their window was finalized
+    late_samples: u64,
+    /// The callback that is called for every sample that is discarded
+    drop_callback: Option<DropFn<S>>,
+    /// The observer that is notified of the events of the resampler
+    observer: Option<Observer<S>>,
+    /// The callback that is called with every emitted resampled sample
+    window_callback: Option<WindowFn<S>>,
+    /// The counters of the processed samples and windows
+    stats: ResamplerStats,
+    /// Aggregates the values of an interval by the SIMD fast path, if the
+    /// `Sum`, `Average`, `Min` and `Max` resampling functions are computed by
+    /// it
+    #[cfg(feature = "simd")]
+    simd: Option<SimdAggregateFn<S>>,
+    /// How long finalized windows are recomputed for late samples
+    correction_horizon: Option<TimeDelta>,
+    /// The samples of the windows within the correction horizon
+    history: VecDeque<S>,
+    /// The timestamps of the late samples whose windows are to be corrected
+    pending_corrections: Vec<DateTime<Utc>>,
 }
 
-impl<
-        T: Div<Output = T> + std::iter::Sum + PartialOrd + FromPrimitive + Default + Debug,
-        S: Sample<Value = T>,
-    > Resampler<T, S>
-{
+impl<T: ResampleValue, S: Sample<Value = T>> Resampler<T, S> {
     /// Creates a new Resampler with the given resampling interval and
-    /// resampling function.
+    /// resampling function. The parameters are not validated, see
+    /// [`try_new`][Self::try_new].
     pub fn new(
         interval: TimeDelta,
         resampling_function: ResamplingFunction<T, S>,
@@ -175,148 +804,2421 @@ impl<
             max_age_in_intervals,
             start: aligned_start,
             first_timestamp,
+            emit_empty_windows: true,
             ..Default::default()
         }
     }
 
-    /// Adds a sample to the buffer.
-    pub fn push(&mut self, sample: S) {
-        self.buffer.push(sample);
+    /// Returns a builder to create a Resampler with named options, e.g.
+    /// `Resampler::builder().interval(interval).function(function).build()`.
+    pub fn builder() -> ResamplerBuilder<T, S> {
+        ResamplerBuilder::default()
     }
 
-    /// Returns a reference to the buffer.
-    pub fn buffer(&self) -> &Vec<S> {
-        &self.buffer
+    /// Creates a new Resampler like [`new`][Self::new], but returns an error if
+    /// the interval is shorter than a millisecond or `max_age_in_intervals`
+    /// is negative.
+    pub fn try_new(
+        interval: TimeDelta,
+        resampling_function: ResamplingFunction<T, S>,
+        max_age_in_intervals: i32,
+        start: DateTime<Utc>,
+        first_timestamp: bool,
+    ) -> Result<Self, ResampleError> {
+        if interval.num_milliseconds() <= 0 {
+            return Err(ResampleError::InvalidInterval(interval));
+        }
+        if max_age_in_intervals < 0 {
+            return Err(ResampleError::InvalidMaxAge(max_age_in_intervals));
+        }
+        Ok(Self::new(
+            interval,
+            resampling_function,
+            max_age_in_intervals,
+            start,
+            first_timestamp,
+        ))
     }
 
-    /// Resamples the samples in the buffer and returns the resampled samples
-    /// until the given end time.
-    pub fn resample(&mut self, end: DateTime<Utc>) -> Vec<S> {
-        if self.start >= end {
-            warn!("start time is greater or equal to end time");
-            return vec![];
+    /// Sets the resampling interval, which can be a calendar interval, e.g. to
+    /// resample to calendar months. The start of the resampling is aligned to
+    /// the given interval.
+    pub fn with_resampling_interval(mut self, interval: ResamplingInterval) -> Self {
+        match interval {
+            ResamplingInterval::Fixed(interval) => {
+                self.interval = interval;
+                self.start = self.epoch_align(self.start);
+                self.calendar_interval = None;
+            }
+            _ => {
+                self.start = self.calendar_align(interval, self.start);
+                self.calendar_interval = Some(interval);
+                self.update_calendar_interval();
+            }
         }
-        let mut res = vec![];
-        let mut interval_buffer = vec![];
-        let mut buffer_iter = self.buffer.iter();
-        let mut next_sample: Option<&S> = buffer_iter.next();
-        self.input_start = next_sample.map(|s| s.timestamp());
-        let offset = if self.first_timestamp {
-            TimeDelta::zero()
-        } else {
-            self.interval
-        };
+        self
+    }
 
-        // loop over the intervals
-        while self.start < end {
-            // loop over the samples in the buffer
-            while next_sample
-                .map(|s| {
-                    is_left_of_buffer_edge(
-                        self.first_timestamp,
-                        &s.timestamp(),
-                        &(self.start + self.interval),
-                    )
-                })
-                .unwrap_or(false)
-            {
-                // next sample is not newer than the current interval
-                if let Some(s) = next_sample {
-                    // add the sample to the interval_buffer
-                    interval_buffer.push(s);
-                    // get the next sample
-                    next_sample = buffer_iter.next();
-                    // update the input_start and input_interval to adapt
-                    // the resampling interval to the input data
-                    if let Some(input_start) = self.input_start {
-                        if self.input_interval.is_none() {
-                            self.input_interval =
-                                Some((s.timestamp() - input_start).max(self.interval));
-                        }
-                    }
-                }
+    /// Sets the timezone that calendar intervals are aligned in, e.g. to
+    /// resample to local days, which are 23 or 25 hours long on DST
+    /// transitions. Defaults to UTC. Fixed intervals are always aligned to
+    /// the Unix epoch.
+    #[cfg(feature = "chrono-tz")]
+    pub fn with_timezone(mut self, timezone: chrono_tz::Tz) -> Self {
+        self.timezone = Some(timezone);
+        if let Some(calendar) = self.calendar_interval {
+            self.start = self.calendar_align(calendar, self.start);
+            self.update_calendar_interval();
+        }
+        self
+    }
+
+    /// Aligns fixed intervals to the given timestamp instead of the Unix
+    /// epoch, e.g. to a contract start at 00:07:30. The start of the
+    /// resampling is realigned accordingly.
+    pub fn with_alignment_timestamp(mut self, alignment_timestamp: DateTime<Utc>) -> Self {
+        self.alignment_timestamp = Some(alignment_timestamp);
+        if self.calendar_interval.is_none() {
+            self.start = self.epoch_align(self.start);
+        }
+        self
+    }
+
+    /// Sets the position of the resampled timestamp within its window, e.g.
+    /// [`Center`][LabelPosition::Center] to label buckets by their midpoint.
+    /// Defaults to the start of the window if `first_timestamp` is `true` and
+    /// to its end otherwise. Which samples belong to a window still follows
+    /// `first_timestamp`, see [`with_closed`][Self::with_closed].
+    pub fn with_label_position(mut self, label_position: LabelPosition) -> Self {
+        self.label_position = Some(label_position);
+        self
+    }
+
+    /// Sets the side of its interval that a window includes, independent of
+    /// the label position. Defaults to the left side if `first_timestamp` is
+    /// `true` and to the right side otherwise.
+    pub fn with_closed(mut self, closed: Closed) -> Self {
+        self.closed = Some(closed);
+        self
+    }
+
+    /// Pre-allocates the buffer for the given number of samples, e.g. the
+    /// number of samples expected between two resamplings, to avoid repeated
+    /// reallocations.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.buffer.reserve(capacity);
+        self
+    }
+
+    /// Sets how the windows of samples that are resampled together are
+    /// delimited, see [`WindowSpec`].
+    pub fn with_window_spec(mut self, window_spec: WindowSpec) -> Self {
+        match window_spec {
+            WindowSpec::Time(interval) => {
+                self.interval = interval;
+                self.start = self.epoch_align(self.start);
+                self.calendar_interval = None;
+                self.count_window = None;
+                self.max_window_count = None;
+            }
+            WindowSpec::Count(count) => {
+                self.count_window = Some(count.max(1));
+                self.max_window_count = None;
             }
+            WindowSpec::TimeOrCount(interval, count) => {
+                self.interval = interval;
+                self.start = self.epoch_align(self.start);
+                self.calendar_interval = None;
+                self.count_window = None;
+                self.max_window_count = Some(count.max(1));
+            }
+        }
+        self
+    }
 
-            // Remove samples from interval_buffer that are older than
-            // max_age
-            let input_interval = self.input_interval.unwrap_or(self.interval);
-            let drain_end_date =
-                self.start + self.interval - input_interval * self.max_age_in_intervals;
-            interval_buffer.retain(|s| {
-                is_right_of_buffer_edge(self.first_timestamp, &s.timestamp(), &drain_end_date)
-            });
+    /// Sets the maximum age of the samples that are resampled as a duration,
+    /// relative to the end of their interval, e.g. to ignore samples older
+    /// than 30 seconds. It replaces `max_age_in_intervals`, which depends on
+    /// the resampling interval and on the detected interval of the input data.
+    pub fn with_max_age(mut self, max_age: TimeDelta) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
 
-            // resample the interval_buffer
-            res.push(Sample::new(
-                self.start + offset,
-                self.resampling_function.apply(interval_buffer.as_slice()),
-            ));
+    /// Sets how the interval of the input data is detected. Defaults to
+    /// [`Once`][InputIntervalDetection::Once].
+    pub fn with_input_interval_detection(mut self, detection: InputIntervalDetection) -> Self {
+        self.input_interval_detection = detection;
+        self
+    }
 
-            // Go to the next interval
-            self.start += self.interval;
+    /// Sets the time step between the starts of consecutive intervals, for
+    /// sliding windows that overlap, e.g. 5-minute intervals resampled every
+    /// minute. Each sample then contributes to multiple resampled samples.
+    /// The hop must be positive and should not exceed the interval. Defaults
+    /// to the interval.
+    pub fn with_hop(mut self, hop: TimeDelta) -> Self {
+        self.hop = Some(hop);
+        self
+    }
+
+    /// Sets the result of the [`Count`][ResamplingFunction::Count] resampling
+    /// function for intervals without any non-None samples.
+    pub fn with_empty_count(mut self, empty_count: EmptyCount) -> Self {
+        self.empty_count = empty_count;
+        self
+    }
+
+    /// Sets the maximum duration the last value is carried forward by the
+    /// [`ForwardFill`][GapPolicy::ForwardFill] gap policy, after which
+    /// intervals without samples are resampled to `None` again.
+    pub fn with_max_fill(mut self, max_fill: TimeDelta) -> Self {
+        self.max_fill = Some(max_fill);
+        self
+    }
+
+    /// Normalizes the results of the [`Sum`][ResamplingFunction::Sum] and
+    /// [`Count`][ResamplingFunction::Count] resampling functions to the given
+    /// reference duration, e.g. to get per-hour values regardless of the
+    /// resampling interval. As with [`Rate`][ResamplingFunction::Rate], only
+    /// the part of the interval covered by the input data is used.
+    pub fn with_reference_interval(mut self, reference_interval: TimeDelta) -> Self {
+        self.reference_interval = Some(reference_interval);
+        self
+    }
+
+    /// Sets whether resampled samples without a value are emitted. If set to
+    /// `false`, [`resample`][Self::resample] only returns samples with a
+    /// value. Defaults to `true`.
+    pub fn with_emit_empty_windows(mut self, emit_empty_windows: bool) -> Self {
+        self.emit_empty_windows = emit_empty_windows;
+        self
+    }
+
+    /// Sets how resampled samples are handled that are not newer than the
+    /// last emitted sample, to protect sinks that can't handle duplicates.
+    /// Samples passed to [`bootstrap`][Self::bootstrap] count as emitted.
+    pub fn with_duplicate_policy(mut self, duplicate_policy: DuplicatePolicy) -> Self {
+        self.duplicate_policy = duplicate_policy;
+        self
+    }
+
+    /// Sets when the resampled samples are produced, see [`EmissionPolicy`].
+    pub fn with_emission_policy(mut self, emission_policy: EmissionPolicy) -> Self {
+        self.emission_policy = emission_policy;
+        self
+    }
+
+    /// Sets whether the samples are aggregated as they are added instead of
+    /// being buffered, to keep the memory usage constant for high-frequency
+    /// input. Disabled by default, as the buffer then stays empty, so the
+    /// samples can't be inspected with [`buffer`][Self::buffer] and aren't
+    /// reported to the observer when they are pruned.
+    ///
+    /// This is only supported for the `Average`, `Sum`, `Count`, `Min` and
+    /// `Max` resampling functions, when every window only depends on its own
+    /// samples, i.e. without a maximum age beyond the window, hops, calendar
+    /// or count-based windows, outlier, quality or source handling, integer
+    /// arithmetic, interpolation, corrections or rolling input interval
+    /// detection. Otherwise, the samples are buffered as usual. As the
+    /// samples of a window can't be split afterwards, an interval that is
+    /// changed with [`set_interval`][Self::set_interval] only takes effect
+    /// once the windows with aggregated samples are resampled.
+    pub fn with_incremental_aggregation(mut self, incremental: bool) -> Self {
+        self.incremental_aggregation = incremental;
+        self
+    }
+
+    /// Sets a transform that is applied to every sample as it is added, before
+    /// any other processing, e.g. to clamp values or flip their sign. Samples
+    /// for which the transform returns `None` are dropped.
+    pub fn with_input_transform(mut self, transform: SampleTransform<S>) -> Self {
+        self.input_transform = Some(Transform(transform));
+        self
+    }
+
+    /// Sets a transform that is applied to every resampled sample before it is
+    /// emitted, after any other processing, e.g. to round or clamp values.
+    /// Samples for which the transform returns `None` are not emitted.
+    pub fn with_output_transform(mut self, transform: SampleTransform<S>) -> Self {
+        self.output_transform = Some(Transform(transform));
+        self
+    }
+
+    /// Sets a callback that is called for every added sample that the
+    /// resampler discards, together with the reason, e.g. to log, count or
+    /// persist them.
+    pub fn with_drop_callback(mut self, callback: DropCallback<S>) -> Self {
+        self.drop_callback = Some(DropFn(callback));
+        self
+    }
+
+    /// Sets a callback that is called with every resampled sample as its
+    /// window is emitted, in addition to it being returned, e.g. to send the
+    /// samples to a channel or writer as they are resampled.
+    pub fn with_window_callback(mut self, callback: WindowCallback<S>) -> Self {
+        self.window_callback = Some(WindowFn(callback));
+        self
+    }
+
+    /// Sets an observer that is notified of emitted windows, dropped samples
+    /// and pruning of the buffer, e.g. to record metrics.
+    pub fn with_observer(mut self, observer: Box<dyn ResamplerObserver<S>>) -> Self {
+        self.observer = Some(Observer(observer));
+        self
+    }
+
+    /// Sets the clock that is used by [`resample_now`][Self::resample_now]
+    /// instead of the system clock, e.g. for testing.
+    pub fn with_clock(mut self, clock: Clock) -> Self {
+        self.clock = Some(ClockFn(clock));
+        self
+    }
+
+    /// Uses the monotonic clock of the process for
+    /// [`resample_now`][Self::resample_now] instead of the system clock, for
+    /// samples that are stamped with [`Instant`][std::time::Instant]s, see
+    /// [`TimeAxis`]. The windows are then unaffected by steps of the wall
+    /// clock.
+    pub fn with_monotonic_clock(self) -> Self {
+        self.with_clock(Box::new(|| std::time::Instant::now().to_utc()))
+    }
+
+    /// Sets how [`resample_now`][Self::resample_now] handles the clock going
+    /// backwards.
+    pub fn with_clock_jump_policy(mut self, clock_jump_policy: ClockJumpPolicy) -> Self {
+        self.clock_jump_policy = clock_jump_policy;
+        self
+    }
+
+    /// Sets whether samples are rejected whose timestamp is not strictly after
+    /// the one of the previous sample, to detect upstream bugs in pipelines
+    /// that require ordered input. Rejected samples are dropped by
+    /// [`push`][Self::push] with a warning, and returned as an error by
+    /// [`try_push`][Self::try_push]. Defaults to `false`.
+    pub fn with_strict_ordering(mut self, strict_ordering: bool) -> Self {
+        self.strict_ordering = strict_ordering;
+        self
+    }
+
+    /// Sets how samples with a NaN value are handled when they are added.
+    /// Defaults to [`Propagate`][NanPolicy::Propagate].
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+
+    /// Sets how long windows are kept open for late samples after their end,
+    /// e.g. for network sources that deliver data a few seconds late. Windows
+    /// are then only resampled once the watermark, i.e. the end of the
+    /// resampling minus the allowed lateness, has passed their end, and
+    /// samples that arrive for windows that were already resampled are
+    /// dropped and counted as [`late_samples`][Self::late_samples].
+    pub fn with_allowed_lateness(mut self, allowed_lateness: TimeDelta) -> Self {
+        self.allowed_lateness = Some(allowed_lateness);
+        self
+    }
+
+    /// Sets how long windows that were already resampled are recomputed when
+    /// late samples arrive for them, see
+    /// [`with_allowed_lateness`][Self::with_allowed_lateness]. The recomputed
+    /// windows are emitted by the next resampling with the
+    /// [`correction`][ResampledWindow::correction] flag set, and late samples
+    /// within the horizon are not counted as
+    /// [`late_samples`][Self::late_samples]. Only time windows without a hop
+    /// are corrected, and corrections are not smoothed.
+    pub fn with_correction_horizon(mut self, correction_horizon: TimeDelta) -> Self {
+        self.correction_horizon = Some(correction_horizon);
+        self
+    }
+
+    /// Primes the resampler with samples it previously emitted, e.g. fetched
+    /// from a sink after a restart, so that the output continues seamlessly.
+    ///
+    /// The resampling continues after the last given sample and its value is
+    /// carried forward by the [`ForwardFill`][GapPolicy::ForwardFill] gap
+    /// policy until the buffer is refilled. The given samples are expected to
+    /// be sorted by timestamp.
+    pub fn bootstrap<I: IntoIterator<Item = S>>(&mut self, emitted: I) {
+        for sample in emitted {
+            let next_start = match self.calendar_interval {
+                Some(_) if self.label_position() == LabelPosition::Right => sample.timestamp(),
+                Some(calendar) => {
+                    let start = self.calendar_align(calendar, sample.timestamp());
+                    self.calendar_next(calendar, start)
+                }
+                None => sample.timestamp() - self.label_offset() + self.hop(),
+            };
+            self.start = self.start.max(next_start);
+            self.update_last_emitted(sample.timestamp());
+            if sample.value().is_some() {
+                let inverse = self.egress_conversion.map(|c| c.inverse());
+                self.last_resampled = Some(self.convert(sample, inverse));
+            }
         }
+    }
 
-        // Remove samples from buffer that are older than max_age
-        let interval = self.input_interval.unwrap_or(self.interval);
-        let drain_end_date = end - interval * self.max_age_in_intervals;
-        self.buffer.retain(|s| {
-            is_right_of_buffer_edge(self.first_timestamp, &s.timestamp(), &drain_end_date)
-        });
+    /// Adds a sample to the buffer, which is kept sorted by timestamp. In
+    /// strict ordering mode, samples that are not after the previous sample
+    /// are dropped and a warning is logged.
+    pub fn push(&mut self, sample: S) {
+        if let Err(e) = self.try_push(sample) {
+            warn!("{e}");
+        }
+    }
+
+    /// Adds a sample to the buffer like [`push`][Self::push], but returns an
+    /// error in strict ordering mode if the sample is not after the previous
+    /// sample, or if its value is NaN and the [`NanPolicy`] is `Error`.
+    pub fn try_push(&mut self, sample: S) -> Result<(), ResampleError> {
+        let timestamp = sample.timestamp();
+        self.buffer_sample(sample)?;
+        if self.emission_policy == EmissionPolicy::OnPush {
+            self.resample_completed(timestamp);
+        }
+        Ok(())
+    }
+
+    /// Adds a sample to the buffer like [`push`][Self::push], and resamples
+    /// and returns the windows that end before it regardless of the
+    /// [`EmissionPolicy`], see [`EmissionPolicy::OnPush`].
+    pub fn push_and_emit(&mut self, sample: S) -> Vec<S> {
+        let timestamp = sample.timestamp();
+        if let Err(e) = self.buffer_sample(sample) {
+            warn!("{e}");
+            return vec![];
+        }
+        self.resample_completed(timestamp)
+    }
 
+    /// Adds samples like [`push_and_emit`][Self::push_and_emit], and returns
+    /// the resampled samples of all windows that end before them.
+    pub fn extend_and_emit<I: IntoIterator<Item = S>>(&mut self, samples: I) -> Vec<S> {
+        let mut res = vec![];
+        for sample in samples {
+            res.extend(self.push_and_emit(sample));
+        }
         res
     }
 
-    /// Resamples the samples in the buffer and returns the resampled samples
-    /// until now.
-    pub fn resample_now(&mut self) -> Vec<S> {
-        self.resample(Utc::now())
+    /// Resamples the windows that end before the given timestamp of an added
+    /// sample, or after the allowed lateness before it, and returns the
+    /// resampled samples.
+    pub(crate) fn resample_completed(&mut self, timestamp: DateTime<Utc>) -> Vec<S> {
+        if self.count_window.is_some() {
+            return vec![];
+        }
+        let allowed_lateness = self.allowed_lateness.unwrap_or_default();
+        let mut res = vec![];
+        loop {
+            let window_end = self.next_window_end();
+            if !is_right_of_buffer_edge(self.closed(), &(timestamp - allowed_lateness), &window_end)
+            {
+                break;
+            }
+            res.extend(self.resample(window_end + allowed_lateness));
+            if self.next_window_end() <= window_end {
+                break;
+            }
+        }
+        res
     }
-}
 
-impl<
-        T: Div<Output = T> + std::iter::Sum + PartialOrd + FromPrimitive + Default + Debug,
-        S: Sample<Value = T>,
-    > Extend<S> for Resampler<T, S>
-{
-    fn extend<I: IntoIterator<Item = S>>(&mut self, iter: I) {
-        self.buffer.extend(iter);
+    /// Processes an added sample and adds it to the sorted buffer, unless it
+    /// is dropped or aggregated incrementally.
+    fn buffer_sample(&mut self, sample: S) -> Result<(), ResampleError> {
+        if let Some(sample) = self.ingest(sample)? {
+            // keep the buffer sorted, so that the windows can be found by
+            // binary search
+            match self.buffer.back() {
+                Some(last) if sample.timestamp() < last.timestamp() => {
+                    let index = self
+                        .buffer
+                        .partition_point(|s| s.timestamp() <= sample.timestamp());
+                    self.buffer.insert(index, sample);
+                }
+                _ => self.buffer.push_back(sample),
+            }
+            self.buffer_high_watermark = self.buffer_high_watermark.max(self.buffer.len());
+        }
+        Ok(())
     }
-}
 
-/// Aligns a timestamp to the epoch of the resampling interval.
-pub(crate) fn epoch_align(
-    interval: TimeDelta,
-    timestamp: DateTime<Utc>,
-    alignment_timestamp: Option<DateTime<Utc>>,
-) -> DateTime<Utc> {
-    let alignment_timestamp = alignment_timestamp.unwrap_or(DateTime::UNIX_EPOCH);
-    DateTime::from_timestamp_millis(
-        (timestamp.timestamp_millis() / interval.num_milliseconds()) * interval.num_milliseconds()
-            + alignment_timestamp.timestamp_millis(),
-    )
-    .unwrap_or(timestamp)
-}
+    /// Adds samples like [`extend`][Extend::extend], but merges them into the
+    /// buffer at once, in linear time if they are sorted by timestamp, e.g.
+    /// for a backfill. Unsorted samples are sorted first.
+    pub fn extend_sorted<I: IntoIterator<Item = S>>(&mut self, samples: I) {
+        let mut batch = vec![];
+        for sample in samples {
+            match self.ingest(sample) {
+                Ok(Some(sample)) => batch.push(sample),
+                Ok(None) => {}
+                Err(e) => warn!("{e}"),
+            }
+        }
+        if !batch.is_sorted_by_key(|s| s.timestamp()) {
+            batch.sort_by_key(|s| s.timestamp());
+        }
+        let last = batch.last().map(|s| s.timestamp());
+        merge_sorted(&mut self.buffer, batch);
+        self.buffer_high_watermark = self.buffer_high_watermark.max(self.buffer.len());
+        if let (Some(last), EmissionPolicy::OnPush) = (last, self.emission_policy) {
+            self.resample_completed(last);
+        }
+    }
 
-fn is_left_of_buffer_edge(
-    first_timestamp: bool,
-    timestamp: &DateTime<Utc>,
-    edge_timestamp: &DateTime<Utc>,
-) -> bool {
-    if first_timestamp {
-        timestamp < edge_timestamp
-    } else {
-        timestamp <= edge_timestamp
+    /// Merges the state of the given resampler into this one, e.g. when two
+    /// redundant data paths for the same channel are reconciled. The buffered
+    /// samples and the partial aggregates of both resamplers are combined,
+    /// while the rest of the state, e.g. the statistics, is kept. Returns an
+    /// error if the resamplers are configured differently, see
+    /// [`fingerprint`][Self::fingerprint].
+    pub fn merge(&mut self, other: Resampler<T, S>) -> Result<(), ResampleError> {
+        let (fingerprint, other_fingerprint) = (self.fingerprint(), other.fingerprint());
+        if fingerprint != other_fingerprint {
+            return Err(ResampleError::IncompatibleConfiguration {
+                fingerprint,
+                other: other_fingerprint,
+            });
+        }
+        merge_sorted(&mut self.buffer, other.buffer);
+        merge_sorted(&mut self.history, other.history);
+        for (window_start, accumulator) in other.accumulators {
+            self.accumulators
+                .entry(window_start)
+                .or_default()
+                .merge(&mut self.resampling_function, &accumulator);
+        }
+        self.pending_corrections.extend(other.pending_corrections);
+        if let Some(first_sample_at) = other.first_sample_at {
+            self.update_first_sample_at(first_sample_at);
+        }
+        self.buffer_high_watermark = self.buffer_high_watermark.max(self.buffer.len());
+        Ok(())
     }
-}
 
-fn is_right_of_buffer_edge(
-    first_timestamp: bool,
+    /// Processes an added sample up to the buffer, and returns it if it is to
+    /// be buffered, i.e. if it is neither dropped nor aggregated
+    /// incrementally.
+    fn ingest(&mut self, sample: S) -> Result<Option<S>, ResampleError> {
+        self.stats.samples_pushed += 1;
+        let sample = match &mut self.input_transform {
+            Some(Transform(transform)) => match transform(&sample) {
+                Some(transformed) => transformed,
+                None => {
+                    self.dropped(&sample, DropReason::Filtered);
+                    return Ok(None);
+                }
+            },
+            None => sample,
+        };
+        let sample = match self.nan_policy {
+            NanPolicy::Propagate => sample,
+            _ if !sample.value().is_some_and(|v| v.is_nan()) => sample,
+            NanPolicy::Ignore => S::new(sample.timestamp(), None),
+            NanPolicy::Error => {
+                self.dropped(&sample, DropReason::NotANumber);
+                return Err(ResampleError::NotANumber {
+                    timestamp: sample.timestamp(),
+                });
+            }
+        };
+        if let (Some(filter), Some(to_f64), Some(value)) =
+            (&self.validity_filter, self.to_f64, sample.value())
+        {
+            if to_f64(&value).is_some_and(|v| !filter.is_valid(v)) {
+                self.stats.invalid_samples += 1;
+                self.dropped(&sample, DropReason::Invalid);
+                return Ok(None);
+            }
+        }
+        if self.is_late(sample.timestamp()) {
+            match self.correction_horizon {
+                Some(horizon)
+                    if self.count_window.is_none()
+                        && is_right_of_buffer_edge(
+                            self.closed(),
+                            &sample.timestamp(),
+                            &(self.start - horizon),
+                        ) =>
+                {
+                    let sample = self.convert(sample, self.ingress_conversion);
+                    let index = self
+                        .history
+                        .partition_point(|s| s.timestamp() <= sample.timestamp());
+                    self.pending_corrections.push(sample.timestamp());
+                    self.history.insert(index, sample);
+                }
+                _ => {
+                    self.late_samples += 1;
+                    self.dropped(&sample, DropReason::Late);
+                }
+            }
+            return Ok(None);
+        }
+        if self.strict_ordering {
+            if let Some(previous) = self.last_pushed.filter(|p| sample.timestamp() <= *p) {
+                self.stats.out_of_order_samples += 1;
+                self.dropped(&sample, DropReason::OutOfOrder);
+                return Err(ResampleError::OutOfOrder {
+                    previous,
+                    timestamp: sample.timestamp(),
+                });
+            }
+            self.last_pushed = Some(sample.timestamp());
+        }
+        // expired samples are only kept as the anchors to interpolate or to
+        // find the nearest sample of later windows
+        if self.is_expired(sample.timestamp()) && !self.needs_anchors() {
+            self.stats.expired_samples += 1;
+            self.dropped(&sample, DropReason::Expired);
+            return Ok(None);
+        }
+        self.update_first_sample_at(sample.timestamp());
+        let sample = self.convert(sample, self.ingress_conversion);
+        let sample = match (&self.low_pass_filter, self.to_f64) {
+            (Some(filter), Some(to_f64)) => {
+                let value = sample
+                    .value()
+                    .and_then(|v| to_f64(&v))
+                    .map(|v| filter.filter(&mut self.filter_history, v));
+                S::new(sample.timestamp(), value.and_then(T::from_scalar))
+            }
+            _ => sample,
+        };
+        if self.is_incremental() {
+            let window_start = self.window_start_of(sample.timestamp());
+            self.accumulators
+                .entry(window_start)
+                .or_default()
+                .add(&mut self.resampling_function, &sample);
+            return Ok(None);
+        }
+        if self.correction_horizon.is_some() {
+            self.history.push_back(sample.clone());
+        }
+        self.update_input_interval(sample.timestamp());
+        Ok(Some(sample))
+    }
+
+    /// Re-detects the interval of the input data from the given timestamp of
+    /// an added sample, for the `RollingMedian` input interval detection.
+    fn update_input_interval(&mut self, timestamp: DateTime<Utc>) {
+        let InputIntervalDetection::RollingMedian(window) = self.input_interval_detection else {
+            return;
+        };
+        if let Some(delta) = self.last_input_at.map(|last| timestamp - last) {
+            if delta > TimeDelta::zero() {
+                self.input_deltas.push_back(delta);
+                while self.input_deltas.len() > window.max(1) {
+                    self.input_deltas.pop_front();
+                }
+                let mut deltas = self.input_deltas.iter().copied().collect::<Vec<_>>();
+                deltas.sort();
+                self.input_interval = Some(deltas[deltas.len() / 2].max(self.interval));
+            }
+        }
+        self.last_input_at = Some(self.last_input_at.map_or(timestamp, |t| t.max(timestamp)));
+    }
+
+    /// Returns whether a sample at the given timestamp arrived after its
+    /// window was finalized, i.e. after the watermark passed it.
+    fn is_late(&self, timestamp: DateTime<Utc>) -> bool {
+        let Some(watermark) = self.watermark else {
+            return false;
+        };
+        // the windows before the start of the resampling were finalized
+        let finalized = match self.count_window {
+            Some(_) => watermark,
+            None => self.start,
+        };
+        is_left_of_buffer_edge(self.closed(), &timestamp, &finalized)
+    }
+
+    /// Returns whether the samples are aggregated incrementally as they are
+    /// added instead of being buffered, which is the case for the resampling
+    /// functions that support it if each window only depends on its own
+    /// samples.
+    fn is_incremental(&self) -> bool {
+        self.incremental_aggregation
+            && Accumulator::supports(&self.resampling_function)
+            && self.max_age.is_none()
+            && self.max_age_in_intervals == 1
+            && self.hop() == self.interval
+            && self.calendar_interval.is_none()
+            && self.count_window.is_none()
+            && self.max_window_count.is_none()
+            && self.outlier_filter.is_none()
+            && self.interpolate == Interpolate::None
+            && self.gap_policy != GapPolicy::LinearInterpolate
+            && self.correction_horizon.is_none()
+            && self.source_priority.is_none()
+            && self.quality.is_none()
+            && self.source.is_none()
+            && self.integer_ops.is_none()
+            && !matches!(
+                self.input_interval_detection,
+                InputIntervalDetection::RollingMedian(_)
+            )
+    }
+
+    /// Returns the start of the window that a sample at the given timestamp,
+    /// which is not before the next window to resample, belongs to.
+    fn window_start_of(&self, timestamp: DateTime<Utc>) -> DateTime<Utc> {
+        let next_start = self.start + self.interval;
+        if is_left_of_buffer_edge(self.closed(), &timestamp, &next_start) {
+            return self.start;
+        }
+        let interval = self.pending_interval.unwrap_or(self.interval);
+        // the timestamp that is included in the same windows
+        let included = match self.closed() {
+            Closed::Left => timestamp,
+            Closed::Right => timestamp - TimeDelta::nanoseconds(1),
+        };
+        let interval = interval.num_nanoseconds().unwrap_or(i64::MAX).max(1);
+        let elapsed = (included - next_start)
+            .num_nanoseconds()
+            .unwrap_or(i64::MAX);
+        next_start + TimeDelta::nanoseconds(elapsed - elapsed % interval)
+    }
+
+    /// Returns the aggregate of the accumulated samples of the windows that
+    /// start before the given timestamp, and removes them if `take` is set.
+    fn accumulated(&mut self, until: DateTime<Utc>, take: bool) -> Accumulator<S> {
+        let mut accumulator = Accumulator::default();
+        if take {
+            while let Some(entry) = self.accumulators.first_entry() {
+                if *entry.key() >= until {
+                    break;
+                }
+                accumulator.merge(&mut self.resampling_function, &entry.remove());
+            }
+        } else {
+            for other in self.accumulators.range(..until).map(|(_, a)| a) {
+                accumulator.merge(&mut self.resampling_function, other);
+            }
+        }
+        accumulator
+    }
+
+    /// Returns the resampled value of the interval starting at
+    /// `window_start` from the aggregate of its samples.
+    fn accumulated_value(
+        &self,
+        accumulator: &Accumulator<S>,
+        window_start: DateTime<Utc>,
+    ) -> Option<T> {
+        if self.is_empty_count(accumulator.values) {
+            return None;
+        }
+        let value = accumulator.result(&self.resampling_function);
+        self.normalize(value, window_start)
+    }
+
+    /// Returns whether the resampled values can depend on samples outside of
+    /// their windows.
+    fn needs_anchors(&self) -> bool {
+        self.interpolate == Interpolate::Linear
+            || self.gap_policy == GapPolicy::LinearInterpolate
+            || matches!(self.resampling_function, ResamplingFunction::Nearest(_))
+    }
+
+    /// Returns whether a sample at the given timestamp is older than the
+    /// retention edge of the next window to resample.
+    fn is_expired(&self, timestamp: DateTime<Utc>) -> bool {
+        if self.count_window.is_some() {
+            return false;
+        }
+        let retention_edge = self.start + self.interval - self.max_age();
+        !is_right_of_buffer_edge(self.closed(), &timestamp, &retention_edge)
+    }
+
+    /// Calls the drop callback and notifies the observer of the given
+    /// discarded sample.
+    fn dropped(&mut self, sample: &S, reason: DropReason) {
+        if let Some(DropFn(callback)) = &mut self.drop_callback {
+            callback(sample, reason);
+        }
+        if let Some(Observer(observer)) = &mut self.observer {
+            observer.on_sample_dropped(sample, reason);
+        }
+    }
+
+    /// Notifies the observer that the given number of samples were removed
+    /// from the buffer.
+    fn pruned(&mut self, pruned: usize) {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(pruned, buffered = self.buffer.len(), "buffer pruned");
+        if let Some(Observer(observer)) = &mut self.observer {
+            observer.on_buffer_pruned(pruned, self.buffer.len());
+        }
+    }
+
+    /// Keeps track of the timestamp of the latest emitted sample.
+    fn update_last_emitted(&mut self, timestamp: DateTime<Utc>) {
+        self.last_emitted = Some(
+            self.last_emitted
+                .map_or(timestamp, |last| last.max(timestamp)),
+        );
+    }
+
+    /// Keeps track of the timestamp of the earliest sample that was added.
+    fn update_first_sample_at(&mut self, timestamp: DateTime<Utc>) {
+        self.first_sample_at = Some(
+            self.first_sample_at
+                .map_or(timestamp, |first| first.min(timestamp)),
+        );
+    }
+
+    /// Returns a reference to the buffer. It stays empty if the samples are
+    /// aggregated incrementally as they are added, which has to be enabled
+    /// with [`with_incremental_aggregation`][Self::with_incremental_aggregation].
+    pub fn buffer(&self) -> &VecDeque<S> {
+        &self.buffer
+    }
+
+    /// Returns the number of samples in the buffer.
+    pub fn buffer_len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns the number of samples that are waiting to be resampled, i.e.
+    /// the samples in the buffer and the samples that were added to the
+    /// aggregates of the incremental aggregation.
+    pub fn pending_len(&self) -> usize {
+        self.buffer.len() + self.accumulators.values().map(|a| a.samples).sum::<usize>()
+    }
+
+    /// Returns the highest number of samples in the buffer so far, e.g. to
+    /// monitor memory pressure.
+    pub fn buffer_high_watermark(&self) -> usize {
+        self.buffer_high_watermark
+    }
+
+    /// Returns the approximate number of bytes the resampler occupies, i.e.
+    /// its own size and the allocated capacity of its buffers. Memory that
+    /// is owned by the samples themselves, custom resampling functions or
+    /// transforms is not included.
+    pub fn approximate_memory_bytes(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + (self.buffer.capacity() + self.history.capacity()) * std::mem::size_of::<S>()
+            + self
+                .last_resampled
+                .as_ref()
+                .map_or(0, |_| std::mem::size_of::<S>())
+            + self.filter_history.capacity() * std::mem::size_of::<f64>()
+            + self.input_deltas.capacity() * std::mem::size_of::<TimeDelta>()
+            + self.pending_corrections.capacity() * std::mem::size_of::<DateTime<Utc>>()
+            + self.accumulators.len() * std::mem::size_of::<(DateTime<Utc>, Accumulator<S>)>()
+    }
+
+    /// Discards the samples in the buffer, e.g. because they are known to be
+    /// invalid.
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+        self.accumulators.clear();
+        self.history.clear();
+        self.pending_corrections.clear();
+    }
+
+    /// Restarts the resampling at the given start time, aligned to the
+    /// resampling interval, e.g. after a device reconnects. The buffer and the
+    /// state derived from previous samples, like the detected input interval
+    /// and the last resampled value, are discarded, while the configuration,
+    /// the statistics and the timestamp of the last emitted sample for the
+    /// [`DuplicatePolicy`] are kept.
+    pub fn reset(&mut self, start: DateTime<Utc>) {
+        self.clear();
+        if let Some(interval) = self.pending_interval.take() {
+            self.interval = interval;
+        }
+        self.start = match self.calendar_interval {
+            Some(calendar) => self.calendar_align(calendar, start),
+            None => self.epoch_align(start),
+        };
+        self.update_calendar_interval();
+        self.input_start = None;
+        self.input_interval = None;
+        self.last_input_at = None;
+        self.input_deltas.clear();
+        self.first_sample_at = None;
+        self.last_resampled = None;
+        self.filter_history.clear();
+        self.smoother_state = None;
+        self.last_pushed = None;
+        self.watermark = None;
+    }
+
+    /// Changes the resampling interval at runtime, e.g. when the reporting
+    /// cadence of a site changes, keeping the buffered samples. If the start
+    /// of the currently open window is not aligned to the new interval, the
+    /// window is shortened to end at the next aligned timestamp, so that no
+    /// period is skipped or resampled twice, and the new interval is used
+    /// from there on.
+    ///
+    /// With [incremental aggregation][Self::with_incremental_aggregation],
+    /// the change is deferred until the windows with aggregated samples are
+    /// resampled, as their samples can't be assigned to the new windows.
+    pub fn set_interval(&mut self, interval: TimeDelta) {
+        if !self.accumulators.is_empty() {
+            self.deferred_interval = Some(interval);
+            return;
+        }
+        self.deferred_interval = None;
+        self.calendar_interval = None;
+        self.input_interval = None;
+        let aligned = epoch_align(interval, self.start, self.alignment_timestamp);
+        if aligned == self.start {
+            self.interval = interval;
+            self.pending_interval = None;
+        } else {
+            self.interval = aligned + interval - self.start;
+            self.pending_interval = Some(interval);
+        }
+    }
+
+    /// Reserves capacity for at least `additional` more samples in the
+    /// buffer, e.g. before extending it with a large batch.
+    pub fn reserve(&mut self, additional: usize) {
+        self.buffer.reserve(additional);
+    }
+
+    /// Resamples the samples in the buffer and returns the resampled samples
+    /// until the given end time. If the end time is not after the start of
+    /// the next interval, nothing is resampled and a warning is logged.
+    pub fn resample(&mut self, end: DateTime<Utc>) -> Vec<S> {
+        self.resample_detailed(end)
+            .into_iter()
+            .map(|window| window.sample)
+            .collect()
+    }
+
+    /// Resamples the samples in the buffer like [`resample`][Self::resample],
+    /// but returns an error if the end time is not after the start of the
+    /// next interval.
+    pub fn try_resample(&mut self, end: DateTime<Utc>) -> Result<Vec<S>, ResampleError> {
+        Ok(self
+            .try_resample_detailed(end)?
+            .into_iter()
+            .map(|window| window.sample)
+            .collect())
+    }
+
+    /// Resamples the samples in the buffer like [`resample`][Self::resample],
+    /// but returns the resampled samples together with the fingerprint of the
+    /// configuration, so that consumers can detect configuration changes.
+    pub fn resample_batch(&mut self, end: DateTime<Utc>) -> ResampledBatch<S> {
+        ResampledBatch {
+            fingerprint: self.fingerprint(),
+            samples: self.resample(end),
+        }
+    }
+
+    /// Returns a fingerprint of the configuration of the resampler, i.e. a
+    /// hash of the version of this crate and of all settings that affect the
+    /// resampled values. The fingerprint is stable across processes, but custom
+    /// resampling functions and transforms are only distinguished by kind.
+    pub fn fingerprint(&self) -> u64 {
+        let config = format!(
+            "{}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{}|{:?}|{:?}|{}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{}|{}|{}",
+            env!("CARGO_PKG_VERSION"),
+            self.calendar_interval
+                .unwrap_or(ResamplingInterval::Fixed(self.interval)),
+            self.alignment_timestamp,
+            self.count_window,
+            self.max_window_count,
+            self.hop,
+            self.resampling_function,
+            self.max_age_in_intervals,
+            self.max_age,
+            self.input_interval_detection,
+            self.first_timestamp,
+            self.label_position(),
+            self.closed(),
+            self.allowed_lateness,
+            self.correction_horizon,
+            self.empty_count,
+            self.interpolate,
+            self.gap_policy,
+            self.max_fill,
+            self.reference_interval,
+            self.emit_empty_windows,
+            self.low_pass_filter,
+            self.smoother,
+            self.outlier_filter,
+            self.ingress_conversion,
+            self.egress_conversion,
+            self.duplicate_policy,
+            self.input_transform.is_some(),
+            self.output_transform.is_some(),
+            self.source_priority.is_some(),
+        );
+        let config = format!(
+            "{config}|{:?}|{:?}|{:?}|{}|{}",
+            self.integer_ops.as_ref().map(|ops| ops.policy),
+            self.nan_policy,
+            self.validity_filter,
+            self.quality.is_some(),
+            self.source.is_some()
+        );
+        #[cfg(feature = "chrono-tz")]
+        let config = format!("{config}|{:?}", self.timezone);
+        #[cfg(feature = "simd")]
+        let config = format!("{config}|{}", self.simd.is_some());
+        // FNV-1a, as the hashers of the standard library are not guaranteed to
+        // be stable
+        config.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+        })
+    }
+
+    /// Resamples the samples in the buffer like [`resample`][Self::resample],
+    /// but returns each resampled sample together with the metadata of the
+    /// window it was aggregated from.
+    pub fn resample_detailed(&mut self, end: DateTime<Utc>) -> Vec<ResampledWindow<S>> {
+        self.try_resample_detailed(end).unwrap_or_else(|e| {
+            warn!("{e}");
+            vec![]
+        })
+    }
+
+    /// Resamples the samples in the buffer like
+    /// [`resample_detailed`][Self::resample_detailed], but returns an error if
+    /// the end time is not after the start of the next interval.
+    pub fn try_resample_detailed(
+        &mut self,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<ResampledWindow<S>>, ResampleError> {
+        let mut res = self.resample_corrections();
+        let end = match self.allowed_lateness {
+            Some(allowed_lateness) => {
+                let watermark = end - allowed_lateness;
+                self.watermark = Some(self.watermark.map_or(watermark, |w| w.max(watermark)));
+                if self.count_window.is_none() && self.start + self.interval > watermark {
+                    return Ok(res);
+                }
+                watermark
+            }
+            None => end,
+        };
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("resample", start = %self.start, %end).entered();
+        if let Some(count) = self.count_window {
+            return Ok(self.resample_count(end, count));
+        }
+        if self.start >= end {
+            if !res.is_empty() {
+                return Ok(res);
+            }
+            return Err(ResampleError::EndNotAfterStart {
+                start: self.start,
+                end,
+            });
+        }
+        let buffer = std::mem::take(&mut self.buffer);
+        let mut interval_buffer = vec![];
+        // the number of samples that belong to the windows so far
+        let mut consumed = 0;
+        self.input_start = buffer.front().map(|s| s.timestamp());
+        let closed = self.closed();
+        let incremental = self.is_incremental();
+
+        // loop over the intervals, up to the last one that starts its last hop
+        // before the end
+        loop {
+            self.update_calendar_interval();
+            if self.start + self.interval - self.hop() >= end
+                || (self.allowed_lateness.is_some() && self.start + self.interval > end)
+            {
+                break;
+            }
+            // find the samples of the interval within max_age in the sorted
+            // buffer
+            let window_end = self.start + self.interval;
+            let drain_end_date = window_end - self.max_age();
+            let window_end_index = buffer
+                .partition_point(|s| is_left_of_buffer_edge(closed, &s.timestamp(), &window_end));
+            let window_start_index = buffer.partition_point(|s| {
+                !is_right_of_buffer_edge(closed, &s.timestamp(), &drain_end_date)
+            });
+            if window_end_index > consumed {
+                // update the input_interval to adapt the resampling interval
+                // to the input data
+                if let Some(input_start) = self.input_start {
+                    if self.input_interval.is_none()
+                        && self.input_interval_detection == InputIntervalDetection::Once
+                    {
+                        self.input_interval =
+                            Some((buffer[consumed].timestamp() - input_start).max(self.interval));
+                    }
+                }
+                consumed = window_end_index;
+            }
+            let accumulator = incremental.then(|| self.accumulated(window_end, true));
+            interval_buffer.clear();
+            interval_buffer
+                .extend(buffer.range(window_start_index.min(window_end_index)..window_end_index));
+
+            let duplicate = self
+                .last_emitted
+                .is_some_and(|last| self.start + self.label_offset() <= last);
+            if duplicate && self.duplicate_policy == DuplicatePolicy::Suppress {
+                self.start += self.hop();
+                continue;
+            }
+            let duplicate = duplicate && self.duplicate_policy == DuplicatePolicy::Flag;
+
+            // resample the aggregate of the samples of the interval
+            if let Some(accumulator) = accumulator {
+                if accumulator.samples > 0
+                    && self.input_interval.is_none()
+                    && self.input_interval_detection == InputIntervalDetection::Once
+                {
+                    self.input_interval = Some(self.interval);
+                }
+                let mut sample = S::new(
+                    self.start + self.label_offset(),
+                    self.accumulated_value(&accumulator, self.start),
+                );
+                if sample.value().is_some() {
+                    self.last_resampled = Some(sample.clone());
+                } else if accumulator.values == 0 {
+                    sample = S::new(sample.timestamp(), self.fill_gap(&buffer, &sample));
+                }
+                let counts = WindowCounts {
+                    sample_count: accumulator.samples,
+                    none_count: accumulator.samples - accumulator.values,
+                    bad_count: 0,
+                    sources: vec![],
+                };
+                self.emit(&mut res, sample, self.start, window_end, counts, duplicate);
+                self.start += self.hop();
+                continue;
+            }
+
+            // resample the interval_buffer
+            let mut bad_count = self.reject_bad_quality(&mut interval_buffer);
+            self.select_source(&mut interval_buffer);
+            let rejected = self.reject_outliers(&mut interval_buffer);
+            self.rejected_outliers += rejected as u64;
+
+            // close the window early for every `max_window_count` samples
+            let mut window_start = self.start;
+            let mut window_samples = interval_buffer.as_slice();
+            if let Some(max_count) = self.max_window_count {
+                while window_samples.len() >= max_count {
+                    let (chunk, rest) = window_samples.split_at(max_count);
+                    let last = chunk[max_count - 1].timestamp();
+                    let label = self.label_position().label(chunk[0].timestamp(), last);
+                    let sample = S::new(label, self.apply_function(chunk));
+                    if sample.value().is_some() {
+                        self.last_resampled = Some(sample.clone());
+                    }
+                    let mut counts = sample_counts(chunk, std::mem::take(&mut bad_count));
+                    counts.sources = self.source_aggregates(chunk, label, None);
+                    self.emit(&mut res, sample, window_start, last, counts, duplicate);
+                    window_start = last;
+                    window_samples = rest;
+                }
+                if window_start != self.start && window_samples.is_empty() {
+                    self.start += self.hop();
+                    continue;
+                }
+            }
+
+            let mut sample = S::new(
+                self.start + self.label_offset(),
+                self.window_value(&buffer, window_samples, self.start),
+            );
+            if sample.value().is_some() {
+                self.last_resampled = Some(sample.clone());
+            } else if window_samples.iter().all(|s| s.value().is_none()) {
+                sample = S::new(sample.timestamp(), self.fill_gap(&buffer, &sample));
+            }
+            let mut counts = sample_counts(window_samples, bad_count);
+            counts.sources =
+                self.source_aggregates(window_samples, sample.timestamp(), Some(self.start));
+            self.emit(
+                &mut res,
+                sample,
+                window_start,
+                window_end,
+                counts,
+                duplicate,
+            );
+
+            // Go to the next interval
+            self.start += self.hop();
+        }
+
+        // Remove samples from the front of the buffer that are older than
+        // max_age
+        self.buffer = buffer;
+        self.update_calendar_interval();
+        let drain_end_date = end - self.max_age();
+        let expired = self
+            .buffer
+            .partition_point(|s| !is_right_of_buffer_edge(closed, &s.timestamp(), &drain_end_date));
+        // keep the last sample with a value before the edge to interpolate from
+        let interpolation_start = match self.interpolate {
+            Interpolate::Linear => self
+                .buffer
+                .range(..expired)
+                .rposition(|s| s.value().is_some()),
+            Interpolate::None => None,
+        };
+        let kept = interpolation_start.and_then(|index| {
+            self.buffer.drain(..index);
+            self.buffer.pop_front()
+        });
+        self.buffer
+            .drain(..expired - interpolation_start.map_or(0, |index| index + 1));
+        let pruned = expired - usize::from(kept.is_some());
+        if let Some(sample) = kept {
+            self.buffer.push_front(sample);
+        }
+        self.pruned(pruned);
+        if let Some(horizon) = self.correction_horizon {
+            let history_start = self.start - horizon - self.max_age();
+            self.history
+                .retain(|s| is_right_of_buffer_edge(closed, &s.timestamp(), &history_start));
+        }
+        if let Some(interval) = self.deferred_interval {
+            self.set_interval(interval);
+        }
+
+        Ok(res)
+    }
+
+    /// Recomputes the windows that late samples arrived for since the last
+    /// resampling, and returns them as corrections.
+    fn resample_corrections(&mut self) -> Vec<ResampledWindow<S>> {
+        let mut timestamps = std::mem::take(&mut self.pending_corrections);
+        if timestamps.is_empty() {
+            return vec![];
+        }
+        let (start, interval) = (self.start, self.interval);
+        let history = std::mem::take(&mut self.history);
+        let closed = self.closed();
+        // the windows the late samples belong to, and the later windows they
+        // contribute to within `max_age_in_intervals`
+        let mut window_starts = vec![];
+        timestamps.sort();
+        for timestamp in timestamps {
+            // the start of the window that includes the timestamp
+            let included = match closed {
+                Closed::Left => timestamp,
+                Closed::Right => timestamp - TimeDelta::nanoseconds(1),
+            };
+            self.start = match self.calendar_interval {
+                Some(calendar) => self.calendar_align(calendar, included),
+                None => self.epoch_align(included),
+            };
+            while self.start < start {
+                self.update_calendar_interval();
+                let drain_end_date = self.start + self.interval - self.max_age();
+                if !is_right_of_buffer_edge(closed, &timestamp, &drain_end_date) {
+                    break;
+                }
+                window_starts.push(self.start);
+                self.start += self.interval;
+            }
+        }
+        window_starts.sort();
+        window_starts.dedup();
+
+        let mut res = vec![];
+        for window_start in window_starts {
+            self.start = window_start;
+            self.update_calendar_interval();
+            let window_end = self.start + self.interval;
+            let drain_end_date = window_end - self.max_age();
+            let mut samples = history
+                .iter()
+                .filter(|s| {
+                    is_left_of_buffer_edge(closed, &s.timestamp(), &window_end)
+                        && is_right_of_buffer_edge(closed, &s.timestamp(), &drain_end_date)
+                })
+                .collect::<Vec<_>>();
+            let bad_count = self.reject_bad_quality(&mut samples);
+            self.select_source(&mut samples);
+            self.reject_outliers(&mut samples);
+            let sample = S::new(
+                window_start + self.label_offset(),
+                self.window_value(&history, samples.as_slice(), window_start),
+            );
+            let sources = self.source_aggregates(&samples, sample.timestamp(), Some(window_start));
+            let sample = self.convert(sample, self.egress_conversion);
+            if let Some(sample) = self
+                .transform_output(sample)
+                .filter(|s| self.emit_empty_windows || s.value().is_some())
+            {
+                res.push(ResampledWindow {
+                    window_start,
+                    window_end,
+                    sample_count: samples.len(),
+                    none_count: samples.iter().filter(|s| s.value().is_none()).count(),
+                    bad_count,
+                    duplicate: false,
+                    correction: true,
+                    sample,
+                    sources,
+                });
+                self.window_emitted(&res);
+            }
+        }
+        self.history = history;
+        self.start = start;
+        self.interval = interval;
+        res
+    }
+
+    /// Returns the samples that [`resample`][Self::resample] would return for
+    /// the given end time, without advancing the resampling or pruning the
+    /// buffer, e.g. for a live preview of the current period.
+    ///
+    /// The state is taken before and restored after resampling, see
+    /// [`snapshot`][Self::snapshot], which copies the buffer, so every call
+    /// takes time and memory linear in the number of buffered samples. The
+    /// window callback and the observer are not called. Custom resampling
+    /// functions and output transforms are called like by
+    /// [`resample`][Self::resample], so their own state can be affected. To
+    /// only inspect the open window,
+    /// [`current_window`][Self::current_window] is cheaper.
+    pub fn peek_resample(&mut self, end: DateTime<Utc>) -> Vec<S> {
+        let snapshot = self.snapshot();
+        let observer = self.observer.take();
+        let window_callback = self.window_callback.take();
+        let res = self.resample(end);
+        self.observer = observer;
+        self.window_callback = window_callback;
+        self.restore(snapshot);
+        res
+    }
+
+    /// Returns the state of the resampler, i.e. the buffered samples and
+    /// everything derived from previous samples, e.g. to persist it for crash
+    /// recovery. The configuration is not part of the state.
+    pub fn snapshot(&self) -> ResamplerState<S> {
+        ResamplerState {
+            version: STATE_VERSION,
+            buffer: self.buffer.iter().cloned().collect(),
+            buffer_high_watermark: self.buffer_high_watermark,
+            accumulators: self.accumulators.clone(),
+            start: self.start,
+            interval: self.interval,
+            pending_interval: self.pending_interval,
+            deferred_interval: self.deferred_interval,
+            input_start: self.input_start,
+            input_interval: self.input_interval,
+            last_input_at: self.last_input_at,
+            input_deltas: self.input_deltas.clone(),
+            first_sample_at: self.first_sample_at,
+            last_resampled: self.last_resampled.clone(),
+            filter_history: self.filter_history.clone(),
+            smoother_state: self.smoother_state,
+            rejected_outliers: self.rejected_outliers,
+            late_samples: self.late_samples,
+            stats: self.stats,
+            last_emitted: self.last_emitted,
+            last_now: self.last_now,
+            last_pushed: self.last_pushed,
+            watermark: self.watermark,
+            history: self.history.iter().cloned().collect(),
+            pending_corrections: self.pending_corrections.clone(),
+        }
+    }
+
+    /// Restores a state returned by [`snapshot`][Self::snapshot], e.g. after
+    /// a restart, so that the resampling continues where it left off. The
+    /// resampler is expected to be configured like the one the state was
+    /// taken from, see [`fingerprint`][Self::fingerprint]. States of older
+    /// versions are migrated, and a state of a newer version is not restored
+    /// and a warning is logged, see [`try_restore`][Self::try_restore].
+    pub fn restore(&mut self, state: ResamplerState<S>) {
+        if let Err(e) = self.try_restore(state) {
+            warn!("{e}");
+        }
+    }
+
+    /// Restores a state like [`restore`][Self::restore], but returns an error
+    /// if the state was taken by a newer version of this crate with another
+    /// layout of the state, see [`ResamplerState::version`]. States of older
+    /// versions are migrated to the current layout first.
+    pub fn try_restore(&mut self, state: ResamplerState<S>) -> Result<(), ResampleError> {
+        let mut state = state;
+        while state.version < STATE_VERSION {
+            let version = state.version;
+            state = state.migrate(version)?;
+        }
+        if state.version != STATE_VERSION {
+            return Err(ResampleError::UnsupportedStateVersion {
+                version: state.version,
+                supported: STATE_VERSION,
+            });
+        }
+        self.buffer = state.buffer.into();
+        self.buffer_high_watermark = state.buffer_high_watermark;
+        self.accumulators = state.accumulators;
+        self.start = state.start;
+        self.interval = state.interval;
+        self.pending_interval = state.pending_interval;
+        self.deferred_interval = state.deferred_interval;
+        self.input_start = state.input_start;
+        self.input_interval = state.input_interval;
+        self.last_input_at = state.last_input_at;
+        self.input_deltas = state.input_deltas;
+        self.first_sample_at = state.first_sample_at;
+        self.last_resampled = state.last_resampled;
+        self.filter_history = state.filter_history;
+        self.smoother_state = state.smoother_state;
+        self.rejected_outliers = state.rejected_outliers;
+        self.late_samples = state.late_samples;
+        self.stats = state.stats;
+        self.last_emitted = state.last_emitted;
+        self.last_now = state.last_now;
+        self.last_pushed = state.last_pushed;
+        self.watermark = state.watermark;
+        self.history = state.history.into();
+        self.pending_corrections = state.pending_corrections;
+        Ok(())
+    }
+
+    /// Returns the aggregate of the samples in the currently open interval,
+    /// i.e. the interval that would be resampled next, without finalizing it.
+    ///
+    /// The buffer and the resampling state are left untouched, so the value
+    /// can be computed repeatedly between calls to
+    /// [`resample`][Self::resample].
+    pub fn current_partial(&mut self) -> S {
+        self.current_window().sample
+    }
+
+    /// Returns the aggregate of the samples in the currently open interval
+    /// like [`current_partial`][Self::current_partial], together with the
+    /// bounds of the interval and the number of samples accumulated so far,
+    /// e.g. to show "so far this interval" values.
+    pub fn current_window(&mut self) -> ResampledWindow<S> {
+        self.update_calendar_interval();
+        let mut buffer = std::mem::take(&mut self.buffer);
+        let (sample, counts, window_start, window_end) = match self.count_window {
+            Some(count) => {
+                let window_len = count.min(buffer.len());
+                let window = &buffer.make_contiguous()[..window_len];
+                let (sample, samples, _, bad_count) = self.count_window_value(window);
+                let window_start = window.first().map_or(self.start, |s| s.timestamp());
+                let window_end = window.last().map_or(self.start, |s| s.timestamp());
+                let mut counts = sample_counts(&samples, bad_count);
+                counts.sources = self.source_aggregates(&samples, sample.timestamp(), None);
+                (sample, counts, window_start, window_end)
+            }
+            None if self.is_incremental() => {
+                let window_end = self.start + self.interval;
+                let accumulator = self.accumulated(window_end, false);
+                let mut sample = S::new(
+                    self.start + self.label_offset(),
+                    self.accumulated_value(&accumulator, self.start),
+                );
+                if sample.value().is_none() && accumulator.values == 0 {
+                    sample = S::new(sample.timestamp(), self.fill_gap(&buffer, &sample));
+                }
+                let counts = WindowCounts {
+                    sample_count: accumulator.samples,
+                    none_count: accumulator.samples - accumulator.values,
+                    bad_count: 0,
+                    sources: vec![],
+                };
+                (sample, counts, self.start, window_end)
+            }
+            None => {
+                let (sample, samples, bad_count) = self.partial_time_window_value(&buffer);
+                let mut counts = sample_counts(&samples, bad_count);
+                counts.sources =
+                    self.source_aggregates(&samples, sample.timestamp(), Some(self.start));
+                (sample, counts, self.start, self.start + self.interval)
+            }
+        };
+        self.buffer = buffer;
+        let sample = self.smooth(sample, &mut self.smoother_state.clone());
+        let sample = self.convert(sample, self.egress_conversion);
+        let timestamp = sample.timestamp();
+        let sample = self
+            .transform_output(sample)
+            .unwrap_or_else(|| S::new(timestamp, None));
+        ResampledWindow {
+            window_start,
+            window_end,
+            sample_count: counts.sample_count,
+            none_count: counts.none_count,
+            bad_count: counts.bad_count,
+            duplicate: false,
+            correction: false,
+            sample,
+            sources: counts.sources,
+        }
+    }
+
+    /// Smooths, converts and transforms the given resampled sample of the
+    /// window from `window_start` to `window_end`, and adds it to the given
+    /// resampled windows if it is to be emitted.
+    fn emit(
+        &mut self,
+        res: &mut Vec<ResampledWindow<S>>,
+        sample: S,
+        window_start: DateTime<Utc>,
+        window_end: DateTime<Utc>,
+        counts: WindowCounts<S>,
+        duplicate: bool,
+    ) {
+        let WindowCounts {
+            sample_count,
+            none_count,
+            bad_count,
+            sources,
+        } = counts;
+        #[cfg(feature = "tracing")]
+        tracing::trace!(
+            %window_start,
+            %window_end,
+            samples = sample_count,
+            "window resampled"
+        );
+        if none_count == sample_count {
+            self.stats.empty_windows += 1;
+        }
+        let mut smoother_state = self.smoother_state;
+        let sample = self.smooth(sample, &mut smoother_state);
+        self.smoother_state = smoother_state;
+        let sample = self.convert(sample, self.egress_conversion);
+        let sample = self
+            .transform_output(sample)
+            .filter(|s| self.emit_empty_windows || s.value().is_some());
+        if let Some(sample) = sample {
+            self.stats.windows_emitted += 1;
+            self.update_last_emitted(sample.timestamp());
+            res.push(ResampledWindow {
+                window_start,
+                window_end,
+                sample_count,
+                none_count,
+                bad_count,
+                duplicate,
+                correction: false,
+                sample,
+                sources,
+            });
+            self.window_emitted(res);
+        }
+    }
+
+    /// Notifies the observer and the window callback of the last of the given
+    /// emitted windows.
+    fn window_emitted(&mut self, res: &[ResampledWindow<S>]) {
+        let Some(window) = res.last() else {
+            return;
+        };
+        if let Some(Observer(observer)) = &mut self.observer {
+            observer.on_window_emitted(window);
+        }
+        if let Some(WindowFn(callback)) = &mut self.window_callback {
+            callback(window.sample.clone());
+        }
+    }
+
+    /// Returns the resampled sample of the currently open time interval, the
+    /// samples without outliers that it was aggregated from and the number of
+    /// excluded samples of bad quality.
+    fn partial_time_window_value<'a>(&mut self, buffer: &'a VecDeque<S>) -> (S, Vec<&'a S>, usize) {
+        let window_end = self.start + self.interval;
+        let drain_end_date = window_end - self.max_age();
+        let mut samples = buffer
+            .iter()
+            .filter(|s| {
+                is_left_of_buffer_edge(self.closed(), &s.timestamp(), &window_end)
+                    && is_right_of_buffer_edge(self.closed(), &s.timestamp(), &drain_end_date)
+            })
+            .collect::<Vec<_>>();
+        let bad_count = self.reject_bad_quality(&mut samples);
+        self.select_source(&mut samples);
+        self.reject_outliers(&mut samples);
+        let sample = S::new(
+            self.start + self.label_offset(),
+            self.window_value(buffer, samples.as_slice(), self.start),
+        );
+        if sample.value().is_none() && samples.iter().all(|s| s.value().is_none()) {
+            let sample = S::new(sample.timestamp(), self.fill_gap(buffer, &sample));
+            return (sample, samples, bad_count);
+        }
+        (sample, samples, bad_count)
+    }
+
+    /// Resamples the samples in the buffer in windows of the given number of
+    /// samples, for the windows that are complete until the given end time.
+    /// The resampled samples are labeled between the timestamps of the first
+    /// and last sample of their window.
+    fn resample_count(&mut self, end: DateTime<Utc>, count: usize) -> Vec<ResampledWindow<S>> {
+        let mut buffer = std::mem::take(&mut self.buffer);
+        let available =
+            buffer.partition_point(|s| is_left_of_buffer_edge(self.closed(), &s.timestamp(), &end));
+        let complete = available - available % count;
+        let mut res = vec![];
+        for window in buffer.make_contiguous()[..complete].chunks(count) {
+            let (sample, samples, rejected, bad_count) = self.count_window_value(window);
+            self.rejected_outliers += rejected as u64;
+            let (window_start, window_end) = (window[0].timestamp(), window[count - 1].timestamp());
+            let mut counts = sample_counts(&samples, bad_count);
+            counts.sources = self.source_aggregates(&samples, sample.timestamp(), None);
+            self.emit(&mut res, sample, window_start, window_end, counts, false);
+        }
+        self.buffer = buffer;
+        self.buffer.drain(..complete);
+        self.pruned(complete);
+        res
+    }
+
+    /// Returns the resampled sample of the given window of a count-based
+    /// resampler, the samples without outliers that it was aggregated from,
+    /// the number of rejected outliers and the number of excluded samples of
+    /// bad quality.
+    fn count_window_value<'a>(&mut self, window: &'a [S]) -> (S, Vec<&'a S>, usize, usize) {
+        let mut samples = window.iter().collect::<Vec<_>>();
+        let bad_count = self.reject_bad_quality(&mut samples);
+        self.select_source(&mut samples);
+        let rejected = self.reject_outliers(&mut samples);
+        let timestamp = match (window.first(), window.last()) {
+            (Some(first), Some(last)) => self
+                .label_position()
+                .label(first.timestamp(), last.timestamp()),
+            _ => self.start,
+        };
+        let value = self.apply_function(samples.as_slice());
+        (S::new(timestamp, value), samples, rejected, bad_count)
+    }
+
+    /// Applies the output transform to the given resampled sample.
+    fn transform_output(&mut self, sample: S) -> Option<S> {
+        match &mut self.output_transform {
+            Some(Transform(transform)) => transform(&sample),
+            None => Some(sample),
+        }
+    }
+
+    /// Removes the samples of bad quality from the given samples of an
+    /// interval, and returns their number, see
+    /// [`with_quality_filter`][Self::with_quality_filter].
+    fn reject_bad_quality(&self, samples: &mut Vec<&S>) -> usize {
+        let Some(is_good) = self.quality else {
+            return 0;
+        };
+        let len = samples.len();
+        samples.retain(|s| is_good(s));
+        len - samples.len()
+    }
+
+    /// Returns the aggregates of the given samples of a window per source for
+    /// a resampled sample at the given timestamp, see
+    /// [`with_source_attribution`][Self::with_source_attribution]. The
+    /// samples are aggregated like the samples of the time window starting at
+    /// `window_start`, or like the samples of a count window if it is None.
+    fn source_aggregates(
+        &mut self,
+        samples: &[&S],
+        timestamp: DateTime<Utc>,
+        window_start: Option<DateTime<Utc>>,
+    ) -> Vec<SourceAggregate<S>> {
+        let Some(source) = self.source else {
+            return vec![];
+        };
+        let mut by_source = BTreeMap::<u64, Vec<&S>>::new();
+        for sample in samples {
+            by_source.entry(source(sample)).or_default().push(sample);
+        }
+        by_source
+            .into_iter()
+            .map(|(source, samples)| {
+                let value = match window_start {
+                    Some(window_start) => self.aggregate(&samples, window_start),
+                    None => self.apply_function(&samples),
+                };
+                let sample = self.convert(S::new(timestamp, value), self.egress_conversion);
+                SourceAggregate {
+                    source,
+                    sample_count: samples.len(),
+                    sample,
+                }
+            })
+            .collect()
+    }
+
+    /// Keeps only the samples of the highest-priority source among the given
+    /// samples of an interval that has a value, see
+    /// [`with_source_priority`][Self::with_source_priority].
+    fn select_source(&self, samples: &mut Vec<&S>) {
+        let Some(PriorityFn(priority)) = &self.source_priority else {
+            return;
+        };
+        let best = samples
+            .iter()
+            .filter(|s| s.value().is_some())
+            .map(|s| priority(s))
+            .min();
+        if let Some(best) = best {
+            samples.retain(|s| priority(s) == best);
+        }
+    }
+
+    /// Removes the outliers from the given samples of an interval, and returns
+    /// the number of rejected outliers. Samples without a value are kept.
+    fn reject_outliers(&self, samples: &mut Vec<&S>) -> usize {
+        let (Some(filter), Some(to_f64)) = (self.outlier_filter, self.to_f64) else {
+            return 0;
+        };
+        let values = samples
+            .iter()
+            .filter_map(|s| s.value().and_then(|v| to_f64(&v)))
+            .collect::<Vec<_>>();
+        let mut outliers = filter.outliers(&values).into_iter();
+        let len = samples.len();
+        samples.retain(|s| {
+            let is_outlier =
+                s.value().and_then(|v| to_f64(&v)).is_some() && outliers.next().unwrap_or(false);
+            !is_outlier
+        });
+        len - samples.len()
+    }
+
+    /// Returns the detected interval of the input data, if any, see
+    /// [`InputIntervalDetection`]. It is at least the resampling interval.
+    pub fn input_interval(&self) -> Option<TimeDelta> {
+        self.input_interval
+    }
+
+    /// Returns the start of the next window to resample.
+    pub fn window_start(&self) -> DateTime<Utc> {
+        self.start
+    }
+
+    /// Returns the end of the next window to resample, e.g. to schedule the
+    /// next call to [`resample`][Self::resample]. With an allowed lateness,
+    /// the window is resampled once the end of the resampling is the allowed
+    /// lateness past it.
+    pub fn next_window_end(&self) -> DateTime<Utc> {
+        match self.calendar_interval {
+            Some(calendar) => self.calendar_next(calendar, self.start),
+            None => self.start + self.interval,
+        }
+    }
+
+    /// Returns the time from which on [`resample`][Self::resample] finalizes
+    /// the next window, or None for count-based windows, which are complete
+    /// once enough samples were added.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn next_resample_at(&self) -> Option<DateTime<Utc>> {
+        self.count_window
+            .is_none()
+            .then(|| self.next_window_end() + self.allowed_lateness.unwrap_or_default())
+    }
+
+    /// Returns the number of samples that were rejected as outliers by the
+    /// outlier filter so far.
+    pub fn rejected_outliers(&self) -> u64 {
+        self.rejected_outliers
+    }
+
+    /// Returns the counters of the samples and windows that were processed so
+    /// far.
+    pub fn stats(&self) -> ResamplerStats {
+        ResamplerStats {
+            late_samples: self.late_samples,
+            rejected_outliers: self.rejected_outliers,
+            ..self.stats
+        }
+    }
+
+    /// Returns the number of samples that were dropped so far because they
+    /// arrived after the watermark passed their window, see
+    /// [`with_allowed_lateness`][Self::with_allowed_lateness].
+    pub fn late_samples(&self) -> u64 {
+        self.late_samples
+    }
+
+    /// Applies the given unit conversion to the value of the given sample.
+    fn convert(&self, sample: S, conversion: Option<UnitConversion>) -> S {
+        match (conversion, self.to_f64, sample.value()) {
+            (Some(conversion), Some(to_f64), Some(value)) => {
+                let value = to_f64(&value).map(|v| conversion.convert(v));
+                S::new(sample.timestamp(), value.and_then(T::from_scalar))
+            }
+            _ => sample,
+        }
+    }
+
+    /// Applies the smoother to the given resampled sample, given the state of
+    /// the smoother after the previous sample.
+    fn smooth(&self, sample: S, state: &mut Option<SmootherState>) -> S {
+        match (self.smoother, self.to_f64, sample.value()) {
+            (Some(smoother), Some(to_f64), Some(value)) => {
+                let value = to_f64(&value).map(|v| smoother.smooth(state, v));
+                S::new(sample.timestamp(), value.and_then(T::from_scalar))
+            }
+            _ => sample,
+        }
+    }
+
+    /// Returns the state of the smoother after the last emitted sample, e.g.
+    /// to save it and restore it with
+    /// [`restore_smoother_state`][Self::restore_smoother_state] later.
+    pub fn smoother_state(&self) -> Option<SmootherState> {
+        self.smoother_state
+    }
+
+    /// Restores a previously saved state of the smoother.
+    pub fn restore_smoother_state(&mut self, state: SmootherState) {
+        self.smoother_state = Some(state);
+    }
+
+    /// Returns the resampled value of the interval starting at
+    /// `window_start`, given the buffer and the samples of the interval
+    /// within `max_age_in_intervals`.
+    fn window_value(
+        &mut self,
+        buffer: &VecDeque<S>,
+        samples: &[&S],
+        window_start: DateTime<Utc>,
+    ) -> Option<T> {
+        let timestamp = window_start + self.label_offset();
+        if let ResamplingFunction::Nearest(tolerance) = self.resampling_function {
+            let candidates = buffer
+                .iter()
+                .filter(|s| (s.timestamp() - timestamp).abs() <= tolerance)
+                .collect::<Vec<_>>();
+            return self
+                .resampling_function
+                .apply_at(candidates.as_slice(), timestamp);
+        }
+        let has_own_samples = samples
+            .iter()
+            .any(|s| is_right_of_buffer_edge(self.closed(), &s.timestamp(), &window_start));
+        if self.interpolate == Interpolate::Linear && !has_own_samples {
+            if let Some(value) = self.interpolate_at(buffer, timestamp) {
+                return Some(value);
+            }
+        }
+        self.aggregate(samples, window_start)
+    }
+
+    /// Returns the value of the given resampled sample of an interval without
+    /// any samples with a value, according to the gap policy.
+    fn fill_gap(&self, buffer: &VecDeque<S>, sample: &S) -> Option<T> {
+        match self.gap_policy {
+            GapPolicy::None => None,
+            GapPolicy::ForwardFill => self
+                .last_resampled
+                .as_ref()
+                .filter(|last| {
+                    self.max_fill
+                        .is_none_or(|max_fill| sample.timestamp() - last.timestamp() <= max_fill)
+                })
+                .and_then(|last| last.value()),
+            GapPolicy::ZeroFill => T::from_scalar(0.0),
+            GapPolicy::LinearInterpolate => self.interpolate_at(buffer, sample.timestamp()),
+        }
+    }
+
+    /// Linearly interpolates the value at the given timestamp between the
+    /// surrounding samples with a value in the given buffer.
+    fn interpolate_at(&self, buffer: &VecDeque<S>, timestamp: DateTime<Utc>) -> Option<T> {
+        let to_f64 = self.to_f64?;
+        let split = buffer.partition_point(|s| s.timestamp() <= timestamp);
+        let (t0, v0) = buffer.range(..split).rev().find_map(|s| {
+            s.value()
+                .and_then(|v| to_f64(&v))
+                .map(|v| (s.timestamp(), v))
+        })?;
+        let (t1, v1) = buffer.range(split..).find_map(|s| {
+            s.value()
+                .and_then(|v| to_f64(&v))
+                .map(|v| (s.timestamp(), v))
+        })?;
+        let elapsed = (timestamp - t0).as_seconds_f64();
+        let total = (t1 - t0).as_seconds_f64();
+        T::from_scalar(v0 + (v1 - v0) * elapsed / total)
+    }
+
+    /// Sets the interval to the duration of the current calendar interval, if
+    /// a calendar interval is configured, or to the interval set by
+    /// [`set_interval`][Self::set_interval] once the start is aligned to it.
+    fn update_calendar_interval(&mut self) {
+        if let Some(calendar) = self.calendar_interval {
+            self.interval = self.calendar_next(calendar, self.start) - self.start;
+        }
+        if let Some(interval) = self.pending_interval {
+            if epoch_align(interval, self.start, self.alignment_timestamp) == self.start {
+                self.interval = interval;
+                self.pending_interval = None;
+                // the input interval was detected in the shorter transitional
+                // window
+                self.input_interval = self.input_interval.map(|i| i.max(interval));
+            }
+        }
+    }
+
+    /// Aligns a timestamp to the fixed interval and the alignment timestamp.
+    fn epoch_align(&self, timestamp: DateTime<Utc>) -> DateTime<Utc> {
+        let interval = self.pending_interval.unwrap_or(self.interval);
+        epoch_align(interval, timestamp, self.alignment_timestamp)
+    }
+
+    /// Returns the start of the calendar interval that contains the given
+    /// timestamp, in the configured timezone.
+    fn calendar_align(
+        &self,
+        calendar: ResamplingInterval,
+        timestamp: DateTime<Utc>,
+    ) -> DateTime<Utc> {
+        #[cfg(feature = "chrono-tz")]
+        if let Some(tz) = self.timezone {
+            return calendar.align_in(timestamp, &tz);
+        }
+        calendar.align(timestamp)
+    }
+
+    /// Returns the start of the calendar interval after the one starting at
+    /// the given timestamp, in the configured timezone.
+    fn calendar_next(&self, calendar: ResamplingInterval, start: DateTime<Utc>) -> DateTime<Utc> {
+        #[cfg(feature = "chrono-tz")]
+        if let Some(tz) = self.timezone {
+            return calendar.next_in(start, &tz);
+        }
+        calendar.next(start)
+    }
+
+    /// Returns the maximum age of the samples that are resampled, relative to
+    /// the end of their interval.
+    fn max_age(&self) -> TimeDelta {
+        self.max_age
+            .unwrap_or_else(|| self.max_age_interval() * self.max_age_in_intervals)
+    }
+
+    /// Returns the interval that `max_age_in_intervals` refers to, i.e. the
+    /// interval of the input data if it is slower than the resampling
+    /// interval. For calendar intervals, it is the current interval.
+    fn max_age_interval(&self) -> TimeDelta {
+        match self.calendar_interval {
+            Some(_) => self.interval,
+            None => self.input_interval.unwrap_or(self.interval),
+        }
+    }
+
+    /// Returns the time step between the starts of consecutive intervals.
+    fn hop(&self) -> TimeDelta {
+        self.hop
+            .filter(|hop| *hop > TimeDelta::zero())
+            .unwrap_or(self.interval)
+    }
+
+    /// Returns the offset of the resampled timestamp from the start of its
+    /// interval.
+    fn label_offset(&self) -> TimeDelta {
+        self.label_position()
+            .label(self.start, self.start + self.interval)
+            - self.start
+    }
+
+    /// Returns the resampled timestamp of the window starting at the given
+    /// timestamp.
+    fn label_at(&self, start: DateTime<Utc>) -> DateTime<Utc> {
+        let end = match self.calendar_interval {
+            Some(calendar) => self.calendar_next(calendar, start),
+            None => start + self.interval,
+        };
+        self.label_position().label(start, end)
+    }
+
+    /// Returns the side of its interval that a window includes.
+    fn closed(&self) -> Closed {
+        self.closed.unwrap_or(if self.first_timestamp {
+            Closed::Left
+        } else {
+            Closed::Right
+        })
+    }
+
+    /// Returns the position of the resampled timestamp within its window.
+    fn label_position(&self) -> LabelPosition {
+        self.label_position.unwrap_or(if self.first_timestamp {
+            LabelPosition::Left
+        } else {
+            LabelPosition::Right
+        })
+    }
+
+    /// Applies the resampling function to the given samples of the interval
+    /// starting at `window_start`, taking the resampler configuration into
+    /// account.
+    fn aggregate(&mut self, samples: &[&S], window_start: DateTime<Utc>) -> Option<T> {
+        let closed = self.closed();
+        if let ResamplingFunction::CustomWithCarry(f) = &mut self.resampling_function {
+            let split = samples.partition_point(|s| {
+                !is_right_of_buffer_edge(closed, &s.timestamp(), &window_start)
+            });
+            return f.as_mut()(&samples[..split], &samples[split..]);
+        }
+        if self.is_empty_count(count_values(samples)) {
+            return None;
+        }
+        if let Some(value) = self.integer_aggregate(samples) {
+            return self.normalize(value, window_start);
+        }
+        #[cfg(feature = "simd")]
+        if let Some(value) = self.simd_aggregate(samples) {
+            return self.normalize(value, window_start);
+        }
+        let covered = self.covered(window_start);
+        let value = match self.resampling_function {
+            ResamplingFunction::SavitzkyGolay(_) => self
+                .resampling_function
+                .apply_at(samples, window_start + self.label_offset()),
+            _ => self.resampling_function.apply_over(samples, covered),
+        };
+        self.normalize(value, window_start)
+    }
+
+    /// Returns whether the result of a `Count` resampling function over the
+    /// given number of values is None, as configured by the empty count.
+    fn is_empty_count(&self, values: usize) -> bool {
+        matches!(
+            self.resampling_function,
+            ResamplingFunction::Count | ResamplingFunction::Rate(RateOf::Count)
+        ) && self.empty_count == EmptyCount::None
+            && values == 0
+    }
+
+    /// Returns the result of the `Sum` and `Average` resampling functions for
+    /// the given samples, computed with the integer arithmetic, or None if it
+    /// isn't configured or doesn't support the resampling function. The result
+    /// is None if the values don't fit into the accumulator.
+    fn integer_aggregate(&self, samples: &[&S]) -> Option<Option<T>> {
+        let ops = self.integer_ops.as_ref()?;
+        let average = match self.resampling_function {
+            ResamplingFunction::Sum => false,
+            ResamplingFunction::Average => true,
+            _ => return None,
+        };
+        let (mut sum, mut count) = (0i128, 0i128);
+        for value in samples.iter().filter_map(|s| s.value()) {
+            // only values above the range of the accumulator can't be
+            // converted, as it covers all signed integer types
+            let Some(next) = (ops.to_i128)(&value).and_then(|v| sum.checked_add(v)) else {
+                return Some(None);
+            };
+            sum = next;
+            count += 1;
+        }
+        if count == 0 {
+            return Some(None);
+        }
+        let value = if average { sum / count } else { sum };
+        Some((ops.from_i128)(value, ops.policy))
+    }
+
+    /// Applies the resampling function to the given samples, with the integer
+    /// arithmetic if it is configured.
+    fn apply_function(&mut self, samples: &[&S]) -> Option<T> {
+        if self.is_empty_count(count_values(samples)) {
+            return None;
+        }
+        match self.integer_aggregate(samples) {
+            Some(value) => value,
+            None => self.resampling_function.apply(samples),
+        }
+    }
+
+    /// Returns the result of the resampling function for the given samples,
+    /// computed by the SIMD fast path, or None if the fast path doesn't
+    /// support the resampling function or the values.
+    #[cfg(feature = "simd")]
+    fn simd_aggregate(&self, samples: &[&S]) -> Option<Option<T>> {
+        let simd_aggregate = self.simd?;
+        let aggregation = match self.resampling_function {
+            ResamplingFunction::Sum => Aggregation::Sum,
+            ResamplingFunction::Average => Aggregation::Average,
+            ResamplingFunction::Min => Aggregation::Min,
+            ResamplingFunction::Max => Aggregation::Max,
+            _ => return None,
+        };
+        Some(simd_aggregate(samples, aggregation)?.and_then(T::from_scalar))
+    }
+
+    /// Returns the part of the interval starting at `window_start` that is
+    /// covered by the input data, as only that part counts.
+    fn covered(&self, window_start: DateTime<Utc>) -> TimeDelta {
+        let covered_start = self
+            .first_sample_at
+            .map_or(window_start, |first| first.max(window_start));
+        window_start + self.interval - covered_start
+    }
+
+    /// Normalizes the result of the `Sum` and `Count` resampling functions for
+    /// the interval starting at `window_start` to the reference interval, if
+    /// any.
+    fn normalize(&self, value: Option<T>, window_start: DateTime<Utc>) -> Option<T> {
+        let covered = self.covered(window_start);
+        match (&self.resampling_function, self.reference_interval) {
+            (ResamplingFunction::Sum | ResamplingFunction::Count, Some(reference)) => {
+                if covered <= TimeDelta::zero() {
+                    return None;
+                }
+                let factor = covered.as_seconds_f64() / reference.as_seconds_f64();
+                value.and_then(|value| value.div_scalar(factor))
+            }
+            _ => value,
+        }
+    }
+
+    /// Returns the current time of the clock of the resampler, see
+    /// [`with_clock`][Self::with_clock].
+    pub fn now(&self) -> DateTime<Utc> {
+        self.clock
+            .as_ref()
+            .map_or_else(Utc::now, |clock| (clock.0)())
+    }
+
+    /// Resamples the samples in the buffer and returns the resampled samples
+    /// until now. If the clock went backwards, it is handled according to the
+    /// [`ClockJumpPolicy`], and errors are logged.
+    pub fn resample_now(&mut self) -> Vec<S> {
+        self.try_resample_now().unwrap_or_else(|e| {
+            warn!("{e}");
+            vec![]
+        })
+    }
+
+    /// Resamples the samples in the buffer like
+    /// [`resample_now`][Self::resample_now], but returns an error if the clock
+    /// went backwards and the [`ClockJumpPolicy`] is `Error`.
+    pub fn try_resample_now(&mut self) -> Result<Vec<S>, ResampleError> {
+        match self.checked_now()? {
+            Some(now) => Ok(self.resample(now)),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Resamples the windows that ended until the time of the clock like
+    /// [`resample_now`][Self::resample_now], but without the window that is
+    /// still in progress.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn resample_completed_now(&mut self) -> Vec<S> {
+        let now = match self.checked_now() {
+            Ok(Some(now)) => now,
+            Ok(None) => return vec![],
+            Err(e) => {
+                warn!("{e}");
+                return vec![];
+            }
+        };
+        if self.count_window.is_some() {
+            return self.resample(now);
+        }
+        let mut res = vec![];
+        while let Some(at) = self.next_resample_at().filter(|at| *at <= now) {
+            res.extend(self.resample(at));
+            if self.next_resample_at() <= Some(at) {
+                break;
+            }
+        }
+        res
+    }
+
+    /// Returns the time of the clock after handling a clock that went
+    /// backwards according to the [`ClockJumpPolicy`], or None if nothing
+    /// should be resampled until the clock caught up.
+    fn checked_now(&mut self) -> Result<Option<DateTime<Utc>>, ResampleError> {
+        let now = self.now();
+        match self.last_now {
+            Some(previous) if now < previous => match self.clock_jump_policy {
+                ClockJumpPolicy::Hold => return Ok(None),
+                ClockJumpPolicy::Realign => {
+                    let mut aligned = match self.calendar_interval {
+                        Some(calendar) => self.calendar_align(calendar, now),
+                        None => self.epoch_align(now),
+                    };
+                    // skip the windows that were already emitted
+                    while aligned < self.start
+                        && self
+                            .last_emitted
+                            .is_some_and(|last| self.label_at(aligned) <= last)
+                    {
+                        aligned = match self.calendar_interval {
+                            Some(calendar) => self.calendar_next(calendar, aligned),
+                            None => aligned + self.hop(),
+                        };
+                    }
+                    self.start = self.start.min(aligned);
+                }
+                ClockJumpPolicy::Error => {
+                    self.last_now = Some(now);
+                    return Err(ResampleError::ClockJump { previous, now });
+                }
+            },
+            _ => {}
+        }
+        self.last_now = Some(now);
+        Ok(Some(now))
+    }
+}
+
+impl<T: ResampleValue + ToPrimitive, S: Sample<Value = T>> Resampler<T, S> {
+    /// Sets how intervals without samples of their own are resampled, e.g.
+    /// to linearly interpolate between the input samples when upsampling.
+    pub fn with_interpolation(mut self, interpolate: Interpolate) -> Self {
+        self.interpolate = interpolate;
+        self.to_f64 = Some(|v| v.to_f64());
+        self
+    }
+
+    /// Sets the value of resampled samples for intervals without any samples
+    /// with a value.
+    pub fn with_gap_policy(mut self, gap_policy: GapPolicy) -> Self {
+        self.gap_policy = gap_policy;
+        self.to_f64 = Some(|v| v.to_f64());
+        self
+    }
+
+    /// Sets a smoother that is applied to the values of the resampled samples
+    /// before they are emitted, e.g. to reduce the jitter of noisy aggregates.
+    pub fn with_smoother(mut self, smoother: Smoother) -> Self {
+        self.smoother = Some(smoother);
+        self.smoother_state = None;
+        self.to_f64 = Some(|v| v.to_f64());
+        self
+    }
+
+    /// Sets a validity filter that rejects samples with implausible values as
+    /// they are added, after the input transform and before the unit
+    /// conversion. The number of rejected samples is available from
+    /// [`stats`][Self::stats].
+    pub fn with_validity_filter(mut self, filter: ValidityFilter) -> Self {
+        self.validity_filter = Some(filter);
+        self.to_f64 = Some(|v| v.to_f64());
+        self
+    }
+
+    /// Sets an outlier filter that is applied to the samples of each interval
+    /// before they are resampled. The number of rejected samples is available
+    /// from [`rejected_outliers`][Self::rejected_outliers].
+    pub fn with_outlier_filter(mut self, filter: OutlierFilter) -> Self {
+        self.outlier_filter = Some(filter);
+        self.to_f64 = Some(|v| v.to_f64());
+        self
+    }
+
+    /// Computes the `Sum`, `Average`, `Min` and `Max` resampling functions by
+    /// a SIMD fast path, e.g. for large windows of kHz inputs. The values are
+    /// aggregated as `f64`, so the results can differ in the last digits from
+    /// the generic path, which is used for all other resampling functions.
+    ///
+    /// The fast path only applies to buffered windows, whose values are
+    /// converted and aggregated in one pass without being copied. It doesn't
+    /// apply with the
+    /// [incremental aggregation][Self::with_incremental_aggregation], which
+    /// aggregates every sample as it is added, nor with the
+    /// [integer arithmetic][Self::with_overflow_policy], which takes
+    /// precedence. Whether it pays off depends on the value type and the
+    /// target, see the `resample_large_windows` benchmark.
+    #[cfg(feature = "simd")]
+    pub fn with_simd_aggregation(mut self) -> Self {
+        self.simd = Some(simd_aggregate::<T, S>);
+        self
+    }
+
+    /// Sets a unit conversion that is applied to the values of the samples as
+    /// they are added, before any other processing, e.g. to resample inputs
+    /// in kW from a device that reports in W.
+    pub fn with_ingress_conversion(mut self, conversion: UnitConversion) -> Self {
+        self.ingress_conversion = Some(conversion);
+        self.to_f64 = Some(|v| v.to_f64());
+        self
+    }
+
+    /// Sets a unit conversion that is applied to the values of the resampled
+    /// samples before they are emitted, e.g. to emit energy in kWh when it is
+    /// resampled in Wh. Samples passed to [`bootstrap`][Self::bootstrap] are
+    /// expected in the converted unit.
+    pub fn with_egress_conversion(mut self, conversion: UnitConversion) -> Self {
+        self.egress_conversion = Some(conversion);
+        self.to_f64 = Some(|v| v.to_f64());
+        self
+    }
+
+    /// Sets a low-pass filter that is applied to the values of the samples as
+    /// they are added, before they are resampled, to avoid aliasing when
+    /// resampling high frequency data to a slower interval. The samples are
+    /// expected to be added in chronological order.
+    pub fn with_low_pass_filter(mut self, filter: LowPassFilter) -> Self {
+        self.low_pass_filter = Some(filter);
+        self.filter_history.clear();
+        self.to_f64 = Some(|v| v.to_f64());
+        self
+    }
+}
+
+impl<T: ResampleValue + PrimInt, S: Sample<Value = T>> Resampler<T, S> {
+    /// Computes the `Sum` and `Average` resampling functions of integer values
+    /// with a 128-bit accumulator, so that they don't overflow while
+    /// aggregating. Sums that don't fit into the value type are handled
+    /// according to the given [`OverflowPolicy`], and averages always fit.
+    /// Intervals of `u128` values that add up to more than the accumulator
+    /// can hold are resampled to None with either policy.
+    pub fn with_overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.integer_ops = Some(IntegerOps {
+            policy,
+            to_i128: |v| v.to_i128(),
+            from_i128: |v, policy| match (T::from(v), policy) {
+                (Some(v), _) => Some(v),
+                (None, OverflowPolicy::Checked) => None,
+                (None, OverflowPolicy::Saturating) if v > 0 => Some(T::max_value()),
+                (None, OverflowPolicy::Saturating) => Some(T::min_value()),
+            },
+        });
+        self
+    }
+}
+
+impl<T: ResampleValue, S: QualitySample<Value = T>> Resampler<T, S> {
+    /// Excludes the samples of bad quality from the aggregation. The number of
+    /// excluded samples of each window is available from
+    /// [`resample_detailed`][Self::resample_detailed].
+    pub fn with_quality_filter(mut self) -> Self {
+        self.quality = Some(|s| s.is_good());
+        self
+    }
+}
+
+impl<T: ResampleValue, S: SourceSample<Value = T>> Resampler<T, S> {
+    /// Also aggregates the samples of every source of a window separately,
+    /// e.g. when a virtual channel merges the samples of several meters. The
+    /// aggregates are available from
+    /// [`resample_detailed`][Self::resample_detailed], with the unit
+    /// conversion but without the smoother and the output transform.
+    pub fn with_source_attribution(mut self) -> Self {
+        self.source = Some(|s| s.source());
+        self
+    }
+
+    /// Sets a function that returns the priority of a source ID, where lower
+    /// numbers are higher priority, e.g. for a measurement that is received
+    /// from a primary and a fallback gateway. In every window, only the
+    /// samples of the highest-priority source that produced a value are
+    /// resampled.
+    pub fn with_source_priority(mut self, priority: SourcePriority) -> Self {
+        self.source_priority = Some(PriorityFn(Box::new(move |s| priority(s.source()))));
+        self
+    }
+}
+
+impl<T: ResampleValue, S: Sample<Value = T>> Extend<S> for Resampler<T, S> {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.buffer.reserve(iter.size_hint().0);
+        for sample in iter {
+            self.push(sample);
+        }
+    }
+}
+
+/// Aligns a timestamp to the epoch of the resampling interval, or to the
+/// given alignment timestamp, i.e. returns the latest timestamp that is not
+/// after the given one and a whole number of intervals away from the epoch.
+pub(crate) fn epoch_align(
+    interval: TimeDelta,
+    timestamp: DateTime<Utc>,
+    alignment_timestamp: Option<DateTime<Utc>>,
+) -> DateTime<Utc> {
+    let alignment_millis = alignment_timestamp
+        .unwrap_or(DateTime::UNIX_EPOCH)
+        .timestamp_millis();
+    let interval_millis = interval.num_milliseconds();
+    if interval_millis <= 0 {
+        return timestamp;
+    }
+    let offset = (timestamp.timestamp_millis() - alignment_millis).rem_euclid(interval_millis);
+    DateTime::from_timestamp_millis(timestamp.timestamp_millis() - offset).unwrap_or(timestamp)
+}
+
+/// Merges the given samples, which are sorted by timestamp, into the sorted
+/// buffer in linear time.
+fn merge_sorted<S: Sample>(buffer: &mut VecDeque<S>, samples: impl IntoIterator<Item = S>) {
+    let mut samples = samples.into_iter().peekable();
+    let Some(first) = samples.peek() else {
+        return;
+    };
+    let split = buffer.partition_point(|s| s.timestamp() <= first.timestamp());
+    let mut tail = buffer.split_off(split).into_iter().peekable();
+    buffer.reserve(samples.size_hint().0 + tail.len());
+    for sample in samples {
+        while let Some(s) = tail.next_if(|s| s.timestamp() <= sample.timestamp()) {
+            buffer.push_back(s);
+        }
+        buffer.push_back(sample);
+    }
+    buffer.extend(tail);
+}
+
+/// Returns the number of the given samples, the number of them without a
+/// value and the given number of excluded samples of bad quality, without
+/// the aggregates of their sources.
+fn sample_counts<S: Sample>(samples: &[&S], bad_count: usize) -> WindowCounts<S> {
+    WindowCounts {
+        sample_count: samples.len(),
+        none_count: samples.iter().filter(|s| s.value().is_none()).count(),
+        bad_count,
+        sources: vec![],
+    }
+}
+
+/// Applies the given aggregation of the SIMD fast path to the values of the
+/// given samples as `f64`, or returns None if a value can't be converted. This
+/// is monomorphized for every value type, so that the conversion of the values
+/// is inlined into the aggregation instead of copying them.
+#[cfg(feature = "simd")]
+fn simd_aggregate<T: ToPrimitive, S: Sample<Value = T>>(
+    samples: &[&S],
+    aggregation: Aggregation,
+) -> Option<Option<f64>> {
+    let mut convertible = true;
+    let mut values = samples.iter().filter_map(|s| s.value()).map_while(|v| {
+        let value = v.to_f64();
+        convertible &= value.is_some();
+        value
+    });
+    let result = crate::simd::aggregate(&mut values, aggregation);
+    convertible.then_some(result)
+}
+
+/// Returns the number of the given samples with a value.
+fn count_values<S: Sample>(samples: &[&S]) -> usize {
+    samples.iter().filter(|s| s.value().is_some()).count()
+}
+
+fn is_left_of_buffer_edge(
+    closed: Closed,
+    timestamp: &DateTime<Utc>,
+    edge_timestamp: &DateTime<Utc>,
+) -> bool {
+    match closed {
+        Closed::Left => timestamp < edge_timestamp,
+        Closed::Right => timestamp <= edge_timestamp,
+    }
+}
+
+fn is_right_of_buffer_edge(
+    closed: Closed,
     timestamp: &DateTime<Utc>,
     edge_timestamp: &DateTime<Utc>,
 ) -> bool {
-    if first_timestamp {
-        timestamp >= edge_timestamp
-    } else {
-        timestamp > edge_timestamp
+    match closed {
+        Closed::Left => timestamp >= edge_timestamp,
+        Closed::Right => timestamp > edge_timestamp,
     }
 }
@@ -0,0 +1,131 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! The savitzky_golay module provides the Savitzky-Golay smoothing that can be
+//! used as a resampling function.
+
+use std::fmt::Debug;
+
+use chrono::{DateTime, Utc};
+use num_traits::ToPrimitive;
+
+use crate::value::ResampleValue;
+use crate::Sample;
+
+/// The SavitzkyGolay struct configures the
+/// [`SavitzkyGolay`][crate::ResamplingFunction::SavitzkyGolay] resampling
+/// function.
+///
+/// A polynomial of the given order is fitted to the last `window_length`
+/// samples with a value by least squares, and evaluated at the resampled
+/// timestamp.
+#[derive(Clone, Copy)]
+pub struct SavitzkyGolay<T> {
+    window_length: usize,
+    order: usize,
+    to_f64: fn(&T) -> Option<f64>,
+}
+
+impl<T: ToPrimitive> SavitzkyGolay<T> {
+    /// Creates a new Savitzky-Golay configuration with the given window length
+    /// in samples and polynomial order.
+    pub fn new(window_length: usize, order: usize) -> Self {
+        Self {
+            window_length,
+            order,
+            to_f64: |v| v.to_f64(),
+        }
+    }
+}
+
+impl<T> SavitzkyGolay<T> {
+    /// Returns the window length in samples.
+    pub fn window_length(&self) -> usize {
+        self.window_length
+    }
+
+    /// Returns the polynomial order.
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
+    /// Fits the polynomial to the given samples and evaluates it at the given
+    /// timestamp. If there are not enough samples for the configured order,
+    /// the order is reduced accordingly.
+    pub(crate) fn smooth_at<S: Sample<Value = T>>(
+        &self,
+        samples: &[&S],
+        timestamp: DateTime<Utc>,
+    ) -> Option<T>
+    where
+        T: ResampleValue,
+    {
+        let mut points = samples
+            .iter()
+            .rev()
+            .filter_map(|s| {
+                let x = (s.timestamp() - timestamp).as_seconds_f64();
+                s.value().and_then(|v| (self.to_f64)(&v)).map(|y| (x, y))
+            })
+            .take(self.window_length)
+            .collect::<Vec<_>>();
+        points.reverse();
+        let order = self.order.min(points.len().checked_sub(1)?);
+
+        // the value at the timestamp is the constant coefficient, as the
+        // timestamp is the origin of x
+        let coefficients = fit_polynomial(&points, order)?;
+        T::from_scalar(coefficients[0])
+    }
+}
+
+impl<T> Debug for SavitzkyGolay<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SavitzkyGolay")
+            .field("window_length", &self.window_length)
+            .field("order", &self.order)
+            .finish()
+    }
+}
+
+/// Fits a polynomial of the given order to the given points by least squares
+/// and returns its coefficients, starting with the constant one.
+fn fit_polynomial(points: &[(f64, f64)], order: usize) -> Option<Vec<f64>> {
+    let n = order + 1;
+    // the augmented matrix of the normal equations
+    let mut matrix = vec![vec![0.0; n + 1]; n];
+    for &(x, y) in points {
+        for (row, equation) in matrix.iter_mut().enumerate() {
+            for (col, entry) in equation.iter_mut().take(n).enumerate() {
+                *entry += x.powi((row + col) as i32);
+            }
+            equation[n] += y * x.powi(row as i32);
+        }
+    }
+
+    // Gaussian elimination with partial pivoting
+    for col in 0..n {
+        let pivot =
+            (col..n).max_by(|&a, &b| matrix[a][col].abs().total_cmp(&matrix[b][col].abs()))?;
+        if matrix[pivot][col].abs() < f64::EPSILON {
+            return None;
+        }
+        matrix.swap(col, pivot);
+        let (upper, lower) = matrix.split_at_mut(col + 1);
+        let pivot_row = &upper[col];
+        for row in lower.iter_mut() {
+            let factor = row[col] / pivot_row[col];
+            for (entry, pivot_entry) in row.iter_mut().zip(pivot_row).skip(col) {
+                *entry -= factor * pivot_entry;
+            }
+        }
+    }
+    let mut coefficients = vec![0.0; n];
+    for row in (0..n).rev() {
+        let rest = (row + 1..n)
+            .map(|col| matrix[row][col] * coefficients[col])
+            .sum::<f64>();
+        coefficients[row] = (matrix[row][n] - rest) / matrix[row][row];
+    }
+    Some(coefficients)
+}
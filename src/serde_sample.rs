@@ -0,0 +1,39 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! The serde_sample module provides the SerdeSample struct, a serializable
+//! implementation of the Sample trait.
+
+use std::fmt::Debug;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::resampler::Sample;
+
+/// The SerdeSample struct is a sample that can be serialized, e.g. to send
+/// resampled samples over the wire without a type that mirrors the sample
+/// type of the application.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct SerdeSample<T> {
+    /// The timestamp of the sample
+    pub timestamp: DateTime<Utc>,
+    /// The value of the sample
+    pub value: Option<T>,
+}
+
+impl<T: Clone + Debug + Default> Sample for SerdeSample<T> {
+    type Value = T;
+
+    fn new(timestamp: DateTime<Utc>, value: Option<T>) -> Self {
+        Self { timestamp, value }
+    }
+
+    fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    fn value(&self) -> Option<T> {
+        self.value.clone()
+    }
+}
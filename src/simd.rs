@@ -0,0 +1,82 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! The simd module provides aggregation functions for `f64` values that
+//! accumulate several independent lanes at once, so that the compiler can
+//! vectorize them. The values are read in chunks from an iterator, so that
+//! they don't have to be copied into a buffer first.
+
+/// The number of values that are accumulated at once.
+const LANES: usize = 8;
+
+/// The resampling functions that are computed by the SIMD fast path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Aggregation {
+    Sum,
+    Average,
+    Min,
+    Max,
+}
+
+/// Returns the given aggregation of the given values, or None if there are
+/// none.
+pub(crate) fn aggregate<I: IntoIterator<Item = f64>>(
+    values: I,
+    aggregation: Aggregation,
+) -> Option<f64> {
+    match aggregation {
+        Aggregation::Sum => sum(values).map(|(sum, _)| sum),
+        Aggregation::Average => sum(values).map(|(sum, count)| sum / count as f64),
+        Aggregation::Min => min(values),
+        Aggregation::Max => max(values),
+    }
+}
+
+/// Returns the sum of the given values and their number, or None if there are
+/// none.
+pub(crate) fn sum<I: IntoIterator<Item = f64>>(values: I) -> Option<(f64, usize)> {
+    fold(values, 0.0, |a, b| a + b)
+}
+
+/// Returns the minimum of the given values, ignoring NaN values unless all
+/// values are NaN, or None if there are none.
+pub(crate) fn min<I: IntoIterator<Item = f64>>(values: I) -> Option<f64> {
+    fold(values, f64::NAN, f64::min).map(|(min, _)| min)
+}
+
+/// Returns the maximum of the given values, ignoring NaN values unless all
+/// values are NaN, or None if there are none.
+pub(crate) fn max<I: IntoIterator<Item = f64>>(values: I) -> Option<f64> {
+    fold(values, f64::NAN, f64::max).map(|(max, _)| max)
+}
+
+/// Folds the given values with the given function, starting every lane with
+/// the given identity, and returns the result and the number of values.
+fn fold<I: IntoIterator<Item = f64>>(
+    values: I,
+    identity: f64,
+    f: fn(f64, f64) -> f64,
+) -> Option<(f64, usize)> {
+    let mut values = values.into_iter();
+    let mut lanes = [identity; LANES];
+    let mut count = 0;
+    loop {
+        let mut chunk = [identity; LANES];
+        let mut len = 0;
+        for (slot, value) in chunk.iter_mut().zip(values.by_ref()) {
+            *slot = value;
+            len += 1;
+        }
+        for (lane, value) in lanes.iter_mut().zip(chunk) {
+            *lane = f(*lane, value);
+        }
+        count += len;
+        if len < LANES {
+            break;
+        }
+    }
+    if count == 0 {
+        return None;
+    }
+    Some((lanes.into_iter().fold(identity, f), count))
+}
@@ -0,0 +1,105 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! The sink module provides the ResamplerSink struct that adds the samples of
+//! async pipelines to a resampler.
+
+use std::fmt::Debug;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use chrono::{DateTime, Utc};
+use futures_sink::Sink;
+
+use crate::error::ResampleError;
+use crate::resampler::{Resampler, Sample};
+use crate::value::ResampleValue;
+
+/// The ResamplerSink struct wraps a [`Resampler`] as a [`Sink`] of samples, so
+/// that async pipelines can be forwarded into it. Sent samples are added with
+/// [`try_push`][Resampler::try_push], and flushing the sink resamples the
+/// windows that end before the latest sent sample. The resampled samples are delivered to the window callback of the
+/// resampler, if any, and kept until they are taken with
+/// [`take_resampled`][Self::take_resampled].
+#[derive(Debug)]
+pub struct ResamplerSink<T, S>
+where
+    T: ResampleValue,
+    S: Sample<Value = T>,
+{
+    resampler: Resampler<T, S>,
+    /// The timestamp of the latest sent sample
+    latest: Option<DateTime<Utc>>,
+    /// The resampled samples that were not taken yet
+    resampled: Vec<S>,
+}
+
+// the fields are never pinned
+impl<T, S> Unpin for ResamplerSink<T, S>
+where
+    T: ResampleValue,
+    S: Sample<Value = T>,
+{
+}
+
+impl<T, S> ResamplerSink<T, S>
+where
+    T: ResampleValue,
+    S: Sample<Value = T>,
+{
+    /// Creates a sink that adds the samples to the given resampler.
+    pub fn new(resampler: Resampler<T, S>) -> Self {
+        Self {
+            resampler,
+            latest: None,
+            resampled: vec![],
+        }
+    }
+
+    /// Returns the resampled samples of all flushes since the last call.
+    pub fn take_resampled(&mut self) -> Vec<S> {
+        std::mem::take(&mut self.resampled)
+    }
+
+    /// Returns the resampler.
+    pub fn resampler(&self) -> &Resampler<T, S> {
+        &self.resampler
+    }
+
+    /// Returns the resampler, e.g. to resample the remaining samples after the
+    /// sink was closed.
+    pub fn into_inner(self) -> Resampler<T, S> {
+        self.resampler
+    }
+}
+
+impl<T, S> Sink<S> for ResamplerSink<T, S>
+where
+    T: ResampleValue,
+    S: Sample<Value = T>,
+{
+    type Error = ResampleError;
+
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, sample: S) -> Result<(), Self::Error> {
+        let this = self.get_mut();
+        this.latest = this.latest.max(Some(sample.timestamp()));
+        this.resampler.try_push(sample)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        if let Some(latest) = this.latest {
+            let resampled = this.resampler.resample_completed(latest);
+            this.resampled.extend(resampled);
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.poll_flush(cx)
+    }
+}
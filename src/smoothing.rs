@@ -0,0 +1,94 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! The smoothing module provides the smoothers that can be applied to the
+//! resampled samples before they are emitted, to reduce the jitter of noisy
+//! aggregates.
+
+/// The Smoother enum represents the different smoothers that can be applied to
+/// the values of the resampled samples.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Smoother {
+    /// A one-dimensional Kalman filter for a random walk with the given
+    /// variances of the process and the measurement noise.
+    Kalman {
+        process_noise: f64,
+        measurement_noise: f64,
+    },
+    /// Double exponential smoothing (Holt's linear method) with the given
+    /// smoothing factors of the level and the trend, both between 0 and 1.
+    DoubleExponential { alpha: f64, beta: f64 },
+}
+
+/// The SmootherState enum represents the state of a [`Smoother`], that can be
+/// saved and restored to continue the smoothing seamlessly.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SmootherState {
+    /// The state of a `Kalman` smoother
+    Kalman { estimate: f64, error_variance: f64 },
+    /// The state of a `DoubleExponential` smoother
+    DoubleExponential { level: f64, trend: f64 },
+}
+
+impl Smoother {
+    /// Smooths the given value, given the state of the smoother after the
+    /// previous value, and updates the state. If the state is None or doesn't
+    /// belong to this kind of smoother, the smoothing starts with the given
+    /// value.
+    pub fn smooth(&self, state: &mut Option<SmootherState>, value: f64) -> f64 {
+        let (next, output) = match (self, *state) {
+            (
+                Self::Kalman {
+                    process_noise,
+                    measurement_noise,
+                },
+                Some(SmootherState::Kalman {
+                    estimate,
+                    error_variance,
+                }),
+            ) => {
+                let error_variance = error_variance + process_noise;
+                let gain = error_variance / (error_variance + measurement_noise);
+                let estimate = estimate + gain * (value - estimate);
+                let next = SmootherState::Kalman {
+                    estimate,
+                    error_variance: (1.0 - gain) * error_variance,
+                };
+                (next, estimate)
+            }
+            (
+                Self::Kalman {
+                    measurement_noise, ..
+                },
+                _,
+            ) => {
+                let next = SmootherState::Kalman {
+                    estimate: value,
+                    error_variance: *measurement_noise,
+                };
+                (next, value)
+            }
+            (
+                Self::DoubleExponential { alpha, beta },
+                Some(SmootherState::DoubleExponential { level, trend }),
+            ) => {
+                let next_level = alpha * value + (1.0 - alpha) * (level + trend);
+                let next = SmootherState::DoubleExponential {
+                    level: next_level,
+                    trend: beta * (next_level - level) + (1.0 - beta) * trend,
+                };
+                (next, next_level)
+            }
+            (Self::DoubleExponential { .. }, _) => {
+                let next = SmootherState::DoubleExponential {
+                    level: value,
+                    trend: 0.0,
+                };
+                (next, value)
+            }
+        };
+        *state = Some(next);
+        output
+    }
+}
@@ -0,0 +1,125 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! The state module provides the ResamplerState struct that holds the state of
+//! a resampler, so that it can be persisted and restored.
+
+use std::collections::{BTreeMap, VecDeque};
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+use crate::accumulator::Accumulator;
+use crate::error::ResampleError;
+use crate::resampler::ResamplerStats;
+use crate::smoothing::SmootherState;
+
+/// The version of the layout of [`ResamplerState`], which is increased
+/// whenever fields are added or their meaning changes. States of older
+/// versions are [migrated][ResamplerState::migrate] when they are restored,
+/// and states of newer versions are rejected instead of restored wrongly.
+///
+/// - Version 1: the initial layout, which didn't store its version.
+/// - Version 2: adds the version, the buffer high watermark, the aggregates of
+///   the incremental aggregation, the deferred interval, the rolling input
+///   interval detection and the statistics.
+pub(crate) const STATE_VERSION: u32 = 2;
+
+/// Returns the version of states that were serialized without a version.
+#[cfg(feature = "serde")]
+fn first_version() -> u32 {
+    1
+}
+
+/// The ResamplerState struct holds the state of a
+/// [`Resampler`][crate::Resampler] that changes while samples are added and
+/// resampled, see [`snapshot`][crate::Resampler::snapshot] and
+/// [`restore`][crate::Resampler::restore]. With the `serde` feature, it can be
+/// serialized, e.g. to persist it for crash recovery.
+///
+/// The struct holds the fields of all versions. The fields that were added
+/// after the first version are defaulted when older states are deserialized,
+/// and filled in when they are restored.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ResamplerState<S> {
+    #[cfg_attr(feature = "serde", serde(default = "first_version"))]
+    pub(crate) version: u32,
+    pub(crate) buffer: Vec<S>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) buffer_high_watermark: usize,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) accumulators: BTreeMap<DateTime<Utc>, Accumulator<S>>,
+    pub(crate) start: DateTime<Utc>,
+    pub(crate) interval: TimeDelta,
+    pub(crate) pending_interval: Option<TimeDelta>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) deferred_interval: Option<TimeDelta>,
+    pub(crate) input_start: Option<DateTime<Utc>>,
+    pub(crate) input_interval: Option<TimeDelta>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) last_input_at: Option<DateTime<Utc>>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) input_deltas: VecDeque<TimeDelta>,
+    pub(crate) first_sample_at: Option<DateTime<Utc>>,
+    pub(crate) last_resampled: Option<S>,
+    pub(crate) filter_history: VecDeque<f64>,
+    pub(crate) smoother_state: Option<SmootherState>,
+    pub(crate) rejected_outliers: u64,
+    pub(crate) late_samples: u64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) stats: ResamplerStats,
+    pub(crate) last_emitted: Option<DateTime<Utc>>,
+    pub(crate) last_now: Option<DateTime<Utc>>,
+    pub(crate) last_pushed: Option<DateTime<Utc>>,
+    pub(crate) watermark: Option<DateTime<Utc>>,
+    pub(crate) history: Vec<S>,
+    pub(crate) pending_corrections: Vec<DateTime<Utc>>,
+}
+
+impl<S> ResamplerState<S> {
+    /// Returns the version of the layout of the state, see
+    /// [`try_restore`][crate::Resampler::try_restore].
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Upgrades a state of the given version to the next version, or returns
+    /// an error if there is no migration from the version. Restoring a state
+    /// applies the migrations from its version up to the current one.
+    pub(crate) fn migrate(mut self, from: u32) -> Result<Self, ResampleError>
+    where
+        S: crate::resampler::Sample,
+    {
+        match from {
+            // the fields added in version 2 are defaulted, except for the ones
+            // that can be derived from the rest of the state
+            1 => {
+                self.buffer_high_watermark = self.buffer.len();
+                self.last_input_at = self.buffer.last().map(|s| s.timestamp());
+            }
+            _ => {
+                return Err(ResampleError::UnsupportedStateVersion {
+                    version: from,
+                    supported: STATE_VERSION,
+                })
+            }
+        }
+        self.version = from + 1;
+        Ok(self)
+    }
+
+    /// Returns the buffered samples.
+    pub fn buffer(&self) -> &[S] {
+        &self.buffer
+    }
+
+    /// Returns the start of the next interval to resample.
+    pub fn start(&self) -> DateTime<Utc> {
+        self.start
+    }
+
+    /// Returns the detected interval of the input data, if any.
+    pub fn input_interval(&self) -> Option<TimeDelta> {
+        self.input_interval
+    }
+}
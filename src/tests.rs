@@ -5,15 +5,43 @@
 
 use std::{
     cmp::Ordering,
+    collections::HashMap,
     iter::Sum,
     ops::{Add, Div},
+    sync::{
+        atomic::{AtomicI64, Ordering as AtomicOrdering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant, SystemTime},
 };
 
-use crate::resampler::{epoch_align, Resampler, ResamplingFunction, Sample};
-use chrono::{DateTime, TimeDelta, Utc};
+use crate::conversion::UnitConversion;
+use crate::error::ResampleError;
+use crate::event::{Event, EventAggregation, EventResampler};
+use crate::filter::LowPassFilter;
+use crate::group::ResamplerGroup;
+use crate::interval::ResamplingInterval;
+use crate::iter::ResampleExt;
+use crate::lttb::lttb;
+use crate::observer::ResamplerObserver;
+use crate::outlier::{OutlierFilter, ValidityFilter};
+use crate::resampler::{
+    epoch_align, ClockJumpPolicy, Closed, DropReason, DuplicatePolicy, EmissionPolicy, EmptyCount,
+    GapPolicy, InputIntervalDetection, Interpolate, LabelPosition, NanPolicy, OverflowPolicy,
+    QualitySample, RateOf, ResampledBatch, ResampledWindow, Resampler, ResamplerStats,
+    ResamplingFunction, Sample, SourceAggregate, SourceSample, WindowSpec,
+};
+use crate::savitzky_golay::SavitzkyGolay;
+use crate::smoothing::{Smoother, SmootherState};
+#[cfg(feature = "serde")]
+use crate::state::ResamplerState;
+use crate::time_axis::{AxisSample, TimeAxis};
+use crate::value::{ArrayValue, ResampleValue};
+use chrono::{DateTime, Datelike, NaiveDate, TimeDelta, Utc};
 use num_traits::FromPrimitive;
 
 #[derive(Debug, Clone, Default, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) struct TestSample {
     timestamp: DateTime<Utc>,
     value: Option<f64>,
@@ -170,6 +198,189 @@ fn test_resampling_count() {
     );
 }
 
+#[test]
+fn test_resampling_count_empty_none() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Count,
+        1,
+        start,
+        false,
+    )
+    .with_empty_count(EmptyCount::None);
+    let step = TimeDelta::seconds(1);
+    let data = vec![
+        TestSample::new(start + step, None),
+        TestSample::new(start + step * 2, None),
+        TestSample::new(start + step * 6, Some(6.0)),
+        TestSample::new(start + step * 7, None),
+    ];
+
+    resampler.extend(data);
+
+    let resampled = resampler.resample(start + step * 15);
+    assert_eq!(
+        resampled,
+        vec![
+            TestSample::new(DateTime::from_timestamp(5, 0).unwrap(), None),
+            TestSample::new(DateTime::from_timestamp(10, 0).unwrap(), Some(1.0)),
+            TestSample::new(DateTime::from_timestamp(15, 0).unwrap(), None),
+        ]
+    );
+}
+
+#[test]
+fn test_resampling_count_empty_none_count_windows() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let values = [None, None, None, Some(4.0), None, Some(6.0)];
+    let data = values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| TestSample::new(start + step * (i as i32 + 1), *value));
+
+    for (spec, expected) in [
+        (WindowSpec::Count(3), vec![None, Some(2.0)]),
+        (
+            WindowSpec::TimeOrCount(TimeDelta::seconds(5), 2),
+            vec![None, Some(1.0), None, Some(1.0)],
+        ),
+    ] {
+        let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+            TimeDelta::seconds(5),
+            ResamplingFunction::Count,
+            1,
+            start,
+            false,
+        )
+        .with_empty_count(EmptyCount::None)
+        .with_window_spec(spec);
+        resampler.extend(data.clone());
+
+        assert_eq!(
+            resampler
+                .resample(start + step * 10)
+                .iter()
+                .map(|s| s.value())
+                .collect::<Vec<_>>(),
+            expected
+        );
+    }
+}
+
+#[test]
+fn test_resampling_events() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let resample = |empty_count| {
+        let mut resampler: EventResampler<Event, TestSample> =
+            EventResampler::new(TimeDelta::seconds(5), start + step).with_empty_count(empty_count);
+        resampler.extend([1, 2, 2, 4, 12].map(|i| Event::at(start + step * i)));
+        let resampled = resampler.resample(start + step * 15);
+        assert_eq!(resampler.window_start(), start + step * 15);
+        resampled
+    };
+
+    assert_eq!(
+        resample(EmptyCount::None),
+        vec![
+            TestSample::new(start, Some(4.0)),
+            TestSample::new(start + step * 5, None),
+            TestSample::new(start + step * 10, Some(1.0)),
+        ]
+    );
+    assert_eq!(resample(EmptyCount::Zero)[1].value(), Some(0.0));
+
+    // events before the next window are dropped
+    let mut resampler: EventResampler<Event, TestSample> =
+        EventResampler::new(TimeDelta::seconds(5), start).with_empty_count(EmptyCount::None);
+    resampler.extend([1, 7].map(|i| Event::at(start + step * i)));
+    assert_eq!(
+        resampler
+            .resample(start + step * 5)
+            .iter()
+            .map(|s| s.value())
+            .collect::<Vec<_>>(),
+        vec![Some(1.0)]
+    );
+    resampler.push(Event::at(start + step * 2));
+    assert_eq!(
+        resampler
+            .resample(start + step * 15)
+            .iter()
+            .map(|s| s.value())
+            .collect::<Vec<_>>(),
+        vec![Some(1.0), None]
+    );
+}
+
+#[test]
+fn test_resampling_rate() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+
+    let resample = |empty_count| {
+        let mut resampler: EventResampler<Event, TestSample> =
+            EventResampler::new(TimeDelta::seconds(5), start)
+                .with_aggregation(EventAggregation::Rate)
+                .with_empty_count(empty_count);
+        resampler.extend([1, 2, 2, 4, 12].map(|i| Event::at(start + step * i)));
+        resampler
+            .resample(start + step * 15)
+            .iter()
+            .map(|s| s.value())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        resample(EmptyCount::Zero),
+        vec![Some(0.8), Some(0.0), Some(0.2)]
+    );
+    assert_eq!(resample(EmptyCount::None), vec![Some(0.8), None, Some(0.2)]);
+
+    // energy in Ws per second, starting within the first time step
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Rate(RateOf::Sum),
+        1,
+        start,
+        false,
+    );
+    resampler.extend((1..=10).map(|i| TestSample::new(start + step * i, Some(10.0))));
+    let resampled = resampler.resample(start + step * 10);
+    assert_eq!(
+        resampled,
+        vec![
+            TestSample::new(start + step * 5, Some(12.5)),
+            TestSample::new(start + step * 10, Some(10.0)),
+        ]
+    );
+}
+
+#[test]
+fn test_resampling_sum_with_reference_interval() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Sum,
+        1,
+        start,
+        false,
+    )
+    .with_reference_interval(TimeDelta::minutes(1));
+    // the input data starts within the first interval
+    resampler.extend((1..=10).map(|i| TestSample::new(start + step * i, Some(2.0))));
+    let resampled = resampler.resample(start + step * 10);
+    assert_eq!(
+        resampled,
+        vec![
+            TestSample::new(start + step * 5, Some(150.0)),
+            TestSample::new(start + step * 10, Some(120.0)),
+        ]
+    );
+}
+
 #[test]
 fn test_resampling_sum() {
     test_resampling(
@@ -345,6 +556,82 @@ fn test_resampling_custom() {
     );
 }
 
+#[test]
+fn test_resampling_nearest() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Nearest(TimeDelta::seconds(1)),
+        1,
+        start,
+        false,
+    );
+    let ms = TimeDelta::milliseconds(1);
+    let data = vec![
+        TestSample::new(start + ms * 4800, Some(1.0)),
+        TestSample::new(start + ms * 5300, Some(2.0)),
+        TestSample::new(start + ms * 9000, Some(3.0)),
+        TestSample::new(start + ms * 10200, None),
+        TestSample::new(start + ms * 13500, Some(4.0)),
+        TestSample::new(start + ms * 18000, Some(5.0)),
+    ];
+
+    resampler.extend(data);
+
+    let expected = vec![
+        TestSample::new(DateTime::from_timestamp(5, 0).unwrap(), Some(1.0)),
+        TestSample::new(DateTime::from_timestamp(10, 0).unwrap(), Some(3.0)),
+        TestSample::new(DateTime::from_timestamp(15, 0).unwrap(), None),
+    ];
+
+    let resampled = resampler.resample(start + TimeDelta::seconds(15));
+    assert_eq!(resampled, expected);
+}
+
+#[test]
+fn test_resampling_custom_with_carry() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(2),
+        ResamplingFunction::CustomWithCarry(Box::new(
+            |carried: &[&TestSample], current: &[&TestSample]| {
+                Some(carried.len() as f64 * 10.0 + current.len() as f64)
+            },
+        )),
+        2,
+        start,
+        false,
+    );
+    let step = TimeDelta::seconds(1);
+    resampler.extend((1..=6).map(|i| TestSample::new(start + step * i, Some(1.0))));
+
+    let resampled = resampler.resample(start + step * 6);
+    assert_eq!(
+        resampled.iter().map(|s| s.value()).collect::<Vec<_>>(),
+        vec![Some(2.0), Some(22.0), Some(22.0)]
+    );
+}
+
+#[test]
+fn test_resampling_savitzky_golay() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    for (order, expected) in [(2, 100.0), (1, 98.0)] {
+        let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+            TimeDelta::seconds(10),
+            ResamplingFunction::SavitzkyGolay(SavitzkyGolay::new(5, order)),
+            1,
+            start,
+            false,
+        );
+        resampler.extend((1..=10).map(|i| TestSample::new(start + step * i, Some((i * i) as f64))));
+
+        let resampled = resampler.resample(start + step * 10);
+        assert_eq!(resampled.len(), 1);
+        assert!((resampled[0].value().unwrap() - expected).abs() < 1e-9);
+    }
+}
+
 #[test]
 fn test_resampling_with_max_age() {
     let start = DateTime::from_timestamp(0, 0).unwrap();
@@ -553,6 +840,107 @@ fn test_resampling_with_gap() {
     assert_eq!(resampled, expected);
 }
 
+fn test_resampling_with_gap_policy(gap_policy: GapPolicy, expected: Vec<Option<f64>>) {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Average,
+        1,
+        start,
+        false,
+    )
+    .with_gap_policy(gap_policy);
+    let step = TimeDelta::seconds(1);
+    let data = vec![
+        TestSample::new(start + step, Some(1.0)),
+        TestSample::new(start + step * 5, Some(5.0)),
+        TestSample::new(start + step * 20, Some(20.0)),
+    ];
+
+    resampler.extend(data);
+
+    let expected = expected
+        .into_iter()
+        .enumerate()
+        .map(|(i, value)| TestSample::new(start + step * (5 * (i as i32 + 1)), value))
+        .collect::<Vec<_>>();
+    let resampled = resampler.resample(start + step * 20);
+    assert_eq!(resampled, expected);
+}
+
+#[test]
+fn test_resampling_gap_policies() {
+    test_resampling_with_gap_policy(GapPolicy::None, vec![Some(3.0), None, None, Some(20.0)]);
+    test_resampling_with_gap_policy(
+        GapPolicy::ForwardFill,
+        vec![Some(3.0), Some(3.0), Some(3.0), Some(20.0)],
+    );
+    test_resampling_with_gap_policy(
+        GapPolicy::ZeroFill,
+        vec![Some(3.0), Some(0.0), Some(0.0), Some(20.0)],
+    );
+    test_resampling_with_gap_policy(
+        GapPolicy::LinearInterpolate,
+        vec![Some(3.0), Some(10.0), Some(15.0), Some(20.0)],
+    );
+}
+
+#[test]
+fn test_resampling_forward_fill_with_max_fill() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Average,
+        1,
+        start,
+        false,
+    )
+    .with_gap_policy(GapPolicy::ForwardFill)
+    .with_max_fill(TimeDelta::seconds(10));
+    let step = TimeDelta::seconds(1);
+
+    resampler.extend(vec![
+        TestSample::new(start + step * 3, Some(3.0)),
+        TestSample::new(start + step * 28, Some(28.0)),
+    ]);
+
+    let resampled = resampler.resample(start + step * 30);
+    assert_eq!(
+        resampled.iter().map(|s| s.value()).collect::<Vec<_>>(),
+        vec![Some(3.0), Some(3.0), Some(3.0), None, None, Some(28.0)]
+    );
+}
+
+#[test]
+fn test_resampling_after_bootstrap() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Average,
+        1,
+        start,
+        false,
+    )
+    .with_gap_policy(GapPolicy::ForwardFill);
+    let step = TimeDelta::seconds(1);
+
+    resampler.bootstrap(vec![
+        TestSample::new(start + step * 5, Some(3.0)),
+        TestSample::new(start + step * 10, Some(4.0)),
+    ]);
+    resampler.push(TestSample::new(start + step * 18, Some(6.0)));
+
+    let resampled = resampler.resample(start + step * 25);
+    assert_eq!(
+        resampled,
+        vec![
+            TestSample::new(start + step * 15, Some(4.0)),
+            TestSample::new(start + step * 20, Some(6.0)),
+            TestSample::new(start + step * 25, Some(6.0)),
+        ]
+    );
+}
+
 #[test]
 fn test_resampling_with_slow_data() {
     let start = DateTime::from_timestamp(0, 0).unwrap();
@@ -589,6 +977,76 @@ fn test_resampling_with_slow_data() {
     assert_eq!(resampled, expected);
 }
 
+#[test]
+fn test_resampling_with_linear_interpolation() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(1),
+        ResamplingFunction::Average,
+        1,
+        start,
+        false,
+    )
+    .with_interpolation(Interpolate::Linear);
+    let step = TimeDelta::seconds(4);
+    let data = vec![
+        TestSample::new(start, Some(0.0)),
+        TestSample::new(start + step, Some(4.0)),
+        TestSample::new(start + step * 2, Some(2.0)),
+    ];
+
+    resampler.extend(data);
+
+    let expected = vec![
+        TestSample::new(DateTime::from_timestamp(1, 0).unwrap(), Some(1.0)),
+        TestSample::new(DateTime::from_timestamp(2, 0).unwrap(), Some(2.0)),
+        TestSample::new(DateTime::from_timestamp(3, 0).unwrap(), Some(3.0)),
+        TestSample::new(DateTime::from_timestamp(4, 0).unwrap(), Some(4.0)),
+        TestSample::new(DateTime::from_timestamp(5, 0).unwrap(), Some(3.5)),
+    ];
+    assert_eq!(resampler.resample(start + TimeDelta::seconds(5)), expected);
+
+    let expected = vec![
+        TestSample::new(DateTime::from_timestamp(6, 0).unwrap(), Some(3.0)),
+        TestSample::new(DateTime::from_timestamp(7, 0).unwrap(), Some(2.5)),
+        TestSample::new(DateTime::from_timestamp(8, 0).unwrap(), Some(2.0)),
+        TestSample::new(DateTime::from_timestamp(9, 0).unwrap(), None),
+    ];
+    assert_eq!(resampler.resample(start + TimeDelta::seconds(9)), expected);
+}
+
+#[test]
+fn test_resampling_without_empty_windows() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Average,
+        1,
+        start,
+        false,
+    )
+    .with_emit_empty_windows(false);
+    let step = TimeDelta::seconds(1);
+    let data = vec![
+        TestSample::new(start + step, Some(1.0)),
+        TestSample::new(start + step * 2, Some(2.0)),
+        TestSample::new(start + step * 4, Some(4.0)),
+        TestSample::new(start + step * 5, Some(5.0)),
+        TestSample::new(start + step * 17, Some(6.0)),
+        TestSample::new(start + step * 20, Some(10.0)),
+    ];
+
+    resampler.extend(data);
+
+    let expected = vec![
+        TestSample::new(DateTime::from_timestamp(5, 0).unwrap(), Some(3.0)),
+        TestSample::new(DateTime::from_timestamp(20, 0).unwrap(), Some(8.0)),
+    ];
+
+    let resampled = resampler.resample(start + step * 20);
+    assert_eq!(resampled, expected);
+}
+
 #[test]
 fn test_resampling_with_gap_early_end_date() {
     let start = DateTime::from_timestamp(0, 0).unwrap();
@@ -629,7 +1087,7 @@ fn test_resampling_with_gap_early_end_date() {
 }
 
 #[test]
-fn test_empty_buffer() {
+fn test_resample_detailed() {
     let start = DateTime::from_timestamp(0, 0).unwrap();
     let mut resampler: Resampler<f64, TestSample> = Resampler::new(
         TimeDelta::seconds(5),
@@ -638,20 +1096,3592 @@ fn test_empty_buffer() {
         start,
         false,
     );
+    let step = TimeDelta::seconds(1);
+    let data = vec![
+        TestSample::new(start + step, Some(1.0)),
+        TestSample::new(start + step * 2, None),
+        TestSample::new(start + step * 3, Some(3.0)),
+        TestSample::new(start + step * 12, Some(12.0)),
+    ];
 
-    let resampled = resampler.resample(start + TimeDelta::seconds(10));
+    resampler.extend(data);
+
+    let resampled = resampler.resample_detailed(start + step * 15);
     assert_eq!(
         resampled,
         vec![
-            TestSample::new(start + TimeDelta::seconds(5), None),
-            TestSample::new(start + TimeDelta::seconds(10), None),
+            ResampledWindow {
+                window_start: start,
+                window_end: start + step * 5,
+                sample_count: 3,
+                none_count: 1,
+                bad_count: 0,
+                duplicate: false,
+                correction: false,
+                sample: TestSample::new(start + step * 5, Some(2.0)),
+                sources: vec![],
+            },
+            ResampledWindow {
+                window_start: start + step * 5,
+                window_end: start + step * 10,
+                sample_count: 0,
+                none_count: 0,
+                bad_count: 0,
+                duplicate: false,
+                correction: false,
+                sample: TestSample::new(start + step * 10, None),
+                sources: vec![],
+            },
+            ResampledWindow {
+                window_start: start + step * 10,
+                window_end: start + step * 15,
+                sample_count: 1,
+                none_count: 0,
+                bad_count: 0,
+                duplicate: false,
+                correction: false,
+                sample: TestSample::new(start + step * 15, Some(12.0)),
+                sources: vec![],
+            },
         ]
     );
 }
 
 #[test]
-fn test_epoch_alignment() {
-    let interval = TimeDelta::seconds(5);
+fn test_current_partial() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Sum,
+        1,
+        start,
+        false,
+    );
+    let step = TimeDelta::seconds(1);
+
+    assert_eq!(
+        resampler.current_partial(),
+        TestSample::new(start + step * 5, None)
+    );
+
+    resampler.extend(vec![
+        TestSample::new(start + step, Some(1.0)),
+        TestSample::new(start + step * 2, Some(2.0)),
+    ]);
+    assert_eq!(
+        resampler.current_partial(),
+        TestSample::new(start + step * 5, Some(3.0))
+    );
+
+    resampler.extend(vec![
+        TestSample::new(start + step * 3, Some(3.0)),
+        TestSample::new(start + step * 6, Some(6.0)),
+    ]);
+    assert_eq!(
+        resampler.current_partial(),
+        TestSample::new(start + step * 5, Some(6.0))
+    );
+
+    assert_eq!(
+        resampler.resample(start + step * 5),
+        vec![TestSample::new(start + step * 5, Some(6.0))]
+    );
+    assert_eq!(
+        resampler.current_partial(),
+        TestSample::new(start + step * 10, Some(6.0))
+    );
+}
+
+#[test]
+fn test_empty_buffer() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Average,
+        1,
+        start,
+        false,
+    );
+
+    let resampled = resampler.resample(start + TimeDelta::seconds(10));
+    assert_eq!(
+        resampled,
+        vec![
+            TestSample::new(start + TimeDelta::seconds(5), None),
+            TestSample::new(start + TimeDelta::seconds(10), None),
+        ]
+    );
+}
+
+#[test]
+fn test_resampling_with_low_pass_filter() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    // a signal alternating at the sampling rate, that aliases when decimated
+    let data = (1..=12)
+        .map(|i| TestSample::new(start + step * i, Some(if i % 2 == 0 { 4.0 } else { 0.0 })))
+        .collect::<Vec<_>>();
+
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(4),
+        ResamplingFunction::Last,
+        1,
+        start,
+        false,
+    );
+    resampler.extend(data.clone());
+    assert_eq!(
+        resampler
+            .resample(start + step * 12)
+            .iter()
+            .map(|s| s.value())
+            .collect::<Vec<_>>(),
+        vec![Some(4.0), Some(4.0), Some(4.0)]
+    );
+
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(4),
+        ResamplingFunction::Last,
+        1,
+        start,
+        false,
+    )
+    .with_low_pass_filter(LowPassFilter::moving_average(2));
+    resampler.extend(data);
+    assert_eq!(
+        resampler
+            .resample(start + step * 12)
+            .iter()
+            .map(|s| s.value())
+            .collect::<Vec<_>>(),
+        vec![Some(2.0), Some(2.0), Some(2.0)]
+    );
+}
+
+#[test]
+fn test_low_pass_filters() {
+    let mut history = Default::default();
+    let fir = LowPassFilter::Fir(vec![0.5, 0.25, 0.25]);
+    let filtered = [4.0, 8.0, 0.0, 4.0]
+        .map(|v| fir.filter(&mut history, v))
+        .to_vec();
+    assert_eq!(filtered, vec![4.0, 6.0, 3.0, 4.0]);
+
+    let mut history = Default::default();
+    let iir = LowPassFilter::Iir(0.5);
+    let filtered = [4.0, 8.0, 0.0, 4.0]
+        .map(|v| iir.filter(&mut history, v))
+        .to_vec();
+    assert_eq!(filtered, vec![4.0, 6.0, 3.0, 3.5]);
+}
+
+#[test]
+fn test_resampling_with_smoother() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let smoother = Smoother::DoubleExponential {
+        alpha: 0.5,
+        beta: 0.5,
+    };
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(1),
+        ResamplingFunction::Last,
+        1,
+        start,
+        false,
+    )
+    .with_smoother(smoother);
+    resampler.extend(vec![
+        TestSample::new(start + step, Some(4.0)),
+        TestSample::new(start + step * 2, Some(8.0)),
+    ]);
+    assert_eq!(
+        resampler.resample(start + step * 3),
+        vec![
+            TestSample::new(start + step, Some(4.0)),
+            TestSample::new(start + step * 2, Some(6.0)),
+            TestSample::new(start + step * 3, None),
+        ]
+    );
+    let state = resampler.smoother_state();
+    assert_eq!(
+        state,
+        Some(SmootherState::DoubleExponential {
+            level: 6.0,
+            trend: 1.0
+        })
+    );
+
+    // a new resampler continues the smoothing from the saved state
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(1),
+        ResamplingFunction::Last,
+        1,
+        start + step * 3,
+        false,
+    )
+    .with_smoother(smoother);
+    resampler.restore_smoother_state(state.unwrap());
+    resampler.push(TestSample::new(start + step * 4, Some(9.0)));
+    assert_eq!(
+        resampler.resample(start + step * 4),
+        vec![TestSample::new(start + step * 4, Some(8.0))]
+    );
+}
+
+#[test]
+fn test_kalman_smoother() {
+    let smoother = Smoother::Kalman {
+        process_noise: 1.0,
+        measurement_noise: 1.0,
+    };
+    let mut state = None;
+    assert_eq!(smoother.smooth(&mut state, 2.0), 2.0);
+    assert_eq!(smoother.smooth(&mut state, 5.0), 4.0);
+    let Some(SmootherState::Kalman {
+        estimate,
+        error_variance,
+    }) = state
+    else {
+        panic!("unexpected smoother state {state:?}");
+    };
+    assert_eq!(estimate, 4.0);
+    assert!((error_variance - 2.0 / 3.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_resampling_with_outlier_filter() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let values = [
+        Some(1.0),
+        Some(2.0),
+        Some(1000.0),
+        None,
+        Some(3.0),
+        Some(2.0),
+        Some(2.0),
+        Some(4.0),
+        Some(2.0),
+        Some(2.0),
+    ];
+    for (filter, expected) in [
+        (OutlierFilter::ZScore(1.5), vec![Some(2.0), Some(2.0)]),
+        (OutlierFilter::Iqr(1.5), vec![Some(2.0), Some(2.0)]),
+    ] {
+        let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+            TimeDelta::seconds(5),
+            ResamplingFunction::Average,
+            1,
+            start,
+            false,
+        )
+        .with_outlier_filter(filter);
+        resampler.extend(
+            values
+                .iter()
+                .enumerate()
+                .map(|(i, value)| TestSample::new(start + step * (i as i32 + 1), *value)),
+        );
+
+        let resampled = resampler.resample_detailed(start + step * 10);
+        assert_eq!(
+            resampled
+                .iter()
+                .map(|w| w.sample.value())
+                .collect::<Vec<_>>(),
+            expected
+        );
+        assert_eq!(resampled[0].sample_count, 4);
+        assert_eq!(resampled[0].none_count, 1);
+    }
+}
+
+#[test]
+fn test_outlier_filters() {
+    let values = [1.0, 2.0, 3.0, 2.0, 2.0, 4.0, 2.0, 2.0];
+    assert_eq!(
+        OutlierFilter::ZScore(1.5).outliers(&values),
+        vec![true, false, false, false, false, true, false, false]
+    );
+    assert_eq!(
+        OutlierFilter::Iqr(1.5).outliers(&values),
+        vec![true, false, true, false, false, true, false, false]
+    );
+}
+
+#[test]
+fn test_resampling_with_unit_conversions() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    // power in W, resampled in kW and emitted in MW
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Average,
+        1,
+        start,
+        false,
+    )
+    .with_ingress_conversion(UnitConversion::TO_KILO)
+    .with_egress_conversion(UnitConversion::TO_KILO);
+    resampler.extend((1..=5).map(|i| TestSample::new(start + step * i, Some(i as f64 * 1e6))));
+    resampler.push(TestSample::new(start + step * 6, None));
+
+    assert_eq!(
+        resampler.buffer().front().and_then(|s| s.value()),
+        Some(1e3)
+    );
+    assert_eq!(resampler.current_partial().value(), Some(3.0));
+    assert_eq!(
+        resampler.resample(start + step * 5),
+        vec![TestSample::new(start + step * 5, Some(3.0))]
+    );
+    assert_eq!(resampler.buffer().back().and_then(|s| s.value()), None);
+}
+
+#[test]
+fn test_unit_conversion() {
+    let celsius_to_fahrenheit = UnitConversion::linear(1.8, 32.0);
+    assert_eq!(celsius_to_fahrenheit.convert(100.0), 212.0);
+    assert_eq!(celsius_to_fahrenheit.inverse().convert(212.0), 100.0);
+    assert_eq!(
+        UnitConversion::FROM_KILO
+            .then(celsius_to_fahrenheit)
+            .convert(0.1),
+        212.0
+    );
+    assert_eq!(UnitConversion::TO_MEGA.convert(2e6), 2.0);
+    assert_eq!(UnitConversion::FROM_MEGA.convert(2.0), 2e6);
+}
+
+#[test]
+fn test_resampling_with_duplicate_policy() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+
+    for policy in [DuplicatePolicy::Suppress, DuplicatePolicy::Flag] {
+        let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+            TimeDelta::seconds(5),
+            ResamplingFunction::Average,
+            1,
+            start,
+            false,
+        )
+        .with_duplicate_policy(policy);
+        resampler.bootstrap(vec![TestSample::new(start + step * 5, Some(1.0))]);
+        resampler.extend((6..=15).map(|i| TestSample::new(start + step * i, Some(i as f64))));
+
+        // overlapping ranges don't emit any window twice
+        let mut resampled = resampler.resample_detailed(start + step * 10);
+        resampled.extend(resampler.resample_detailed(start + step * 5));
+        resampled.extend(resampler.resample_detailed(start + step * 15));
+        assert_eq!(
+            resampled
+                .iter()
+                .map(|w| (w.sample.timestamp(), w.duplicate))
+                .collect::<Vec<_>>(),
+            vec![(start + step * 10, false), (start + step * 15, false)]
+        );
+    }
+}
+
+#[test]
+fn test_resampling_with_input_transform() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    // flip the sign of the values, clamp them and drop the samples without a
+    // value
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Sum,
+        1,
+        start,
+        false,
+    )
+    .with_input_transform(Box::new(|s: &TestSample| {
+        s.value()
+            .map(|v| TestSample::new(s.timestamp(), Some((-v).max(-3.0))))
+    }));
+    resampler.extend((1..=5).map(|i| TestSample::new(start + step * i, Some(i as f64))));
+    resampler.push(TestSample::new(start + step * 5, None));
+
+    assert_eq!(resampler.buffer().len(), 5);
+    assert_eq!(
+        resampler.resample(start + step * 5),
+        vec![TestSample::new(start + step * 5, Some(-12.0))]
+    );
+}
+
+#[test]
+fn test_resample_now_with_clock_jumps() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let timestamps = |samples: Vec<TestSample>| {
+        samples
+            .iter()
+            .map(|s| (s.timestamp() - start).num_seconds())
+            .collect::<Vec<_>>()
+    };
+
+    let new_resampler = |policy: ClockJumpPolicy, duplicate_policy: DuplicatePolicy| {
+        let now = Arc::new(AtomicI64::new(10));
+        let clock_now = now.clone();
+        let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+            TimeDelta::seconds(5),
+            ResamplingFunction::Average,
+            1,
+            start,
+            false,
+        )
+        .with_clock(Box::new(move || {
+            DateTime::from_timestamp(clock_now.load(AtomicOrdering::SeqCst), 0).unwrap()
+        }))
+        .with_clock_jump_policy(policy)
+        .with_duplicate_policy(duplicate_policy);
+        resampler.extend((1..=15).map(|i| TestSample::new(start + step * i, Some(i as f64))));
+        (resampler, now)
+    };
+
+    // hold until the clock catches up
+    let (mut resampler, now) = new_resampler(ClockJumpPolicy::Hold, DuplicatePolicy::Emit);
+    assert_eq!(timestamps(resampler.resample_now()), vec![5, 10]);
+    now.store(6, AtomicOrdering::SeqCst);
+    assert_eq!(timestamps(resampler.resample_now()), Vec::<i64>::new());
+    now.store(9, AtomicOrdering::SeqCst);
+    assert_eq!(timestamps(resampler.resample_now()), Vec::<i64>::new());
+    now.store(15, AtomicOrdering::SeqCst);
+    assert_eq!(timestamps(resampler.resample_now()), vec![15]);
+
+    // realign to the new time, without emitting any window again whatever the
+    // duplicate policy is
+    for duplicate_policy in [
+        DuplicatePolicy::Emit,
+        DuplicatePolicy::Suppress,
+        DuplicatePolicy::Flag,
+    ] {
+        let (mut resampler, now) = new_resampler(ClockJumpPolicy::Realign, duplicate_policy);
+        let mut emitted = timestamps(resampler.resample_now());
+        assert_eq!(emitted, vec![5, 10]);
+        for time in [6, 1, 12, 15] {
+            now.store(time, AtomicOrdering::SeqCst);
+            emitted.extend(timestamps(resampler.resample_now()));
+        }
+        assert_eq!(emitted, vec![5, 10, 15]);
+    }
+
+    // report the clock jump
+    let (mut resampler, now) = new_resampler(ClockJumpPolicy::Error, DuplicatePolicy::Emit);
+    assert_eq!(
+        timestamps(resampler.try_resample_now().unwrap()),
+        vec![5, 10]
+    );
+    now.store(6, AtomicOrdering::SeqCst);
+    assert_eq!(
+        resampler.try_resample_now(),
+        Err(ResampleError::ClockJump {
+            previous: start + step * 10,
+            now: start + step * 6,
+        })
+    );
+    now.store(15, AtomicOrdering::SeqCst);
+    assert_eq!(timestamps(resampler.try_resample_now().unwrap()), vec![15]);
+}
+
+#[test]
+fn test_resampling_with_output_transform() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    // scale from W to kW, round to whole kW and drop the empty windows
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Average,
+        1,
+        start,
+        false,
+    )
+    .with_output_transform(Box::new(|s: &TestSample| {
+        s.value()
+            .map(|v| TestSample::new(s.timestamp(), Some((v / 1000.0).round())))
+    }));
+    resampler.extend(
+        (1..=5)
+            .chain(11..=13)
+            .map(|i| TestSample::new(start + step * i, Some(i as f64 * 1100.0))),
+    );
+
+    assert_eq!(resampler.current_partial().value(), Some(3.0));
+    assert_eq!(
+        resampler.resample(start + step * 15),
+        vec![
+            TestSample::new(start + step * 5, Some(3.0)),
+            TestSample::new(start + step * 15, Some(13.0)),
+        ]
+    );
+}
+
+#[test]
+fn test_resample_batch_fingerprint() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let new_resampler = |interval: TimeDelta, resampling_function| {
+        let mut resampler: Resampler<f64, TestSample> =
+            Resampler::new(interval, resampling_function, 1, start, false);
+        resampler.extend((1..=10).map(|i| TestSample::new(start + step * i, Some(i as f64))));
+        resampler
+    };
+
+    let mut resampler = new_resampler(TimeDelta::seconds(5), ResamplingFunction::Average);
+    let fingerprint = resampler.fingerprint();
+    assert_eq!(
+        resampler.resample_batch(start + step * 10),
+        ResampledBatch {
+            fingerprint,
+            samples: vec![
+                TestSample::new(start + step * 5, Some(3.0)),
+                TestSample::new(start + step * 10, Some(8.0)),
+            ],
+        }
+    );
+
+    // the same configuration has the same fingerprint
+    assert_eq!(
+        new_resampler(TimeDelta::seconds(5), ResamplingFunction::Average).fingerprint(),
+        fingerprint
+    );
+    // other configurations have different fingerprints
+    for other in [
+        new_resampler(TimeDelta::seconds(10), ResamplingFunction::Average),
+        new_resampler(TimeDelta::seconds(5), ResamplingFunction::Sum),
+        new_resampler(TimeDelta::seconds(5), ResamplingFunction::Average)
+            .with_gap_policy(GapPolicy::ForwardFill),
+    ] {
+        assert_ne!(other.fingerprint(), fingerprint);
+    }
+}
+
+#[test]
+fn test_resampling_with_hop() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Average,
+        1,
+        start,
+        false,
+    )
+    .with_hop(TimeDelta::seconds(1));
+    resampler.extend((1..=10).map(|i| TestSample::new(start + step * i, Some(i as f64))));
+
+    // each 5s interval is resampled every second
+    assert_eq!(
+        resampler.resample(start + step * 10),
+        (5..=10)
+            .map(|i| TestSample::new(start + step * i, Some(i as f64 - 2.0)))
+            .collect::<Vec<_>>()
+    );
+
+    // the samples of the overlap are kept for the next intervals
+    resampler.extend((11..=12).map(|i| TestSample::new(start + step * i, Some(i as f64))));
+    assert_eq!(
+        resampler.resample_detailed(start + step * 12),
+        vec![
+            ResampledWindow {
+                window_start: start + step * 6,
+                window_end: start + step * 11,
+                sample_count: 5,
+                none_count: 0,
+                bad_count: 0,
+                duplicate: false,
+                correction: false,
+                sample: TestSample::new(start + step * 11, Some(9.0)),
+                sources: vec![],
+            },
+            ResampledWindow {
+                window_start: start + step * 7,
+                window_end: start + step * 12,
+                sample_count: 5,
+                none_count: 0,
+                bad_count: 0,
+                duplicate: false,
+                correction: false,
+                sample: TestSample::new(start + step * 12, Some(10.0)),
+                sources: vec![],
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_resampling_count_windows() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Sum,
+        1,
+        start,
+        false,
+    )
+    .with_window_spec(WindowSpec::Count(3));
+    resampler.extend((1..=8).map(|i| TestSample::new(start + step * i, Some(i as f64))));
+
+    assert_eq!(resampler.current_partial().value(), Some(6.0));
+    assert_eq!(
+        resampler.resample_detailed(start + step * 10),
+        vec![
+            ResampledWindow {
+                window_start: start + step,
+                window_end: start + step * 3,
+                sample_count: 3,
+                none_count: 0,
+                bad_count: 0,
+                duplicate: false,
+                correction: false,
+                sample: TestSample::new(start + step * 3, Some(6.0)),
+                sources: vec![],
+            },
+            ResampledWindow {
+                window_start: start + step * 4,
+                window_end: start + step * 6,
+                sample_count: 3,
+                none_count: 0,
+                bad_count: 0,
+                duplicate: false,
+                correction: false,
+                sample: TestSample::new(start + step * 6, Some(15.0)),
+                sources: vec![],
+            },
+        ]
+    );
+
+    // the incomplete window is resampled once it is complete
+    assert_eq!(resampler.buffer().len(), 2);
+    assert_eq!(resampler.current_partial().value(), Some(15.0));
+    resampler.push(TestSample::new(start + step * 9, None));
+    assert_eq!(resampler.resample(start + step * 8), vec![]);
+    assert_eq!(
+        resampler.resample(start + step * 10),
+        vec![TestSample::new(start + step * 9, Some(15.0))]
+    );
+    assert!(resampler.buffer().is_empty());
+}
+
+#[test]
+fn test_resampling_time_or_count_windows() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Sum,
+        1,
+        start,
+        false,
+    )
+    .with_window_spec(WindowSpec::TimeOrCount(TimeDelta::seconds(5), 2));
+    // a burst in the first interval, a single sample in the second and none
+    // in the third
+    resampler.extend(
+        (1..=5)
+            .chain(7..=7)
+            .map(|i| TestSample::new(start + step * i, Some(i as f64))),
+    );
+
+    assert_eq!(
+        resampler
+            .resample_detailed(start + step * 15)
+            .iter()
+            .map(|w| (
+                (w.window_start - start).num_seconds(),
+                (w.window_end - start).num_seconds(),
+                w.sample_count,
+                w.sample,
+            ))
+            .collect::<Vec<_>>(),
+        vec![
+            (0, 2, 2, TestSample::new(start + step * 2, Some(3.0))),
+            (2, 4, 2, TestSample::new(start + step * 4, Some(7.0))),
+            (4, 5, 1, TestSample::new(start + step * 5, Some(5.0))),
+            (5, 10, 1, TestSample::new(start + step * 10, Some(7.0))),
+            (10, 15, 0, TestSample::new(start + step * 15, None)),
+        ]
+    );
+}
+
+#[test]
+fn test_resampling_calendar_months() {
+    let at = |y, m, d| {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+            .and_utc()
+    };
+    let midnight = |y, m, d| at(y, m, d) - TimeDelta::hours(12);
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::days(1),
+        ResamplingFunction::Sum,
+        1,
+        at(2024, 1, 15),
+        false,
+    )
+    .with_resampling_interval(ResamplingInterval::Months(1));
+    // one sample per day, with the day of the month as value
+    let mut day = at(2024, 1, 1);
+    while day < at(2024, 4, 1) {
+        resampler.push(TestSample::new(day, Some(day.day() as f64)));
+        day += TimeDelta::days(1);
+    }
+
+    let sum = |days: u32| (1..=days).sum::<u32>() as f64;
+    assert_eq!(
+        resampler
+            .resample_detailed(midnight(2024, 4, 1))
+            .iter()
+            .map(|w| (w.window_start, w.sample_count, w.sample))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                midnight(2024, 1, 1),
+                31,
+                TestSample::new(midnight(2024, 2, 1), Some(sum(31)))
+            ),
+            (
+                midnight(2024, 2, 1),
+                29,
+                TestSample::new(midnight(2024, 3, 1), Some(sum(29)))
+            ),
+            (
+                midnight(2024, 3, 1),
+                31,
+                TestSample::new(midnight(2024, 4, 1), Some(sum(31)))
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_resampling_interval_align() {
+    let at = |y, m, d, h| {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(h, 0, 0)
+            .unwrap()
+            .and_utc()
+    };
+    // 2024-05-15 is a Wednesday
+    let timestamp = at(2024, 5, 15, 13);
+    assert_eq!(
+        ResamplingInterval::Fixed(TimeDelta::hours(1)).align(timestamp),
+        timestamp
+    );
+    assert_eq!(
+        ResamplingInterval::Days(1).align(timestamp),
+        at(2024, 5, 15, 0)
+    );
+    assert_eq!(
+        ResamplingInterval::Weeks(1).align(timestamp),
+        at(2024, 5, 13, 0)
+    );
+    assert_eq!(
+        ResamplingInterval::Months(1).align(timestamp),
+        at(2024, 5, 1, 0)
+    );
+    assert_eq!(
+        ResamplingInterval::Months(3).align(timestamp),
+        at(2024, 4, 1, 0)
+    );
+    assert_eq!(
+        ResamplingInterval::Months(1).next(at(2024, 1, 31, 0)),
+        at(2024, 2, 29, 0)
+    );
+    assert_eq!(
+        ResamplingInterval::Weeks(2).next(at(2024, 5, 13, 0)),
+        at(2024, 5, 27, 0)
+    );
+}
+
+#[cfg(feature = "chrono-tz")]
+#[test]
+fn test_resampling_calendar_days_in_timezone() {
+    let at = |y, m, d, h| {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(h, 0, 0)
+            .unwrap()
+            .and_utc()
+    };
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::days(1),
+        ResamplingFunction::Count,
+        1,
+        at(2024, 3, 30, 12),
+        true,
+    )
+    .with_resampling_interval(ResamplingInterval::Days(1))
+    .with_timezone(chrono_tz::Europe::Berlin);
+    // one sample per hour, across the switch to and from summer time
+    let mut hour = at(2024, 3, 29, 0);
+    while hour < at(2024, 10, 29, 0) {
+        resampler.push(TestSample::new(hour, Some(1.0)));
+        hour += TimeDelta::hours(1);
+    }
+
+    let windows = resampler.resample_detailed(at(2024, 10, 28, 0));
+    assert_eq!(
+        windows
+            .iter()
+            .take(3)
+            .map(|w| (w.window_start, w.sample_count))
+            .collect::<Vec<_>>(),
+        vec![
+            (at(2024, 3, 29, 23), 24),
+            (at(2024, 3, 30, 23), 23),
+            (at(2024, 3, 31, 22), 24),
+        ]
+    );
+    assert_eq!(
+        windows
+            .iter()
+            .rev()
+            .take(3)
+            .map(|w| (w.window_start, w.sample_count))
+            .collect::<Vec<_>>(),
+        vec![
+            (at(2024, 10, 27, 23), 24),
+            (at(2024, 10, 26, 22), 25),
+            (at(2024, 10, 25, 22), 24),
+        ]
+    );
+}
+
+#[test]
+fn test_lttb() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let values = [1.0, 1.0, 1.0, 9.0, 1.0, 1.0, 1.0, 1.0, -5.0, 1.0, 1.0];
+    let data = values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| TestSample::new(start + step * i as i32, Some(*value)))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        lttb(&data, 4),
+        vec![
+            TestSample::new(start, Some(1.0)),
+            TestSample::new(start + step * 3, Some(9.0)),
+            TestSample::new(start + step * 8, Some(-5.0)),
+            TestSample::new(start + step * 10, Some(1.0)),
+        ]
+    );
+    assert_eq!(lttb(&data, 20), data);
+    assert_eq!(lttb(&data, 2), vec![data[0], data[10]]);
+    assert_eq!(lttb::<TestSample>(&[], 4), vec![]);
+}
+
+#[test]
+fn test_resampling_label_position_center() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let data = (1..=10)
+        .map(|i| TestSample::new(start + step * i, Some(i as f64)))
+        .collect::<Vec<_>>();
+
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Average,
+        1,
+        start,
+        false,
+    )
+    .with_label_position(LabelPosition::Center);
+    resampler.extend(data.clone());
+    assert_eq!(
+        resampler.resample(start + step * 10),
+        vec![
+            TestSample::new(start + TimeDelta::milliseconds(2500), Some(3.0)),
+            TestSample::new(start + TimeDelta::milliseconds(7500), Some(8.0)),
+        ]
+    );
+
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Sum,
+        1,
+        start,
+        false,
+    )
+    .with_window_spec(WindowSpec::Count(3))
+    .with_label_position(LabelPosition::Center);
+    resampler.extend(data);
+    assert_eq!(
+        resampler.resample(start + step * 10),
+        vec![
+            TestSample::new(start + step * 2, Some(6.0)),
+            TestSample::new(start + step * 5, Some(15.0)),
+            TestSample::new(start + step * 8, Some(24.0)),
+        ]
+    );
+}
+
+#[test]
+fn test_resampling_closed_left_labeled_right() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Sum,
+        1,
+        start,
+        false,
+    )
+    .with_closed(Closed::Left);
+    resampler.extend((0..10).map(|i| TestSample::new(start + step * i, Some(i as f64))));
+
+    // the samples at 0s and 5s belong to the windows starting at them, which
+    // are still labeled with their end
+    assert_eq!(
+        resampler.resample(start + step * 10),
+        vec![
+            TestSample::new(start + step * 5, Some(10.0)),
+            TestSample::new(start + step * 10, Some(35.0)),
+        ]
+    );
+}
+
+#[test]
+fn test_try_new() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let try_new = |interval, max_age| {
+        Resampler::<f64, TestSample>::try_new(
+            interval,
+            ResamplingFunction::Average,
+            max_age,
+            start,
+            false,
+        )
+        .map(|_| ())
+    };
+    assert_eq!(try_new(TimeDelta::seconds(5), 1), Ok(()));
+    assert_eq!(try_new(TimeDelta::seconds(5), 0), Ok(()));
+    assert_eq!(
+        try_new(TimeDelta::zero(), 1),
+        Err(ResampleError::InvalidInterval(TimeDelta::zero()))
+    );
+    assert_eq!(
+        try_new(TimeDelta::seconds(-5), 1),
+        Err(ResampleError::InvalidInterval(TimeDelta::seconds(-5)))
+    );
+    assert_eq!(
+        try_new(TimeDelta::microseconds(10), 1),
+        Err(ResampleError::InvalidInterval(TimeDelta::microseconds(10)))
+    );
+    assert_eq!(
+        try_new(TimeDelta::seconds(5), -1),
+        Err(ResampleError::InvalidMaxAge(-1))
+    );
+}
+
+#[test]
+fn test_try_resample() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Sum,
+        1,
+        start,
+        false,
+    );
+    resampler.extend((1..=5).map(|i| TestSample::new(start + step * i, Some(1.0))));
+
+    assert_eq!(
+        resampler.try_resample(start),
+        Err(ResampleError::EndNotAfterStart { start, end: start })
+    );
+    assert_eq!(
+        resampler.try_resample(start + step * 5),
+        Ok(vec![TestSample::new(start + step * 5, Some(5.0))])
+    );
+    assert_eq!(
+        resampler.try_resample(start + step * 3),
+        Err(ResampleError::EndNotAfterStart {
+            start: start + step * 5,
+            end: start + step * 3,
+        })
+    );
+    assert_eq!(resampler.resample(start + step * 3), vec![]);
+}
+
+#[test]
+fn test_strict_ordering() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Sum,
+        1,
+        start,
+        false,
+    )
+    .with_strict_ordering(true);
+
+    assert_eq!(
+        resampler.try_push(TestSample::new(start + step, Some(1.0))),
+        Ok(())
+    );
+    assert_eq!(
+        resampler.try_push(TestSample::new(start + step, Some(2.0))),
+        Err(ResampleError::OutOfOrder {
+            previous: start + step,
+            timestamp: start + step,
+        })
+    );
+    resampler.push(TestSample::new(start + step * 3, Some(3.0)));
+    resampler.push(TestSample::new(start + step * 2, Some(4.0)));
+    assert_eq!(
+        resampler.resample(start + step * 5),
+        vec![TestSample::new(start + step * 5, Some(4.0))]
+    );
+}
+
+#[test]
+fn test_allowed_lateness() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Sum,
+        1,
+        start,
+        false,
+    )
+    .with_allowed_lateness(TimeDelta::seconds(2));
+    resampler.extend((1..=5).map(|i| TestSample::new(start + step * i, Some(1.0))));
+
+    // the watermark hasn't passed the first window yet
+    assert_eq!(resampler.try_resample(start + step * 6), Ok(vec![]));
+    // late, but within the allowed lateness
+    resampler.push(TestSample::new(start + step * 4, Some(1.0)));
+    assert_eq!(
+        resampler.resample(start + step * 7),
+        vec![TestSample::new(start + step * 5, Some(6.0))]
+    );
+    // after the watermark passed its window
+    resampler.push(TestSample::new(start + step * 5, Some(1.0)));
+    resampler.push(TestSample::new(start + step * 6, Some(1.0)));
+    assert_eq!(resampler.late_samples(), 1);
+    assert_eq!(
+        resampler.resample(start + step * 12),
+        vec![TestSample::new(start + step * 10, Some(1.0))]
+    );
+}
+
+#[test]
+fn test_correction_of_late_samples() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Sum,
+        1,
+        start,
+        false,
+    )
+    .with_allowed_lateness(TimeDelta::seconds(1))
+    .with_correction_horizon(TimeDelta::seconds(5));
+    resampler.extend((1..=10).map(|i| TestSample::new(start + step * i, Some(1.0))));
+    assert_eq!(
+        resampler.resample(start + step * 11),
+        vec![
+            TestSample::new(start + step * 5, Some(5.0)),
+            TestSample::new(start + step * 10, Some(5.0)),
+        ]
+    );
+
+    // late samples for the second window, within the correction horizon, and
+    // for the first window, outside of it
+    resampler.push(TestSample::new(start + step * 7, Some(2.0)));
+    resampler.push(TestSample::new(start + step * 3, Some(2.0)));
+    resampler.push(TestSample::new(start + step * 12, Some(1.0)));
+    assert_eq!(resampler.late_samples(), 1);
+    assert_eq!(
+        resampler
+            .resample_detailed(start + step * 16)
+            .iter()
+            .map(|w| (w.correction, w.sample_count, w.sample))
+            .collect::<Vec<_>>(),
+        vec![
+            (true, 6, TestSample::new(start + step * 10, Some(7.0))),
+            (false, 1, TestSample::new(start + step * 15, Some(1.0))),
+        ]
+    );
+}
+
+#[test]
+fn test_drop_callback() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let dropped = Arc::new(std::sync::Mutex::new(vec![]));
+    let sink = dropped.clone();
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Sum,
+        1,
+        start,
+        false,
+    )
+    .with_strict_ordering(true)
+    .with_input_transform(Box::new(|s: &TestSample| s.value().map(|_| *s)))
+    .with_drop_callback(Box::new(move |s: &TestSample, reason| {
+        sink.lock().unwrap().push((s.timestamp(), reason));
+    }));
+
+    resampler.push(TestSample::new(start - step, Some(1.0)));
+    resampler.push(TestSample::new(start + step, Some(1.0)));
+    resampler.push(TestSample::new(start + step * 2, None));
+    resampler.push(TestSample::new(start + step, Some(1.0)));
+    assert_eq!(
+        *dropped.lock().unwrap(),
+        vec![
+            (start - step, DropReason::Expired),
+            (start + step * 2, DropReason::Filtered),
+            (start + step, DropReason::OutOfOrder),
+        ]
+    );
+}
+
+#[test]
+fn test_buffer_capacity() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Average,
+        1,
+        start,
+        false,
+    )
+    .with_capacity(100);
+    assert!(resampler.buffer().capacity() >= 100);
+
+    resampler.extend((1..=100).map(|i| TestSample::new(start + TimeDelta::seconds(i), None)));
+    resampler.reserve(1000);
+    assert!(resampler.buffer().capacity() >= 1100);
+}
+
+#[test]
+fn test_clear_and_reset() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Sum,
+        1,
+        start,
+        false,
+    );
+    resampler.extend((1..=5).map(|i| TestSample::new(start + step * i, Some(1.0))));
+    resampler.clear();
+    assert!(resampler.buffer().is_empty());
+    assert_eq!(
+        resampler.resample(start + step * 5),
+        vec![TestSample::new(start + step * 5, None)]
+    );
+
+    // restart the grid after a reconnect, 100s later
+    resampler.reset(start + step * 102);
+    resampler.extend((101..=110).map(|i| TestSample::new(start + step * i, Some(1.0))));
+    assert_eq!(
+        resampler.resample(start + step * 110),
+        vec![
+            TestSample::new(start + step * 105, Some(5.0)),
+            TestSample::new(start + step * 110, Some(5.0)),
+        ]
+    );
+}
+
+#[test]
+fn test_peek_resample() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Sum,
+        1,
+        start,
+        false,
+    );
+    resampler.extend((1..=8).map(|i| TestSample::new(start + step * i, Some(1.0))));
+
+    let expected = vec![
+        TestSample::new(start + step * 5, Some(5.0)),
+        TestSample::new(start + step * 10, Some(3.0)),
+    ];
+    assert_eq!(resampler.peek_resample(start + step * 10), expected);
+    assert_eq!(resampler.buffer().len(), 8);
+    assert_eq!(resampler.peek_resample(start + step * 10), expected);
+    assert_eq!(resampler.resample(start + step * 10), expected);
+}
+
+#[test]
+fn test_current_window() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Sum,
+        1,
+        start,
+        false,
+    );
+    resampler.extend(vec![
+        TestSample::new(start + step * 3, Some(1.0)),
+        TestSample::new(start + step * 4, None),
+        TestSample::new(start + step * 6, Some(2.0)),
+    ]);
+
+    let window = resampler.current_window();
+    assert_eq!(
+        (
+            window.window_start,
+            window.window_end,
+            window.sample_count,
+            window.none_count,
+            window.sample
+        ),
+        (
+            start,
+            start + step * 5,
+            2,
+            1,
+            TestSample::new(start + step * 5, Some(1.0))
+        )
+    );
+    assert_eq!(resampler.buffer().len(), 3);
+}
+
+#[test]
+fn test_set_interval() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let minute = TimeDelta::minutes(1);
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::minutes(5),
+        ResamplingFunction::Sum,
+        1,
+        start,
+        false,
+    );
+    resampler.extend((1..=60).map(|i| TestSample::new(start + minute * i, Some(1.0))));
+    assert_eq!(
+        resampler.resample(start + minute * 5),
+        vec![TestSample::new(start + minute * 5, Some(5.0))]
+    );
+
+    // the window from 5 to 15 minutes bridges to the new grid
+    resampler.set_interval(TimeDelta::minutes(15));
+    assert_eq!(
+        resampler.resample(start + minute * 30),
+        vec![
+            TestSample::new(start + minute * 15, Some(10.0)),
+            TestSample::new(start + minute * 30, Some(15.0)),
+        ]
+    );
+
+    resampler.set_interval(TimeDelta::minutes(10));
+    assert_eq!(
+        resampler.resample(start + minute * 60),
+        vec![
+            TestSample::new(start + minute * 40, Some(10.0)),
+            TestSample::new(start + minute * 50, Some(10.0)),
+            TestSample::new(start + minute * 60, Some(10.0)),
+        ]
+    );
+}
+
+#[test]
+fn test_builder() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let mut resampler: Resampler<f64, TestSample> = Resampler::builder()
+        .interval(TimeDelta::seconds(5))
+        .function(ResamplingFunction::Sum)
+        .max_age(1)
+        .start(start + step * 3)
+        .first_timestamp(false)
+        .align_to(start + step * 2)
+        .build()
+        .unwrap();
+    resampler.extend((1..=12).map(|i| TestSample::new(start + step * i, Some(1.0))));
+    assert_eq!(
+        resampler.resample(start + step * 12),
+        vec![
+            TestSample::new(start + step * 7, Some(5.0)),
+            TestSample::new(start + step * 12, Some(5.0)),
+        ]
+    );
+
+    assert_eq!(
+        Resampler::<f64, TestSample>::builder()
+            .start(start)
+            .build()
+            .map(|_| ()),
+        Err(ResampleError::InvalidInterval(TimeDelta::zero()))
+    );
+}
+
+#[test]
+fn test_snapshot_and_restore() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let new_resampler = || -> Resampler<f64, TestSample> {
+        Resampler::new(
+            TimeDelta::seconds(5),
+            ResamplingFunction::Average,
+            2,
+            start,
+            false,
+        )
+    };
+    let mut resampler = new_resampler();
+    resampler.extend((1..=7).map(|i| TestSample::new(start + step * i, Some(i as f64))));
+    resampler.resample(start + step * 5);
+    let state = resampler.snapshot();
+    assert_eq!(state.start(), start + step * 5);
+    assert_eq!(state.buffer().len(), 7);
+
+    // states of newer versions are rejected
+    let mut restored = new_resampler();
+    let mut unsupported = state.clone();
+    unsupported.version += 1;
+    assert_eq!(
+        restored.try_restore(unsupported),
+        Err(ResampleError::UnsupportedStateVersion {
+            version: state.version() + 1,
+            supported: state.version(),
+        })
+    );
+    assert_eq!(restored.buffer_len(), 0);
+
+    restored.restore(state);
+    resampler.extend((8..=10).map(|i| TestSample::new(start + step * i, Some(i as f64))));
+    restored.extend((8..=10).map(|i| TestSample::new(start + step * i, Some(i as f64))));
+    assert_eq!(
+        restored.resample(start + step * 10),
+        resampler.resample(start + step * 10)
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_snapshot_serde() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Average,
+        1,
+        start,
+        false,
+    );
+    resampler.extend((1..=7).map(|i| TestSample::new(start + TimeDelta::seconds(i), None)));
+    resampler.resample(start + TimeDelta::seconds(5));
+
+    let state = resampler.snapshot();
+    let json = serde_json::to_string(&state).unwrap();
+    assert_eq!(
+        serde_json::from_str::<ResamplerState<TestSample>>(&json).unwrap(),
+        state
+    );
+
+    // a state of the first version, which was serialized before the version
+    // and the later fields existed, is migrated when it is restored
+    let v1 = r#"{
+        "buffer": [
+            {"timestamp": "1970-01-01T00:00:01Z", "value": 1.0},
+            {"timestamp": "1970-01-01T00:00:02Z", "value": 2.0},
+            {"timestamp": "1970-01-01T00:00:03Z", "value": 3.0},
+            {"timestamp": "1970-01-01T00:00:04Z", "value": 4.0},
+            {"timestamp": "1970-01-01T00:00:05Z", "value": 5.0},
+            {"timestamp": "1970-01-01T00:00:06Z", "value": 6.0},
+            {"timestamp": "1970-01-01T00:00:07Z", "value": 7.0}
+        ],
+        "start": "1970-01-01T00:00:05Z",
+        "interval": [5, 0],
+        "pending_interval": null,
+        "input_start": "1970-01-01T00:00:01Z",
+        "input_interval": [5, 0],
+        "first_sample_at": "1970-01-01T00:00:01Z",
+        "last_resampled": {"timestamp": "1970-01-01T00:00:05Z", "value": 3.0},
+        "filter_history": [],
+        "smoother_state": null,
+        "rejected_outliers": 0,
+        "late_samples": 0,
+        "last_emitted": "1970-01-01T00:00:05Z",
+        "last_now": null,
+        "last_pushed": null,
+        "watermark": null,
+        "history": [],
+        "pending_corrections": []
+    }"#;
+    let v1 = serde_json::from_str::<ResamplerState<TestSample>>(v1).unwrap();
+    assert_eq!(v1.version(), 1);
+    let mut restored: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Average,
+        1,
+        start,
+        false,
+    );
+    assert_eq!(restored.try_restore(v1), Ok(()));
+    assert_eq!(restored.buffer_len(), 7);
+    assert_eq!(restored.buffer_high_watermark(), 7);
+    assert_eq!(restored.snapshot().version(), state.version());
+    let mut reference: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Average,
+        1,
+        start,
+        false,
+    );
+    reference
+        .extend((1..=7).map(|i| TestSample::new(start + TimeDelta::seconds(i), Some(i as f64))));
+    reference.resample(start + TimeDelta::seconds(5));
+    assert_eq!(
+        restored.resample(start + TimeDelta::seconds(10)),
+        reference.resample(start + TimeDelta::seconds(10))
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_sample() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let mut resampler: Resampler<f64, crate::SerdeSample<f64>> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Sum,
+        1,
+        start,
+        false,
+    );
+    resampler.extend((1..=5).map(|i| Sample::new(start + TimeDelta::seconds(i), Some(1.0))));
+    let batch = resampler.resample_batch(start + TimeDelta::seconds(5));
+    let json = serde_json::to_string(&batch).unwrap();
+    assert_eq!(
+        serde_json::from_str::<ResampledBatch<crate::SerdeSample<f64>>>(&json).unwrap(),
+        batch
+    );
+    assert!(json.contains(r#""samples":[{"timestamp":"1970-01-01T00:00:05Z","value":5.0}]"#));
+}
+
+#[test]
+fn test_max_age_duration() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Count,
+        1,
+        start,
+        false,
+    )
+    .with_max_age(TimeDelta::seconds(8));
+    // one sample every 4 seconds, which would extend `max_age_in_intervals`
+    resampler.extend((1..=5).map(|i| TestSample::new(start + step * (4 * i), Some(1.0))));
+
+    assert_eq!(
+        resampler.resample(start + step * 20),
+        vec![
+            TestSample::new(start + step * 5, Some(1.0)),
+            TestSample::new(start + step * 10, Some(2.0)),
+            TestSample::new(start + step * 15, Some(2.0)),
+            TestSample::new(start + step * 20, Some(2.0)),
+        ]
+    );
+}
+
+#[test]
+fn test_input_interval_rolling_median() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Count,
+        1,
+        start,
+        false,
+    )
+    .with_input_interval_detection(InputIntervalDetection::RollingMedian(3));
+    resampler.extend((1..=5).map(|i| TestSample::new(start + step * i, Some(1.0))));
+    assert_eq!(
+        resampler.resample(start + step * 5),
+        vec![TestSample::new(start + step * 5, Some(5.0))]
+    );
+
+    // the device slows down to one sample every 10 seconds, so every window
+    // looks back 10 seconds
+    resampler.extend((1..=3).map(|i| TestSample::new(start + step * (5 + 10 * i), Some(1.0))));
+    assert_eq!(
+        resampler
+            .resample(start + step * 35)
+            .iter()
+            .map(|s| s.value())
+            .collect::<Vec<_>>(),
+        vec![
+            Some(5.0),
+            Some(1.0),
+            Some(1.0),
+            Some(1.0),
+            Some(1.0),
+            Some(1.0)
+        ]
+    );
+}
+
+#[test]
+fn test_grid_accessors() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Average,
+        1,
+        start,
+        false,
+    )
+    .with_input_interval_detection(InputIntervalDetection::RollingMedian(3));
+    assert_eq!(resampler.input_interval(), None);
+    assert_eq!(resampler.window_start(), start);
+    assert_eq!(resampler.next_window_end(), start + step * 5);
+
+    resampler.extend((1..=3).map(|i| TestSample::new(start + step * (10 * i), Some(1.0))));
+    resampler.resample(start + step * 10);
+    assert_eq!(resampler.input_interval(), Some(step * 10));
+    assert_eq!(resampler.window_start(), start + step * 10);
+    assert_eq!(resampler.next_window_end(), start + step * 15);
+}
+
+#[test]
+fn test_memory_introspection() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Average,
+        1,
+        start,
+        false,
+    );
+    let empty_bytes = resampler.approximate_memory_bytes();
+    resampler.extend((1..=10).map(|i| TestSample::new(start + step * i, Some(1.0))));
+    assert_eq!(resampler.buffer_len(), 10);
+    assert!(
+        resampler.approximate_memory_bytes()
+            >= empty_bytes + 10 * std::mem::size_of::<TestSample>()
+    );
+
+    resampler.resample(start + step * 10);
+    assert_eq!(resampler.buffer_len(), 5);
+    assert_eq!(resampler.buffer_high_watermark(), 10);
+}
+
+#[test]
+fn test_stats() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Average,
+        1,
+        start,
+        false,
+    )
+    .with_strict_ordering(true);
+    resampler.push(TestSample::new(start - step * 10, Some(1.0)));
+    resampler.extend((1..=4).map(|i| TestSample::new(start + step * i, Some(1.0))));
+    resampler.push(TestSample::new(start + step * 2, Some(1.0)));
+    // dropped samples aren't buffered
+    assert_eq!(resampler.buffer_len(), 4);
+    resampler.resample(start + step * 15);
+
+    assert_eq!(
+        resampler.stats(),
+        ResamplerStats {
+            samples_pushed: 6,
+            expired_samples: 1,
+            out_of_order_samples: 1,
+            windows_emitted: 3,
+            empty_windows: 2,
+            ..Default::default()
+        }
+    );
+}
+
+#[derive(Default)]
+struct TestObserver {
+    events: Arc<Mutex<Vec<String>>>,
+}
+
+impl ResamplerObserver<TestSample> for TestObserver {
+    fn on_window_emitted(&mut self, window: &ResampledWindow<TestSample>) {
+        let mut events = self.events.lock().unwrap();
+        events.push(format!("emitted {}", window.sample_count));
+    }
+
+    fn on_sample_dropped(&mut self, _sample: &TestSample, reason: DropReason) {
+        let mut events = self.events.lock().unwrap();
+        events.push(format!("dropped {reason:?}"));
+    }
+
+    fn on_buffer_pruned(&mut self, pruned: usize, buffered: usize) {
+        let mut events = self.events.lock().unwrap();
+        events.push(format!("pruned {pruned} {buffered}"));
+    }
+}
+
+#[test]
+fn test_observer() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let observer = TestObserver::default();
+    let events = observer.events.clone();
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Average,
+        1,
+        start,
+        false,
+    )
+    .with_observer(Box::new(observer));
+    resampler.push(TestSample::new(start - step * 10, Some(1.0)));
+    resampler.extend((1..=7).map(|i| TestSample::new(start + step * i, Some(1.0))));
+    resampler.peek_resample(start + step * 10);
+    resampler.resample(start + step * 10);
+
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec!["dropped Expired", "emitted 5", "emitted 2", "pruned 5 2",]
+    );
+}
+
+#[test]
+fn test_buffer_front_pruning() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Average,
+        2,
+        start,
+        false,
+    );
+    for i in 0..10 {
+        resampler.extend((1..=5).map(|j| TestSample::new(start + step * (5 * i + j), Some(1.0))));
+        resampler.resample(start + step * (5 * i + 5));
+        assert_eq!(resampler.buffer_len(), if i == 0 { 5 } else { 10 });
+    }
+    assert_eq!(
+        resampler.buffer().front().map(|s| s.timestamp()),
+        Some(start + step * 41)
+    );
+}
+
+#[test]
+fn test_incremental_aggregation() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let samples = [
+        (1, Some(3.0)),
+        (3, None),
+        (2, Some(1.0)),
+        (4, Some(5.0)),
+        (7, Some(2.0)),
+        (12, None),
+    ];
+    let functions: [fn() -> ResamplingFunction<f64, TestSample>; 5] = [
+        || ResamplingFunction::Average,
+        || ResamplingFunction::Sum,
+        || ResamplingFunction::Count,
+        || ResamplingFunction::Min,
+        || ResamplingFunction::Max,
+    ];
+    for function in functions {
+        let new = |resampling_function| {
+            Resampler::new(TimeDelta::seconds(5), resampling_function, 1, start, false)
+        };
+        let mut incremental: Resampler<f64, TestSample> =
+            new(function()).with_incremental_aggregation(true);
+        // custom functions are computed from the buffered samples
+        let mut buffered = new(ResamplingFunction::Custom(Box::new(move |samples| {
+            function().apply(samples)
+        })));
+        for (i, value) in samples {
+            incremental.push(TestSample::new(start + step * i, value));
+            buffered.push(TestSample::new(start + step * i, value));
+        }
+        assert_eq!(incremental.buffer_len(), 0);
+        assert_eq!(incremental.current_window(), buffered.current_window());
+        assert_eq!(
+            incremental.resample_detailed(start + step * 15),
+            buffered.resample_detailed(start + step * 15)
+        );
+    }
+}
+
+#[cfg(feature = "simd")]
+#[test]
+fn test_simd_aggregation() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::milliseconds(1);
+    let samples = (1..=20_000)
+        .map(|i| TestSample::new(start + step * i, (i % 7 != 0).then_some((i % 101) as f64)))
+        .collect::<Vec<_>>();
+    let functions: [fn() -> ResamplingFunction<f64, TestSample>; 4] = [
+        || ResamplingFunction::Average,
+        || ResamplingFunction::Sum,
+        || ResamplingFunction::Min,
+        || ResamplingFunction::Max,
+    ];
+    for function in functions {
+        let new = |resampling_function| {
+            Resampler::new(TimeDelta::seconds(5), resampling_function, 2, start, false)
+        };
+        let mut simd: Resampler<f64, TestSample> = new(function()).with_simd_aggregation();
+        let mut generic = new(function());
+        simd.extend(samples.iter().copied());
+        generic.extend(samples.iter().copied());
+        assert_eq!(
+            simd.resample(start + TimeDelta::seconds(20)),
+            generic.resample(start + TimeDelta::seconds(20))
+        );
+    }
+    assert_eq!(crate::simd::min([f64::NAN, 2.0, 1.0]), Some(1.0));
+    assert!(crate::simd::max([f64::NAN]).unwrap().is_nan());
+    assert_eq!(crate::simd::sum([]), None);
+    assert_eq!(crate::simd::sum((1..=17).map(f64::from)), Some((153.0, 17)));
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_resample_all() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let mut resamplers = (0..100)
+        .map(|key| {
+            let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+                TimeDelta::seconds(5),
+                ResamplingFunction::Sum,
+                1,
+                start,
+                false,
+            );
+            resampler.extend((1..=10).map(|i| TestSample::new(start + step * i, Some(key as f64))));
+            (key, resampler)
+        })
+        .collect::<HashMap<_, _>>();
+
+    let resampled = crate::resample_all(&mut resamplers, start + step * 10);
+    assert_eq!(resampled.len(), 100);
+    for (key, samples) in resampled {
+        let sum = Some(5.0 * key as f64);
+        assert_eq!(
+            samples,
+            vec![
+                TestSample::new(start + step * 5, sum),
+                TestSample::new(start + step * 10, sum),
+            ]
+        );
+    }
+}
+
+#[test]
+fn test_extend_sorted() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let new = || -> Resampler<f64, TestSample> {
+        Resampler::new(
+            TimeDelta::seconds(5),
+            ResamplingFunction::Last,
+            2,
+            start,
+            false,
+        )
+    };
+    let mut resampler = new();
+    resampler.extend([1, 4, 7].map(|i| TestSample::new(start + step * i, Some(i as f64))));
+    resampler
+        .extend_sorted([2, 3, 8, 9].map(|i| TestSample::new(start + step * i, Some(i as f64))));
+    resampler.extend_sorted([6, 5].map(|i| TestSample::new(start + step * i, Some(i as f64))));
+    let timestamps = resampler
+        .buffer()
+        .iter()
+        .map(|s| s.timestamp())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        timestamps,
+        (1..=9).map(|i| start + step * i).collect::<Vec<_>>()
+    );
+
+    let mut pushed = new();
+    pushed.extend(
+        [1, 4, 7, 2, 3, 8, 9, 6, 5].map(|i| TestSample::new(start + step * i, Some(i as f64))),
+    );
+    assert_eq!(
+        resampler.resample(start + step * 10),
+        pushed.resample(start + step * 10)
+    );
+}
+
+#[test]
+fn test_resampler_group() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let mut group: ResamplerGroup<u64, f64, TestSample> =
+        ResamplerGroup::new(Box::new(move |component_id| {
+            let resampling_function = match component_id {
+                1 => ResamplingFunction::Sum,
+                _ => ResamplingFunction::Max,
+            };
+            Resampler::new(TimeDelta::seconds(5), resampling_function, 1, start, false)
+        }));
+    assert!(group.is_empty());
+    for i in 1..=5 {
+        group.push(1, TestSample::new(start + step * i, Some(i as f64)));
+        group.push(2, TestSample::new(start + step * i, Some(i as f64)));
+    }
+    assert_eq!(group.len(), 2);
+
+    let resampled = group.resample(start + step * 5);
+    assert_eq!(
+        resampled,
+        HashMap::from([
+            (1, vec![TestSample::new(start + step * 5, Some(15.0))]),
+            (2, vec![TestSample::new(start + step * 5, Some(5.0))]),
+        ])
+    );
+    assert!(group.remove(&1).is_some());
+    assert_eq!(group.keys().collect::<Vec<_>>(), vec![&2]);
+}
+
+#[test]
+fn test_resampler_group_idle_timeout() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let expired = Arc::new(Mutex::new(vec![]));
+    let expired_keys = expired.clone();
+    let mut group: ResamplerGroup<u64, f64, TestSample> =
+        ResamplerGroup::new(Box::new(move |_| {
+            Resampler::new(
+                TimeDelta::seconds(5),
+                ResamplingFunction::Last,
+                1,
+                start,
+                false,
+            )
+        }))
+        .with_idle_timeout(TimeDelta::seconds(10))
+        .with_expiry_callback(Box::new(move |key, _| {
+            expired_keys.lock().unwrap().push(key);
+        }));
+    group.push(1, TestSample::new(start + step, Some(1.0)));
+    group.push(2, TestSample::new(start + step, Some(2.0)));
+    group.resample(start + step * 5);
+    assert_eq!(group.len(), 2);
+
+    group.push(2, TestSample::new(start + step * 12, Some(3.0)));
+    group.resample(start + step * 15);
+    assert_eq!(group.keys().collect::<Vec<_>>(), vec![&2]);
+    assert_eq!(*expired.lock().unwrap(), vec![1]);
+
+    // a resampler without samples expires after the timeout since its first
+    // resampling
+    group.resampler_mut(3);
+    group.resample(start + step * 20);
+    assert_eq!(group.len(), 2);
+    group.resample(start + step * 31);
+    assert!(group.is_empty());
+    let mut expired = expired.lock().unwrap().clone();
+    expired.sort();
+    assert_eq!(expired, vec![1, 2, 3]);
+}
+
+#[test]
+fn test_resampler_group_quotas() {
+    use crate::group::{Quota, QuotaStats};
+
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let mut group: ResamplerGroup<u64, f64, TestSample> =
+        ResamplerGroup::new(Box::new(move |_| {
+            Resampler::new(
+                TimeDelta::seconds(1),
+                ResamplingFunction::Last,
+                1,
+                start,
+                false,
+            )
+        }))
+        .with_max_keys(2)
+        .with_max_pending_per_key(3)
+        .with_max_pending(5)
+        .with_max_output_per_key(2);
+
+    for i in 1..=4 {
+        let _ = group.try_push(1, TestSample::new(start + step * i, Some(i as f64)));
+    }
+    assert_eq!(
+        group.try_push(1, TestSample::new(start + step * 5, Some(5.0))),
+        Err(ResampleError::QuotaExceeded {
+            quota: Quota::PendingPerKey,
+            limit: 3
+        })
+    );
+    group.push(2, TestSample::new(start + step, Some(1.0)));
+    group.push(2, TestSample::new(start + step * 2, Some(2.0)));
+    assert_eq!(
+        group.try_push(2, TestSample::new(start + step * 3, Some(3.0))),
+        Err(ResampleError::QuotaExceeded {
+            quota: Quota::Pending,
+            limit: 5
+        })
+    );
+    assert_eq!(
+        group.try_push(3, TestSample::new(start + step, Some(1.0))),
+        Err(ResampleError::QuotaExceeded {
+            quota: Quota::Keys,
+            limit: 2
+        })
+    );
+    assert_eq!(group.pending_len(), 5);
+
+    // only the latest resampled samples are returned
+    let resampled = group.resample(start + step * 3);
+    assert_eq!(
+        resampled[&1],
+        vec![
+            TestSample::new(start + step * 2, Some(2.0)),
+            TestSample::new(start + step * 3, Some(3.0)),
+        ]
+    );
+    assert_eq!(
+        group.pending_len(),
+        group
+            .keys()
+            .map(|key| group.get(key).unwrap().pending_len())
+            .sum::<usize>()
+    );
+    assert_eq!(
+        group.quota_stats(),
+        QuotaStats {
+            rejected_keys: 1,
+            rejected_per_key: 2,
+            rejected_pending: 1,
+            dropped_outputs: 2,
+        }
+    );
+    assert!(group
+        .try_push(1, TestSample::new(start + step * 4, Some(4.0)))
+        .is_ok());
+}
+
+#[test]
+fn test_merge() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let new = |resampling_function, max_age| -> Resampler<f64, TestSample> {
+        Resampler::new(
+            TimeDelta::seconds(5),
+            resampling_function,
+            max_age,
+            start,
+            false,
+        )
+    };
+    // buffered samples
+    let mut resampler = new(ResamplingFunction::Last, 2);
+    let mut other = new(ResamplingFunction::Last, 2);
+    resampler.extend([1, 3, 6].map(|i| TestSample::new(start + step * i, Some(i as f64))));
+    other.extend([2, 4, 7].map(|i| TestSample::new(start + step * i, Some(i as f64))));
+    resampler.merge(other).unwrap();
+    let timestamps = resampler
+        .buffer()
+        .iter()
+        .map(|s| s.timestamp())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        timestamps,
+        [1, 2, 3, 4, 6, 7].map(|i| start + step * i).to_vec()
+    );
+
+    // partial aggregates
+    let mut resampler = new(ResamplingFunction::Average, 1);
+    let mut other = new(ResamplingFunction::Average, 1);
+    resampler.extend([1, 2, 6].map(|i| TestSample::new(start + step * i, Some(i as f64))));
+    other.extend([3, 7, 8].map(|i| TestSample::new(start + step * i, Some(i as f64))));
+    resampler.merge(other).unwrap();
+    assert_eq!(
+        resampler.resample(start + step * 10),
+        vec![
+            TestSample::new(start + step * 5, Some(2.0)),
+            TestSample::new(start + step * 10, Some(7.0)),
+        ]
+    );
+
+    let other = new(ResamplingFunction::Sum, 1);
+    assert!(matches!(
+        resampler.merge(other),
+        Err(ResampleError::IncompatibleConfiguration { .. })
+    ));
+}
+
+#[test]
+fn test_source_priority() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    // the fallback source 2 reports the values offset by 100
+    let mut resampler: Resampler<f64, SourceTestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Average,
+        1,
+        start,
+        false,
+    )
+    .with_source_priority(Box::new(|source| u32::from(source != 1)));
+    let sample = |i: i32, value: f64, source: u64| SourceTestSample {
+        timestamp: start + step * i,
+        value: Some(value),
+        source,
+    };
+    for i in 1..=15 {
+        // the primary source has an outage in the second interval
+        if !(6..=10).contains(&i) {
+            resampler.push(sample(i, i as f64, 1));
+        }
+        resampler.push(sample(i, 100.0 + i as f64, 2));
+    }
+    assert_eq!(
+        resampler
+            .resample(start + step * 15)
+            .iter()
+            .map(|s| s.value())
+            .collect::<Vec<_>>(),
+        vec![Some(3.0), Some(108.0), Some(13.0)]
+    );
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(start_paused = true)]
+async fn test_spawn_resampler() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    // a clock that follows the paused time of the runtime
+    let base = tokio::time::Instant::now();
+    let resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Average,
+        1,
+        start,
+        false,
+    )
+    .with_clock(Box::new(move || {
+        start + TimeDelta::from_std(base.elapsed()).unwrap()
+    }));
+    let (sample_tx, sample_rx) = tokio::sync::mpsc::channel(16);
+    let (batch_tx, mut batch_rx) = tokio::sync::mpsc::channel(16);
+    let handle = crate::spawn_resampler(resampler, sample_rx, batch_tx);
+
+    for i in 1..=9 {
+        sample_tx
+            .send(TestSample::new(start + step * i, Some(i as f64)))
+            .await
+            .unwrap();
+    }
+    let batch = batch_rx.recv().await.unwrap();
+    assert_eq!(
+        batch.samples,
+        vec![TestSample::new(start + step * 5, Some(3.0))]
+    );
+    assert_eq!(base.elapsed(), std::time::Duration::from_secs(5));
+    let batch = batch_rx.recv().await.unwrap();
+    assert_eq!(
+        batch.samples,
+        vec![TestSample::new(start + step * 10, Some(7.5))]
+    );
+
+    drop(sample_tx);
+    let resampler = handle.await.unwrap();
+    assert_eq!(resampler.window_start(), start + step * 10);
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn test_resample_completed_now() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let now = Arc::new(Mutex::new(start));
+    let clock = now.clone();
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Average,
+        1,
+        start,
+        false,
+    )
+    .with_clock(Box::new(move || *clock.lock().unwrap()));
+    resampler.extend((1..=9).map(|i| TestSample::new(start + step * i, Some(i as f64))));
+
+    // the window in progress isn't resampled
+    *now.lock().unwrap() = start + TimeDelta::milliseconds(6500);
+    assert_eq!(
+        resampler.resample_completed_now(),
+        vec![TestSample::new(start + step * 5, Some(3.0))]
+    );
+    assert_eq!(resampler.resample_completed_now(), vec![]);
+    *now.lock().unwrap() = start + TimeDelta::milliseconds(10_001);
+    assert_eq!(
+        resampler.resample_completed_now(),
+        vec![TestSample::new(start + step * 10, Some(7.5))]
+    );
+}
+
+#[test]
+fn test_window_callback() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Sum,
+        1,
+        start,
+        false,
+    )
+    .with_window_callback(Box::new(move |sample| {
+        sender.send(sample).unwrap();
+    }));
+    resampler.extend((1..=10).map(|i| TestSample::new(start + step * i, Some(1.0))));
+    resampler.peek_resample(start + step * 10);
+    assert!(receiver.try_recv().is_err());
+
+    let resampled = resampler.resample(start + step * 10);
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), resampled);
+    assert_eq!(resampled.len(), 2);
+}
+
+#[test]
+fn test_emission_on_push() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let new = || -> Resampler<f64, TestSample> {
+        Resampler::new(
+            TimeDelta::seconds(5),
+            ResamplingFunction::Sum,
+            1,
+            start,
+            false,
+        )
+    };
+    let mut resampler = new();
+    let emitted = (1..=12)
+        .map(|i| resampler.push_and_emit(TestSample::new(start + step * i, Some(1.0))))
+        .collect::<Vec<_>>();
+    // the windows are closed by the first sample after their end
+    for (i, samples) in emitted.iter().enumerate() {
+        match i + 1 {
+            6 => assert_eq!(samples, &vec![TestSample::new(start + step * 5, Some(5.0))]),
+            11 => assert_eq!(
+                samples,
+                &vec![TestSample::new(start + step * 10, Some(5.0))]
+            ),
+            _ => assert!(samples.is_empty()),
+        }
+    }
+
+    // gaps close all windows up to the sample
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let mut resampler = new()
+        .with_emission_policy(EmissionPolicy::OnPush)
+        .with_window_callback(Box::new(move |sample| {
+            sender.send(sample).unwrap();
+        }));
+    resampler.push(TestSample::new(start + step * 2, Some(1.0)));
+    resampler.push(TestSample::new(start + step * 16, Some(1.0)));
+    assert_eq!(
+        receiver.try_iter().collect::<Vec<_>>(),
+        vec![
+            TestSample::new(start + step * 5, Some(1.0)),
+            TestSample::new(start + step * 10, None),
+            TestSample::new(start + step * 15, None),
+        ]
+    );
+}
+
+#[test]
+fn test_resample_iterator() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let samples = (0..12).map(|i| TestSample::new(start + step * i, Some(i as f64)));
+    let mut resampled = samples.resample(TimeDelta::seconds(5), ResamplingFunction::Sum, start);
+    assert_eq!(resampled.next(), Some(TestSample::new(start, Some(10.0))));
+    assert_eq!(resampled.resampler().stats().samples_pushed, 6);
+    assert_eq!(
+        resampled.collect::<Vec<_>>(),
+        vec![
+            TestSample::new(start + step * 5, Some(35.0)),
+            TestSample::new(start + step * 10, Some(21.0)),
+        ]
+    );
+
+    let resampler = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Last,
+        1,
+        start,
+        false,
+    );
+    let resampled = std::iter::empty::<TestSample>().resample_with(resampler);
+    assert_eq!(resampled.count(), 0);
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn test_resampler_sink() {
+    use futures::StreamExt;
+
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Sum,
+        1,
+        start,
+        false,
+    );
+    let mut sink = crate::ResamplerSink::new(resampler);
+    let samples = (1..=12).map(|i| Ok(TestSample::new(start + step * i, Some(1.0))));
+    futures::executor::block_on(futures::stream::iter(samples).forward(&mut sink)).unwrap();
+    assert_eq!(
+        sink.take_resampled(),
+        vec![
+            TestSample::new(start + step * 5, Some(5.0)),
+            TestSample::new(start + step * 10, Some(5.0)),
+        ]
+    );
+    assert!(sink.take_resampled().is_empty());
+
+    let mut resampler = sink.into_inner();
+    assert_eq!(
+        resampler.resample(start + step * 15),
+        vec![TestSample::new(start + step * 15, Some(2.0))]
+    );
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_bounded_resampler() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Last,
+        1,
+        start,
+        false,
+    );
+    let bounded = crate::BoundedResampler::new(resampler, 5);
+    for i in 1..=5 {
+        bounded
+            .push(TestSample::new(start + step * i, Some(i as f64)))
+            .await
+            .unwrap();
+    }
+    assert_eq!(
+        bounded.try_push(TestSample::new(start + step * 6, Some(6.0))),
+        Err(ResampleError::BufferFull { capacity: 5 })
+    );
+
+    // the producer waits until the buffer is pruned
+    let producer = bounded.clone();
+    let push = tokio::spawn(async move {
+        producer
+            .push(TestSample::new(start + step * 11, Some(11.0)))
+            .await
+    });
+    tokio::task::yield_now().await;
+    assert!(!push.is_finished());
+    assert_eq!(
+        bounded.resample(start + step * 10)[0],
+        TestSample::new(start + step * 5, Some(5.0))
+    );
+    push.await.unwrap().unwrap();
+    assert_eq!(bounded.buffer_len(), 1);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_bounded_resampler_incremental() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Sum,
+        1,
+        start,
+        false,
+    )
+    .with_incremental_aggregation(true);
+    let bounded = crate::BoundedResampler::new(resampler, 3);
+    for i in 1..=3 {
+        bounded
+            .try_push(TestSample::new(start + step * i, Some(i as f64)))
+            .unwrap();
+    }
+
+    // the aggregated samples count towards the capacity
+    assert_eq!(bounded.buffer_len(), 0);
+    assert_eq!(bounded.pending_len(), 3);
+    assert_eq!(
+        bounded.try_push(TestSample::new(start + step * 4, Some(4.0))),
+        Err(ResampleError::BufferFull { capacity: 3 })
+    );
+    assert_eq!(
+        bounded.resample(start + step * 5),
+        vec![TestSample::new(start + step * 5, Some(6.0))]
+    );
+    assert_eq!(bounded.pending_len(), 0);
+    bounded
+        .push(TestSample::new(start + step * 6, Some(6.0)))
+        .await
+        .unwrap();
+    assert_eq!(bounded.pending_len(), 1);
+}
+
+#[test]
+fn test_time_axis() {
+    let tick = TimeDelta::milliseconds(100);
+    assert_eq!((tick * 7).align(tick * 5, tick), tick * 6);
+    assert_eq!((tick * 7).delta_since(tick * 9), -tick * 2);
+    assert_eq!(tick.add_duration(tick), tick * 2);
+
+    // samples stamped with ticks since the start of a control loop
+    let mut resampler: Resampler<f64, AxisSample<TimeDelta, f64>> = Resampler::new(
+        tick * 5,
+        ResamplingFunction::Average,
+        1,
+        TimeDelta::zero().to_utc(),
+        true,
+    );
+    resampler.extend((0..10).map(|i| AxisSample {
+        time: tick * i,
+        value: Some(i as f64),
+    }));
+    let resampled = resampler.resample((tick * 10).to_utc());
+    assert_eq!(
+        resampled,
+        vec![
+            AxisSample {
+                time: TimeDelta::zero(),
+                value: Some(2.0)
+            },
+            AxisSample {
+                time: tick * 5,
+                value: Some(7.0)
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_system_time_axis() {
+    let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let interval = TimeDelta::seconds(5);
+    let at = |seconds: f64| start + Duration::from_secs_f64(seconds);
+    assert_eq!(at(7.5).align(interval, SystemTime::UNIX_EPOCH), at(5.0));
+    assert_eq!(
+        Duration::from_millis(7_500).align(interval, Duration::ZERO),
+        Duration::from_secs(5)
+    );
+    assert_eq!(
+        Duration::from_nanos(7).align(TimeDelta::nanoseconds(5), Duration::from_nanos(1)),
+        Duration::from_nanos(6)
+    );
+
+    // times beyond the range of the UTC time line are clamped instead of
+    // panicking
+    assert_eq!(Duration::MAX.to_utc(), DateTime::<Utc>::MAX_UTC);
+    assert_eq!(TimeDelta::MAX.to_utc(), DateTime::<Utc>::MAX_UTC);
+    assert_eq!(TimeDelta::MIN.to_utc(), DateTime::<Utc>::MIN_UTC);
+    assert_eq!(
+        (SystemTime::UNIX_EPOCH + Duration::from_secs(u64::MAX / 4)).to_utc(),
+        DateTime::<Utc>::MAX_UTC
+    );
+
+    let mut resampler: Resampler<f64, AxisSample<SystemTime, f64>> =
+        Resampler::new(interval, ResamplingFunction::Max, 1, start.to_utc(), true);
+    resampler.extend((0..10).map(|i| AxisSample {
+        time: at(i as f64),
+        value: Some(i as f64),
+    }));
+    assert_eq!(
+        resampler.resample(at(10.0).to_utc()),
+        vec![
+            AxisSample {
+                time: start,
+                value: Some(4.0)
+            },
+            AxisSample {
+                time: at(5.0),
+                value: Some(9.0)
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_monotonic_time_axis() {
+    let now = Instant::now();
+    let tick = Duration::from_millis(100);
+    assert_eq!(Instant::from_utc(now.to_utc()), now);
+    assert_eq!(
+        (now + tick).to_utc() - now.to_utc(),
+        TimeDelta::milliseconds(100)
+    );
+
+    let start = now.to_utc();
+    let mut resampler: Resampler<f64, AxisSample<Instant, f64>> = Resampler::new(
+        TimeDelta::milliseconds(500),
+        ResamplingFunction::Count,
+        1,
+        start,
+        true,
+    )
+    .with_monotonic_clock();
+    resampler.extend((0..10).map(|i| AxisSample {
+        time: now + tick * i,
+        value: Some(1.0),
+    }));
+    let window_start = resampler.window_start();
+    let resampled = resampler.resample(window_start + TimeDelta::seconds(1));
+    assert_eq!(resampled.len(), 2);
+    assert_eq!(resampled[0].time.to_utc(), window_start);
+    assert!(resampler.now() >= start);
+}
+
+#[cfg(feature = "arrow")]
+#[test]
+fn test_extend_from_arrow() {
+    use arrow_array::{Float64Array, TimestampNanosecondArray};
+
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Count,
+        1,
+        start,
+        false,
+    );
+    let timestamps = TimestampNanosecondArray::from(vec![
+        Some(1_000_000_000),
+        None,
+        Some(2_000_000_000),
+        Some(6_000_000_000),
+    ]);
+    let values = Float64Array::from(vec![Some(1.0), Some(2.0), None, Some(3.0)]);
+    resampler.extend_from_arrow(&timestamps, &values).unwrap();
+    assert_eq!(
+        resampler.resample(start + TimeDelta::seconds(10)),
+        vec![
+            TestSample::new(start + TimeDelta::seconds(5), Some(1.0)),
+            TestSample::new(start + TimeDelta::seconds(10), Some(1.0)),
+        ]
+    );
+    assert_eq!(resampler.stats().samples_pushed, 3);
+    assert_eq!(
+        resampler.extend_from_arrow(&timestamps, &Float64Array::from(vec![1.0])),
+        Err(ResampleError::LengthMismatch {
+            timestamps: 4,
+            values: 1
+        })
+    );
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn test_parquet_writer() {
+    use arrow_array::{Array, Float64Array, StringArray};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let new = || -> Resampler<f64, TestSample> {
+        Resampler::new(
+            TimeDelta::seconds(5),
+            ResamplingFunction::Sum,
+            1,
+            start,
+            false,
+        )
+    };
+    let (mut first, mut second) = (new(), new());
+    first.extend((1..=5).map(|i| TestSample::new(start + TimeDelta::seconds(i), Some(1.0))));
+    second.extend((6..=10).map(|i| TestSample::new(start + TimeDelta::seconds(i), Some(2.0))));
+
+    let path = std::env::temp_dir().join(format!("resampled-{}.parquet", std::process::id()));
+    let file = std::fs::File::create(&path).unwrap();
+    let mut writer = crate::ParquetWriter::try_new(file).unwrap();
+    writer
+        .write(
+            "first",
+            &first.resample_detailed(start + TimeDelta::seconds(10)),
+        )
+        .unwrap();
+    writer
+        .write(
+            "second",
+            &second.resample_detailed(start + TimeDelta::seconds(10)),
+        )
+        .unwrap();
+    writer.close().unwrap();
+
+    let file = std::fs::File::open(&path).unwrap();
+    let batches = ParquetRecordBatchReaderBuilder::try_new(file)
+        .unwrap()
+        .build()
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let (channels, values) = read_columns(&batches);
+    let channels = channels.iter().flatten().collect::<Vec<_>>();
+    assert_eq!(channels, vec!["first", "first", "second", "second"]);
+    assert_eq!(values, vec![Some(5.0), None, None, Some(10.0)]);
+
+    fn read_columns(
+        batches: &[arrow_array::RecordBatch],
+    ) -> (Vec<Option<String>>, Vec<Option<f64>>) {
+        let mut res = (vec![], vec![]);
+        for batch in batches {
+            let channels = batch
+                .column_by_name("channel")
+                .and_then(|c| c.as_any().downcast_ref::<StringArray>())
+                .unwrap();
+            let values = batch
+                .column_by_name("value")
+                .and_then(|c| c.as_any().downcast_ref::<Float64Array>())
+                .unwrap();
+            res.0.extend(channels.iter().map(|c| c.map(String::from)));
+            res.1.extend(values.iter());
+        }
+        res
+    }
+}
+
+#[cfg(feature = "polars")]
+#[test]
+fn test_dataframe() {
+    use polars::prelude::*;
+
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Average,
+        1,
+        start,
+        false,
+    );
+    let timestamps = Int64Chunked::from_iter_options(
+        "ts".into(),
+        [Some(1_000), Some(2_000), None, Some(6_000)].into_iter(),
+    )
+    .into_datetime(TimeUnit::Milliseconds, None);
+    let df = DataFrame::new(vec![
+        timestamps.into_column(),
+        Column::new("power".into(), [Some(1), Some(3), Some(5), None]),
+    ])
+    .unwrap();
+    resampler.extend_from_dataframe(&df, "ts", "power").unwrap();
+    assert!(resampler
+        .extend_from_dataframe(&df, "ts", "energy")
+        .is_err());
+
+    let resampled = resampler
+        .resample_dataframe(start + TimeDelta::seconds(10))
+        .unwrap();
+    let expected = DataFrame::new(vec![
+        Int64Chunked::from_iter_values(
+            "timestamp".into(),
+            [5_000_000_000, 10_000_000_000].into_iter(),
+        )
+        .into_datetime(TimeUnit::Nanoseconds, Some(TimeZone::UTC))
+        .into_column(),
+        Column::new("value".into(), [Some(2.0), None]),
+    ])
+    .unwrap();
+    assert!(resampled.equals_missing(&expected));
+}
+
+#[cfg(feature = "ndarray")]
+#[test]
+fn test_ndarray() {
+    use ndarray::array;
+
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Max,
+        1,
+        start,
+        false,
+    );
+    let timestamps = array![1_000_000_000, 2_000_000_000, 6_000_000_000];
+    let values = array![1.0, 4.0, f64::NAN];
+    resampler
+        .extend_from_ndarray(timestamps.view(), values.view())
+        .unwrap();
+    assert!(resampler
+        .extend_from_ndarray(timestamps.view(), values.slice(ndarray::s![..1]))
+        .is_err());
+
+    let (timestamps, values) = resampler.resample_ndarray(start + TimeDelta::seconds(10));
+    assert_eq!(timestamps, array![5_000_000_000, 10_000_000_000]);
+    assert_eq!(values[0], 4.0);
+    assert!(values[1].is_nan());
+}
+
+#[cfg(feature = "csv")]
+#[test]
+fn test_resample_csv() {
+    use crate::csv_io::{resample_csv, CsvError, TimestampFormat};
+
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let new = || -> Resampler<f64, TestSample> {
+        Resampler::new(
+            TimeDelta::seconds(5),
+            ResamplingFunction::Average,
+            1,
+            start,
+            true,
+        )
+    };
+    let input = "timestamp,value\n1,1\n2.5,\n3,3\n6,6\n";
+    let mut output = vec![];
+    resample_csv(
+        &mut new(),
+        input.as_bytes(),
+        &mut output,
+        &TimestampFormat::UnixSeconds,
+    )
+    .unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "timestamp,value\n0,2\n5,6\n"
+    );
+
+    let format = TimestampFormat::Custom("%Y-%m-%d %H:%M:%S".to_string());
+    let input = "time,power\n1970-01-01 00:00:01,1.5\n";
+    let mut resampler = new();
+    resampler
+        .extend_from_csv(input.as_bytes(), &format)
+        .unwrap();
+    let mut output = vec![];
+    resampler
+        .resample_to_csv(
+            start + TimeDelta::seconds(5),
+            &mut output,
+            &TimestampFormat::Rfc3339,
+        )
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "timestamp,value\n1970-01-01T00:00:00+00:00,1.5\n"
+    );
+
+    assert!(matches!(
+        new().extend_from_csv("timestamp,value\nnow,1\n".as_bytes(), &format),
+        Err(CsvError::InvalidTimestamp(_))
+    ));
+}
+
+#[test]
+fn test_line_protocol() {
+    use crate::line_protocol::{LineProtocolError, LineProtocolSelector, TimestampPrecision};
+
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Average,
+        1,
+        start,
+        true,
+    )
+    .with_clock(Box::new(move || start + TimeDelta::seconds(4)));
+    let selector = LineProtocolSelector::new("power meter", "active_power")
+        .with_tag("site", "a=1")
+        .with_precision(TimestampPrecision::Seconds);
+    let points = r#"# a comment
+power\ meter,site=a\=1,phase=l1 active_power=1.0,state="on, idle" 1
+power\ meter,site=a\=1 reactive_power=10.0 2
+power\ meter,site=b active_power=100.0 2
+voltage,site=a\=1 active_power=100.0 2
+
+power\ meter,site=a\=1 active_power=2i 3
+power\ meter,site=a\=1 active_power=6u
+"#;
+    assert_eq!(
+        resampler.extend_from_line_protocol(points, &selector),
+        Ok(3)
+    );
+    assert_eq!(
+        resampler.resample(start + TimeDelta::seconds(5)),
+        vec![TestSample::new(start, Some(3.0))]
+    );
+
+    assert_eq!(
+        resampler
+            .extend_from_line_protocol(r#"power\ meter,site=a\=1 active_power="x" 6"#, &selector),
+        Err(LineProtocolError::InvalidValue("\"x\"".to_string()))
+    );
+    assert_eq!(
+        resampler
+            .extend_from_line_protocol(r#"power\ meter,site=a\=1 active_power=1 now"#, &selector),
+        Err(LineProtocolError::InvalidTimestamp("now".to_string()))
+    );
+    assert!(matches!(
+        resampler.extend_from_line_protocol("power\\ meter", &selector),
+        Err(LineProtocolError::Syntax(_))
+    ));
+}
+
+#[cfg(feature = "prometheus")]
+#[test]
+fn test_render_prometheus() {
+    use crate::group::ResamplerGroup;
+
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let mut group: ResamplerGroup<String, f64, TestSample> =
+        ResamplerGroup::new(Box::new(move |_| {
+            Resampler::new(
+                TimeDelta::seconds(5),
+                ResamplingFunction::Average,
+                1,
+                start,
+                true,
+            )
+        }));
+    assert_eq!(group.render_prometheus("power"), "");
+
+    let step = TimeDelta::seconds(1);
+    group.extend(
+        "inverter.1/power".to_string(),
+        vec![
+            TestSample::new(start + step, Some(1.0)),
+            TestSample::new(start + step * 6, Some(2.0)),
+            TestSample::new(start + step * 7, Some(4.0)),
+        ],
+    );
+    group.push("1_battery".to_string(), TestSample::new(start + step, None));
+    group.push(
+        "meter \"a\"\\b\n".to_string(),
+        TestSample::new(start + step * 6, Some(-1.0)),
+    );
+    group.resample(start + step * 10);
+
+    assert_eq!(
+        group.latest(&"inverter.1/power".to_string()),
+        Some(&TestSample::new(start + step * 5, Some(3.0)))
+    );
+    assert_eq!(
+        group.render_prometheus("1.power"),
+        "# TYPE _1_power gauge\n\
+         _1_power{key=\"1_battery\"} NaN 5000\n\
+         _1_power{key=\"inverter.1/power\"} 3 5000\n\
+         _1_power{key=\"meter \\\"a\\\"\\\\b\\n\"} -1 5000\n"
+    );
+}
+
+/// A power value that can't be divided by itself, like the unit types of
+/// quantity crates.
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd)]
+struct Power {
+    watts: f64,
+}
+
+impl Sum for Power {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        Power {
+            watts: iter.map(|p| p.watts).sum(),
+        }
+    }
+}
+
+impl ResampleValue for Power {
+    fn from_scalar(value: f64) -> Option<Self> {
+        Some(Power { watts: value })
+    }
+
+    fn from_count(_count: usize) -> Option<Self> {
+        None
+    }
+
+    fn div_scalar(self, divisor: f64) -> Option<Self> {
+        Some(Power {
+            watts: self.watts / divisor,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct PowerSample {
+    timestamp: DateTime<Utc>,
+    value: Option<Power>,
+}
+
+impl Sample for PowerSample {
+    type Value = Power;
+
+    fn new(timestamp: DateTime<Utc>, value: Option<Power>) -> Self {
+        Self { timestamp, value }
+    }
+
+    fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    fn value(&self) -> Option<Power> {
+        self.value
+    }
+}
+
+#[test]
+fn test_resampling_unit_typed_values() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let data = (1..=10)
+        .map(|i| {
+            PowerSample::new(
+                start + step * i,
+                Some(Power {
+                    watts: f64::from(i),
+                }),
+            )
+        })
+        .collect::<Vec<_>>();
+    let resample = |function| {
+        let mut resampler: Resampler<Power, PowerSample> =
+            Resampler::new(TimeDelta::seconds(5), function, 1, start, false);
+        resampler.extend(data.clone());
+        resampler
+            .resample(start + step * 10)
+            .into_iter()
+            .map(|s| s.value().map(|p| p.watts))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        resample(ResamplingFunction::Average),
+        vec![Some(3.0), Some(8.0)]
+    );
+    assert_eq!(
+        resample(ResamplingFunction::Sum),
+        vec![Some(15.0), Some(40.0)]
+    );
+    assert_eq!(
+        resample(ResamplingFunction::Max),
+        vec![Some(5.0), Some(10.0)]
+    );
+    assert_eq!(
+        resample(ResamplingFunction::Rate(RateOf::Sum)),
+        vec![Some(3.75), Some(8.0)]
+    );
+}
+
+#[cfg(feature = "uom")]
+#[test]
+fn test_resampling_uom_quantities() {
+    use crate::quantity::Quantity;
+    use uom::si::f64::Power;
+    use uom::si::power::{kilowatt, watt};
+
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let data = (1..=10)
+        .map(|i| AxisSample {
+            time: start + step * i,
+            value: Some(Quantity(Power::new::<watt>(f64::from(i) * 100.0))),
+        })
+        .collect::<Vec<_>>();
+    let resample = |function| {
+        let mut resampler: Resampler<Quantity<Power>, AxisSample<DateTime<Utc>, Quantity<Power>>> =
+            Resampler::new(TimeDelta::seconds(5), function, 1, start, false);
+        resampler.extend(data.clone());
+        resampler
+            .resample(start + step * 10)
+            .into_iter()
+            .map(|s| s.value.map(|p| p.into_inner().get::<kilowatt>()))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        resample(ResamplingFunction::Average),
+        vec![Some(0.3), Some(0.8)]
+    );
+    assert_eq!(
+        resample(ResamplingFunction::Sum),
+        vec![Some(1.5), Some(4.0)]
+    );
+    assert_eq!(
+        resample(ResamplingFunction::Min),
+        vec![Some(0.1), Some(0.6)]
+    );
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct BoolSample {
+    timestamp: DateTime<Utc>,
+    value: Option<bool>,
+}
+
+impl Sample for BoolSample {
+    type Value = bool;
+
+    fn new(timestamp: DateTime<Utc>, value: Option<bool>) -> Self {
+        Self { timestamp, value }
+    }
+
+    fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    fn value(&self) -> Option<bool> {
+        self.value
+    }
+}
+
+#[test]
+fn test_bool_resampler() {
+    use crate::boolean::{BoolAggregation, BoolResampler};
+
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let data = vec![
+        BoolSample::new(start + step * 2, Some(true)),
+        BoolSample::new(start + step * 3, Some(false)),
+        BoolSample::new(start + step * 6, Some(true)),
+        BoolSample::new(start + step * 13, None),
+        BoolSample::new(start + step * 14, Some(false)),
+    ];
+    let resample = |aggregation| {
+        let mut resampler: BoolResampler<BoolSample, TestSample> =
+            BoolResampler::new(TimeDelta::seconds(5), aggregation, start);
+        resampler.extend(data.clone());
+        let mut values = resampler
+            .resample(start + step * 12)
+            .into_iter()
+            .map(|s| s.value())
+            .collect::<Vec<_>>();
+        values.extend(
+            resampler
+                .resample(start + step * 20)
+                .iter()
+                .map(|s| s.value()),
+        );
+        assert_eq!(resampler.window_start(), start + step * 20);
+        values
+    };
+
+    assert_eq!(
+        resample(BoolAggregation::Any),
+        vec![Some(1.0), Some(1.0), Some(1.0), Some(0.0)]
+    );
+    assert_eq!(
+        resample(BoolAggregation::All),
+        vec![Some(0.0), Some(0.0), Some(0.0), Some(0.0)]
+    );
+    assert_eq!(
+        resample(BoolAggregation::DutyCycle),
+        vec![Some(1.0 / 3.0), Some(0.8), Some(0.75), Some(0.0)]
+    );
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct CounterSample<V = i64> {
+    timestamp: DateTime<Utc>,
+    value: Option<V>,
+}
+
+impl<V: Copy + std::fmt::Debug + Default> Sample for CounterSample<V> {
+    type Value = V;
+
+    fn new(timestamp: DateTime<Utc>, value: Option<V>) -> Self {
+        Self { timestamp, value }
+    }
+
+    fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    fn value(&self) -> Option<V> {
+        self.value
+    }
+}
+
+#[test]
+fn test_overflow_policy() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let data = vec![
+        CounterSample::new(start + step, Some(i64::MAX)),
+        CounterSample::new(start + step * 2, Some(i64::MAX - 2)),
+        CounterSample::new(start + step * 6, Some(-3)),
+        CounterSample::new(start + step * 7, Some(-4)),
+        CounterSample::new(start + step * 8, None),
+    ];
+    let resample = |function, policy| {
+        let mut resampler: Resampler<i64, CounterSample> =
+            Resampler::new(TimeDelta::seconds(5), function, 1, start, true)
+                .with_overflow_policy(policy);
+        resampler.extend(data.clone());
+        resampler
+            .resample(start + step * 15)
+            .into_iter()
+            .map(|s| s.value())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        resample(ResamplingFunction::Sum, OverflowPolicy::Checked),
+        vec![None, Some(-7), None]
+    );
+    assert_eq!(
+        resample(ResamplingFunction::Sum, OverflowPolicy::Saturating),
+        vec![Some(i64::MAX), Some(-7), None]
+    );
+    assert_eq!(
+        resample(ResamplingFunction::Average, OverflowPolicy::Checked),
+        vec![Some(i64::MAX - 1), Some(-3), None]
+    );
+    assert_eq!(
+        resample(ResamplingFunction::Max, OverflowPolicy::Checked),
+        vec![Some(i64::MAX), Some(-3), None]
+    );
+
+    // values and sums beyond the range of the accumulator result in None
+    // instead of falling back to the generic aggregation
+    let data = vec![
+        CounterSample::new(start + step, Some(u128::MAX)),
+        CounterSample::new(start + step * 6, Some(i128::MAX as u128)),
+        CounterSample::new(start + step * 7, Some(1)),
+        CounterSample::new(start + step * 11, Some(2)),
+    ];
+    let resample = |function, policy| {
+        let mut resampler: Resampler<u128, CounterSample<u128>> =
+            Resampler::new(TimeDelta::seconds(5), function, 1, start, true)
+                .with_overflow_policy(policy);
+        resampler.extend(data.clone());
+        resampler
+            .resample(start + step * 15)
+            .into_iter()
+            .map(|s| s.value())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        resample(ResamplingFunction::Sum, OverflowPolicy::Saturating),
+        vec![None, None, Some(2)]
+    );
+    assert_eq!(
+        resample(ResamplingFunction::Average, OverflowPolicy::Checked),
+        vec![None, None, Some(2)]
+    );
+}
+
+#[cfg(feature = "complex")]
+#[test]
+fn test_resampling_phasors() {
+    use crate::phasor::Phasor;
+
+    #[derive(Debug, Clone, Copy, Default, PartialEq)]
+    struct PhasorSample {
+        timestamp: DateTime<Utc>,
+        value: Option<Phasor>,
+    }
+
+    impl Sample for PhasorSample {
+        type Value = Phasor;
+
+        fn new(timestamp: DateTime<Utc>, value: Option<Phasor>) -> Self {
+            Self { timestamp, value }
+        }
+
+        fn timestamp(&self) -> DateTime<Utc> {
+            self.timestamp
+        }
+
+        fn value(&self) -> Option<Phasor> {
+            self.value
+        }
+    }
+
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let data = vec![
+        PhasorSample::new(start + step, Some(Phasor::new(230.0, 0.0))),
+        PhasorSample::new(start + step * 2, Some(Phasor::new(0.0, 230.0))),
+        PhasorSample::new(start + step * 3, Some(Phasor::new(-3.0, 4.0))),
+        PhasorSample::new(start + step * 4, Some(Phasor::new(-230.0, 0.0))),
+    ];
+    let resample = |function| {
+        let mut resampler: Resampler<Phasor, PhasorSample> =
+            Resampler::new(TimeDelta::seconds(5), function, 1, start, true);
+        resampler.extend(data.clone());
+        resampler.resample(start + step * 5)[0].value()
+    };
+
+    assert_eq!(
+        resample(ResamplingFunction::Average),
+        Some(Phasor::new(-0.75, 58.5))
+    );
+    assert_eq!(
+        resample(ResamplingFunction::Min),
+        Some(Phasor::new(-3.0, 4.0))
+    );
+    // all other phasors have the same magnitude, and this one the largest
+    // phase angle
+    assert_eq!(
+        resample(ResamplingFunction::Max),
+        Some(Phasor::new(-230.0, 0.0))
+    );
+    assert_eq!(Phasor::from_polar(2.0, 0.0), Phasor::new(2.0, 0.0));
+    assert_eq!(Phasor::new(0.0, 2.0).magnitude(), 2.0);
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct PhasesSample {
+    timestamp: DateTime<Utc>,
+    value: Option<ArrayValue<3>>,
+}
+
+impl Sample for PhasesSample {
+    type Value = ArrayValue<3>;
+
+    fn new(timestamp: DateTime<Utc>, value: Option<ArrayValue<3>>) -> Self {
+        Self { timestamp, value }
+    }
+
+    fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    fn value(&self) -> Option<ArrayValue<3>> {
+        self.value
+    }
+}
+
+#[test]
+fn test_resampling_array_values() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let data = vec![
+        PhasesSample::new(start + step, Some([1.0, 5.0, 3.0].into())),
+        PhasesSample::new(start + step * 2, Some([3.0, 1.0, f64::NAN].into())),
+        PhasesSample::new(start + step * 3, None),
+        PhasesSample::new(start + step * 6, Some([2.0, 2.0, 2.0].into())),
+    ];
+    let resample = |function| {
+        let mut resampler: Resampler<ArrayValue<3>, PhasesSample> =
+            Resampler::new(TimeDelta::seconds(5), function, 1, start, true);
+        resampler.extend(data.clone());
+        resampler
+            .resample(start + step * 10)
+            .into_iter()
+            .map(|s| s.value().map(<[f64; 3]>::from))
+            .collect::<Vec<_>>()
+    };
+
+    for (function, expected) in [
+        (ResamplingFunction::Max, [3.0, 5.0]),
+        (ResamplingFunction::Min, [1.0, 1.0]),
+        (ResamplingFunction::Average, [2.0, 3.0]),
+    ] {
+        let values = resample(function);
+        assert_eq!(values[0].unwrap()[..2], expected);
+        assert!(values[0].unwrap()[2].is_nan());
+        assert_eq!(values[1], Some([2.0, 2.0, 2.0]));
+    }
+    assert_eq!(
+        resample(ResamplingFunction::Count),
+        vec![Some([2.0; 3]), Some([1.0; 3])]
+    );
+}
+
+#[test]
+fn test_nan_policy() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let resample = |function, policy| {
+        let mut resampler: Resampler<f64, TestSample> =
+            Resampler::new(TimeDelta::seconds(5), function, 1, start, true).with_nan_policy(policy);
+        let mut errors = vec![];
+        for (i, value) in [1.0, f64::NAN, 3.0].into_iter().enumerate() {
+            let sample = TestSample::new(start + step * (i as i32 + 1), Some(value));
+            if let Err(e) = resampler.try_push(sample) {
+                errors.push(e);
+            }
+        }
+        let value = resampler.resample(start + step * 5)[0].value();
+        (value, errors)
+    };
+
+    for function in [
+        ResamplingFunction::Sum,
+        ResamplingFunction::Average,
+        ResamplingFunction::Min,
+        ResamplingFunction::Max,
+    ] {
+        let (value, errors) = resample(function, NanPolicy::Propagate);
+        assert!(value.unwrap().is_nan());
+        assert!(errors.is_empty());
+    }
+    assert_eq!(
+        resample(ResamplingFunction::Average, NanPolicy::Ignore),
+        (Some(2.0), vec![])
+    );
+    assert_eq!(
+        resample(ResamplingFunction::Count, NanPolicy::Ignore),
+        (Some(2.0), vec![])
+    );
+    assert_eq!(
+        resample(ResamplingFunction::Max, NanPolicy::Error),
+        (
+            Some(3.0),
+            vec![ResampleError::NotANumber {
+                timestamp: start + step * 2
+            }]
+        )
+    );
+}
+
+#[test]
+fn test_validity_filter() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let dropped = Arc::new(Mutex::new(vec![]));
+    let dropped_clone = dropped.clone();
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::seconds(5),
+        ResamplingFunction::Average,
+        1,
+        start,
+        true,
+    )
+    .with_validity_filter(ValidityFilter::new().with_min(-100.0).with_max(100.0))
+    .with_drop_callback(Box::new(move |sample, reason| {
+        dropped_clone
+            .lock()
+            .unwrap()
+            .push((sample.timestamp(), reason));
+    }));
+    resampler.extend(vec![
+        TestSample::new(start + step, Some(1.0)),
+        TestSample::new(start + step * 2, Some(1e308)),
+        TestSample::new(start + step * 3, Some(f64::NEG_INFINITY)),
+        TestSample::new(start + step * 4, Some(-100.0)),
+        TestSample::new(start + step * 4, None),
+    ]);
+
+    assert_eq!(
+        resampler.resample(start + step * 5),
+        vec![TestSample::new(start, Some(-49.5))]
+    );
+    assert_eq!(resampler.stats().invalid_samples, 2);
+    assert_eq!(
+        *dropped.lock().unwrap(),
+        vec![
+            (start + step * 2, DropReason::Invalid),
+            (start + step * 3, DropReason::Invalid)
+        ]
+    );
+
+    let filter = ValidityFilter::new();
+    assert!(filter.is_valid(1e308));
+    assert!(!filter.is_valid(f64::INFINITY));
+    assert!(filter.with_infinite(true).is_valid(f64::INFINITY));
+    assert!(!filter
+        .with_infinite(true)
+        .with_max(0.0)
+        .is_valid(f64::INFINITY));
+}
+
+#[test]
+fn test_min_max_total_order() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let resample = |function, values: &[f64]| {
+        let mut resampler: Resampler<f64, TestSample> =
+            Resampler::new(TimeDelta::seconds(5), function, 1, start, true);
+        resampler.extend(
+            values
+                .iter()
+                .enumerate()
+                .map(|(i, v)| TestSample::new(start + step * i as i32, Some(*v))),
+        );
+        resampler.resample(start + step * 5)[0].value().unwrap()
+    };
+
+    for max in [false, true] {
+        let results = [
+            [-f64::NAN, 1.0, f64::NAN],
+            [f64::NAN, -f64::NAN, 1.0],
+            [1.0, f64::NAN, -f64::NAN],
+        ]
+        .map(|values| {
+            let function = match max {
+                true => ResamplingFunction::Max,
+                false => ResamplingFunction::Min,
+            };
+            resample(function, &values).to_bits()
+        });
+        assert_eq!(results, [f64::NAN.to_bits(); 3]);
+    }
+    assert_eq!(resample(ResamplingFunction::Max, &[-0.0, 2.0, 1.0]), 2.0);
+    assert_eq!(resample(ResamplingFunction::Min, &[3.0, -1.0, 1.0]), -1.0);
+
+    assert_eq!(
+        ResampleValue::total_cmp(&f64::NAN, &-f64::NAN),
+        Ordering::Equal
+    );
+    assert_eq!(ResampleValue::total_cmp(&f64::NAN, &1.0), Ordering::Greater);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct QualityTestSample {
+    timestamp: DateTime<Utc>,
+    value: Option<f64>,
+    good: bool,
+}
+
+impl Default for QualityTestSample {
+    fn default() -> Self {
+        Self::new(DateTime::default(), None)
+    }
+}
+
+impl Sample for QualityTestSample {
+    type Value = f64;
+
+    fn new(timestamp: DateTime<Utc>, value: Option<f64>) -> Self {
+        Self {
+            timestamp,
+            value,
+            good: true,
+        }
+    }
+
+    fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.value
+    }
+}
+
+impl QualitySample for QualityTestSample {
+    fn is_good(&self) -> bool {
+        self.good
+    }
+}
+
+#[test]
+fn test_quality_filter() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let data = [
+        (1.0, true),
+        (100.0, false),
+        (3.0, true),
+        (7.0, false),
+        (8.0, false),
+    ]
+    .iter()
+    .enumerate()
+    .map(|(i, (value, good))| QualityTestSample {
+        timestamp: start + step * (i as i32 * 2 + 1),
+        value: Some(*value),
+        good: *good,
+    })
+    .collect::<Vec<_>>();
+    let resample = |resampler: Resampler<f64, QualityTestSample>| {
+        let mut resampler = resampler.with_emit_empty_windows(true);
+        resampler.extend(data.clone());
+        resampler
+            .resample_detailed(start + step * 10)
+            .into_iter()
+            .map(|w| (w.sample.value(), w.sample_count, w.bad_count))
+            .collect::<Vec<_>>()
+    };
+
+    let resampler = Resampler::new(step * 5, ResamplingFunction::Average, 1, start, false);
+    assert_eq!(
+        resample(resampler),
+        vec![(Some(104.0 / 3.0), 3, 0), (Some(7.5), 2, 0)]
+    );
+
+    let resampler = Resampler::new(step * 5, ResamplingFunction::Average, 1, start, false);
+    assert_eq!(
+        resample(resampler.with_quality_filter()),
+        vec![(Some(2.0), 2, 1), (None, 0, 2)]
+    );
+
+    let resampler = Resampler::new(step * 5, ResamplingFunction::Average, 1, start, false);
+    let mut resampler = resampler
+        .with_window_spec(WindowSpec::Count(3))
+        .with_quality_filter();
+    resampler.extend(data.clone());
+    let window = resampler.current_window();
+    assert_eq!(
+        (window.sample.value(), window.sample_count, window.bad_count),
+        (Some(2.0), 2, 1)
+    );
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct SourceTestSample {
+    timestamp: DateTime<Utc>,
+    value: Option<f64>,
+    source: u64,
+}
+
+impl Sample for SourceTestSample {
+    type Value = f64;
+
+    fn new(timestamp: DateTime<Utc>, value: Option<f64>) -> Self {
+        Self {
+            timestamp,
+            value,
+            source: 0,
+        }
+    }
+
+    fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.value
+    }
+}
+
+impl SourceSample for SourceTestSample {
+    fn source(&self) -> u64 {
+        self.source
+    }
+}
+
+#[test]
+fn test_source_attribution() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let data = [(2, 1.0), (1, 2.0), (2, 3.0), (1, 4.0), (2, 5.0), (2, 6.0)]
+        .iter()
+        .enumerate()
+        .map(|(i, (source, value))| SourceTestSample {
+            timestamp: start + step * (i as i32 + 1),
+            value: Some(*value),
+            source: *source,
+        })
+        .collect::<Vec<_>>();
+    let aggregate = |source, sample_count, timestamp, value| SourceAggregate {
+        source,
+        sample_count,
+        sample: SourceTestSample::new(timestamp, Some(value)),
+    };
+
+    let mut resampler: Resampler<f64, SourceTestSample> =
+        Resampler::new(step * 3, ResamplingFunction::Sum, 1, start, false)
+            .with_source_attribution();
+    resampler.extend(data.clone());
+    let windows = resampler.resample_detailed(start + step * 6);
+    assert_eq!(
+        windows
+            .iter()
+            .map(|w| (w.sample.value(), w.sources.clone()))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                Some(6.0),
+                vec![
+                    aggregate(1, 1, start + step * 3, 2.0),
+                    aggregate(2, 2, start + step * 3, 4.0),
+                ]
+            ),
+            (
+                Some(15.0),
+                vec![
+                    aggregate(1, 1, start + step * 6, 4.0),
+                    aggregate(2, 2, start + step * 6, 11.0),
+                ]
+            ),
+        ]
+    );
+
+    let mut resampler: Resampler<f64, SourceTestSample> =
+        Resampler::new(step * 3, ResamplingFunction::Average, 1, start, false)
+            .with_window_spec(WindowSpec::Count(4))
+            .with_source_attribution();
+    resampler.extend(data);
+    let window = resampler.current_window();
+    assert_eq!(window.sample.value(), Some(2.5));
+    assert_eq!(
+        window.sources,
+        vec![
+            aggregate(1, 2, window.sample.timestamp(), 3.0),
+            aggregate(2, 2, window.sample.timestamp(), 2.0),
+        ]
+    );
+
+    let mut resampler: Resampler<f64, SourceTestSample> =
+        Resampler::new(step * 3, ResamplingFunction::Sum, 1, start, false);
+    resampler.extend([SourceTestSample::new(start + step, Some(1.0))]);
+    assert!(resampler.resample_detailed(start + step * 3)[0]
+        .sources
+        .is_empty());
+}
+
+#[test]
+fn test_set_interval_with_accumulated_samples() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let step = TimeDelta::seconds(1);
+    let resample = |function, incremental| {
+        let mut resampler: Resampler<f64, TestSample> =
+            Resampler::new(step * 5, function, 1, start, false)
+                .with_incremental_aggregation(incremental);
+        resampler.extend((1..=5).map(|i| TestSample::new(start + step * i, Some(1.0))));
+        resampler.set_interval(step);
+        resampler.extend((6..=7).map(|i| TestSample::new(start + step * i, Some(2.0))));
+        resampler
+            .resample(start + step * 7)
+            .iter()
+            .map(|s| (s.timestamp().timestamp(), s.value()))
+            .collect::<Vec<_>>()
+    };
+
+    // the windows of the new interval are resampled alike by all functions
+    let per_second = (1..=7)
+        .map(|i| (i, Some(if i > 5 { 2.0 } else { 1.0 })))
+        .collect::<Vec<_>>();
+    assert_eq!(resample(ResamplingFunction::Sum, false), per_second);
+    assert_eq!(resample(ResamplingFunction::Last, false), per_second);
+    assert_eq!(resample(ResamplingFunction::Last, true), per_second);
+
+    // aggregated samples can't be split, so the interval changes after
+    // their windows
+    assert_eq!(
+        resample(ResamplingFunction::Sum, true),
+        vec![(5, Some(5.0)), (10, Some(4.0))]
+    );
+}
+
+#[test]
+fn test_epoch_alignment() {
+    let interval = TimeDelta::seconds(5);
     let test_time = DateTime::from_timestamp(3, 0).unwrap();
     assert_eq!(
         epoch_align(interval, test_time, None),
@@ -665,6 +4695,39 @@ fn test_epoch_alignment() {
         ),
         DateTime::from_timestamp(1, 0).unwrap()
     );
+    assert_eq!(
+        epoch_align(
+            interval,
+            test_time,
+            Some(DateTime::from_timestamp(4, 0).unwrap())
+        ),
+        DateTime::from_timestamp(-1, 0).unwrap()
+    );
+}
+
+#[test]
+fn test_resampling_with_alignment_timestamp() {
+    let start = DateTime::from_timestamp(0, 0).unwrap();
+    let mut resampler: Resampler<f64, TestSample> = Resampler::new(
+        TimeDelta::minutes(15),
+        ResamplingFunction::Sum,
+        1,
+        start + TimeDelta::minutes(10),
+        true,
+    )
+    .with_alignment_timestamp(start + TimeDelta::seconds(450));
+    let step = TimeDelta::minutes(5);
+    resampler.extend((0..12).map(|i| TestSample::new(start + step * i, Some(1.0))));
+
+    let aligned = |seconds| start + TimeDelta::seconds(seconds);
+    assert_eq!(
+        resampler.resample(start + TimeDelta::seconds(2250)),
+        vec![
+            TestSample::new(aligned(-450), Some(2.0)),
+            TestSample::new(aligned(450), Some(3.0)),
+            TestSample::new(aligned(1350), Some(3.0)),
+        ]
+    );
 }
 
 #[test]
@@ -727,6 +4790,7 @@ impl Div for NonPrimitive {
 }
 
 impl Sum for NonPrimitive {
+    #[allow(clippy::map_flatten)]
     fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
         Self {
             value: iter.map(|s| s.value).flatten().collect(),
@@ -0,0 +1,221 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! The time_axis module provides the TimeAxis trait that maps other time types
+//! than `DateTime<Utc>` onto the UTC time line of the resampler, so that
+//! samples can be stamped with them, e.g. with monotonic ticks.
+
+use std::fmt::Debug;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant, SystemTime};
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+use crate::resampler::{epoch_align, Sample};
+
+/// The TimeAxis trait represents a time type that samples can be stamped
+/// with. It is an adapter at the boundary of the resampler: the resampler
+/// itself always works on `DateTime<Utc>`, so every time type maps its points
+/// onto the UTC time line, and the arithmetic and alignment of the windows
+/// follows from that mapping. Times beyond the range of `DateTime<Utc>` are
+/// clamped to it.
+///
+/// The provided methods do the window arithmetic on the time type itself,
+/// e.g. to compute the start time of a resampler in ticks.
+pub trait TimeAxis: Copy + PartialOrd + Debug {
+    /// Returns the point on the UTC time line that corresponds to the time.
+    fn to_utc(self) -> DateTime<Utc>;
+
+    /// Returns the time that corresponds to the given point on the UTC time
+    /// line.
+    fn from_utc(timestamp: DateTime<Utc>) -> Self;
+
+    /// Returns the duration from the given earlier time to this time, which
+    /// is negative if the given time is later.
+    fn delta_since(self, earlier: Self) -> TimeDelta {
+        self.to_utc() - earlier.to_utc()
+    }
+
+    /// Returns the time the given duration after this time.
+    fn add_duration(self, duration: TimeDelta) -> Self {
+        Self::from_utc(add_clamped(self.to_utc(), duration))
+    }
+
+    /// Returns the latest time that is not after this time and is a multiple
+    /// of the given interval after the given origin.
+    fn align(self, interval: TimeDelta, origin: Self) -> Self {
+        Self::from_utc(epoch_align(interval, self.to_utc(), Some(origin.to_utc())))
+    }
+}
+
+impl TimeAxis for DateTime<Utc> {
+    fn to_utc(self) -> DateTime<Utc> {
+        self
+    }
+
+    fn from_utc(timestamp: DateTime<Utc>) -> Self {
+        timestamp
+    }
+}
+
+/// A relative time axis, e.g. for monotonic ticks, whose origin is mapped to
+/// the Unix epoch.
+impl TimeAxis for TimeDelta {
+    fn to_utc(self) -> DateTime<Utc> {
+        after_epoch(self)
+    }
+
+    fn from_utc(timestamp: DateTime<Utc>) -> Self {
+        timestamp - DateTime::UNIX_EPOCH
+    }
+}
+
+impl TimeAxis for SystemTime {
+    fn to_utc(self) -> DateTime<Utc> {
+        let delta = match self.duration_since(SystemTime::UNIX_EPOCH) {
+            Ok(duration) => TimeDelta::from_std(duration).unwrap_or(TimeDelta::MAX),
+            Err(e) => -TimeDelta::from_std(e.duration()).unwrap_or(TimeDelta::MAX),
+        };
+        after_epoch(delta)
+    }
+
+    fn from_utc(timestamp: DateTime<Utc>) -> Self {
+        timestamp.into()
+    }
+
+    fn align(self, interval: TimeDelta, origin: Self) -> Self {
+        let since_epoch = |time: SystemTime| match time.duration_since(SystemTime::UNIX_EPOCH) {
+            Ok(duration) => duration.as_nanos() as i128,
+            Err(e) => -(e.duration().as_nanos() as i128),
+        };
+        let aligned = align_nanos(since_epoch(self), interval, since_epoch(origin));
+        let offset = Duration::from_nanos(aligned.unsigned_abs() as u64);
+        if aligned < 0 {
+            SystemTime::UNIX_EPOCH - offset
+        } else {
+            SystemTime::UNIX_EPOCH + offset
+        }
+    }
+}
+
+/// A time axis of durations since the Unix epoch, e.g. for timestamps that
+/// are stored as such. Times before the epoch are clamped to it.
+impl TimeAxis for Duration {
+    fn to_utc(self) -> DateTime<Utc> {
+        after_epoch(TimeDelta::from_std(self).unwrap_or(TimeDelta::MAX))
+    }
+
+    fn from_utc(timestamp: DateTime<Utc>) -> Self {
+        (timestamp - DateTime::UNIX_EPOCH)
+            .to_std()
+            .unwrap_or_default()
+    }
+
+    fn align(self, interval: TimeDelta, origin: Self) -> Self {
+        let aligned = align_nanos(self.as_nanos() as i128, interval, origin.as_nanos() as i128);
+        Duration::from_nanos(aligned.max(0) as u64)
+    }
+}
+
+/// A monotonic time axis, e.g. for realtime control loops whose windows must
+/// not move when the wall clock is stepped. The instants are mapped to the
+/// UTC time line relative to a fixed instant of the process, whose
+/// corresponding point is the Unix epoch, so the windows are aligned to that
+/// instant rather than to the wall clock.
+impl TimeAxis for Instant {
+    fn to_utc(self) -> DateTime<Utc> {
+        let origin = monotonic_origin();
+        let delta = match self.checked_duration_since(origin) {
+            Some(duration) => TimeDelta::from_std(duration).unwrap_or(TimeDelta::MAX),
+            None => -TimeDelta::from_std(origin - self).unwrap_or(TimeDelta::MAX),
+        };
+        after_epoch(delta)
+    }
+
+    fn from_utc(timestamp: DateTime<Utc>) -> Self {
+        let origin = monotonic_origin();
+        let delta = timestamp - DateTime::UNIX_EPOCH;
+        match delta.to_std() {
+            Ok(duration) => origin + duration,
+            Err(_) => {
+                let duration = delta.abs().to_std().unwrap_or_default();
+                origin.checked_sub(duration).unwrap_or(origin)
+            }
+        }
+    }
+}
+
+/// Returns the point on the UTC time line the given duration after the Unix
+/// epoch, clamped to the range of `DateTime<Utc>`.
+fn after_epoch(delta: TimeDelta) -> DateTime<Utc> {
+    add_clamped(DateTime::UNIX_EPOCH, delta)
+}
+
+/// Returns the given time plus the given duration, clamped to the range of
+/// `DateTime<Utc>`.
+fn add_clamped(time: DateTime<Utc>, delta: TimeDelta) -> DateTime<Utc> {
+    time.checked_add_signed(delta)
+        .unwrap_or(if delta > TimeDelta::zero() {
+            DateTime::<Utc>::MAX_UTC
+        } else {
+            DateTime::<Utc>::MIN_UTC
+        })
+}
+
+/// Returns the instant that the monotonic time axis is relative to.
+fn monotonic_origin() -> Instant {
+    static ORIGIN: OnceLock<Instant> = OnceLock::new();
+    *ORIGIN.get_or_init(Instant::now)
+}
+
+/// Returns the latest time in nanoseconds that is not after the given time and
+/// is a multiple of the given interval after the given origin.
+fn align_nanos(time: i128, interval: TimeDelta, origin: i128) -> i128 {
+    let interval = interval.num_nanoseconds().unwrap_or(i64::MAX) as i128;
+    if interval <= 0 {
+        return time;
+    }
+    time - (time - origin).rem_euclid(interval)
+}
+
+/// The AxisSample struct is a sample whose timestamp is of the given
+/// [`TimeAxis`], so that a [`Resampler`][crate::Resampler] can be instantiated
+/// over other time types than `DateTime<Utc>`. The times that are passed to the
+/// resampler, e.g. the start and the end of the resampling, are the
+/// corresponding points on the UTC time line, see [`TimeAxis::to_utc`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AxisSample<A, V> {
+    /// The time of the sample
+    pub time: A,
+    /// The value of the sample
+    pub value: Option<V>,
+}
+
+impl<A: TimeAxis, V> Default for AxisSample<A, V> {
+    fn default() -> Self {
+        Self {
+            time: A::from_utc(DateTime::UNIX_EPOCH),
+            value: None,
+        }
+    }
+}
+
+impl<A: TimeAxis, V: Clone + Debug> Sample for AxisSample<A, V> {
+    type Value = V;
+
+    fn new(timestamp: DateTime<Utc>, value: Option<V>) -> Self {
+        Self {
+            time: A::from_utc(timestamp),
+            value,
+        }
+    }
+
+    fn timestamp(&self) -> DateTime<Utc> {
+        self.time.to_utc()
+    }
+
+    fn value(&self) -> Option<V> {
+        self.value.clone()
+    }
+}
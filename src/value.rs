@@ -0,0 +1,221 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! The value module provides the ResampleValue trait for the values that can
+//! be resampled, and the ArrayValue struct for multi-dimensional values.
+
+use std::cmp::Ordering;
+use std::fmt::Debug;
+use std::ops::Div;
+
+use num_traits::FromPrimitive;
+
+/// The ResampleValue trait provides the arithmetic that the resampling
+/// functions need from the values of the samples.
+///
+/// It is implemented for all types that can be divided by themselves and
+/// created from primitive numbers, like `f64` or `i32`. Values with a
+/// physical unit, whose quotient is a ratio rather than a value of the same
+/// unit, can implement it directly, so that they don't have to be converted to
+/// `f64` for resampling:
+///
+/// ```rust
+/// use frequenz_resampling::ResampleValue;
+///
+/// #[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd)]
+/// struct Power {
+///     watts: f64,
+/// }
+///
+/// impl std::iter::Sum for Power {
+///     fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+///         Power {
+///             watts: iter.map(|p| p.watts).sum(),
+///         }
+///     }
+/// }
+///
+/// impl ResampleValue for Power {
+///     fn from_scalar(value: f64) -> Option<Self> {
+///         Some(Power { watts: value })
+///     }
+///
+///     fn div_scalar(self, divisor: f64) -> Option<Self> {
+///         Some(Power {
+///             watts: self.watts / divisor,
+///         })
+///     }
+/// }
+/// ```
+///
+/// The quantities of the `uom` crate can be resampled through the `Quantity`
+/// wrapper of the `uom` feature.
+pub trait ResampleValue: std::iter::Sum + PartialOrd + Default + Debug + Sized {
+    /// Creates a value from the given number, e.g. for interpolated or
+    /// smoothed values. Returns None if the number can't be represented.
+    fn from_scalar(value: f64) -> Option<Self>;
+
+    /// Creates a value from the given number of samples for the
+    /// [`Count`][crate::ResamplingFunction::Count] resampling function.
+    /// Returns None if the number can't be represented.
+    fn from_count(count: usize) -> Option<Self> {
+        Self::from_scalar(count as f64)
+    }
+
+    /// Divides the value by the given number, e.g. by a duration in seconds
+    /// for the [`Rate`][crate::ResamplingFunction::Rate] resampling function.
+    fn div_scalar(self, divisor: f64) -> Option<Self>;
+
+    /// Divides the value by the given number of samples, e.g. a sum for the
+    /// [`Average`][crate::ResamplingFunction::Average] resampling function.
+    fn div_count(self, count: usize) -> Option<Self> {
+        self.div_scalar(count as f64)
+    }
+
+    /// Returns whether the value is NaN, i.e. not comparable to itself.
+    fn is_nan(&self) -> bool {
+        self.partial_cmp(self).is_none()
+    }
+
+    /// Compares the value with the given one by a total order, in which NaN
+    /// values are equal to each other and larger than all other values, and
+    /// other values that can't be compared are equal.
+    fn total_cmp(&self, other: &Self) -> Ordering {
+        self.partial_cmp(other)
+            .unwrap_or_else(|| self.is_nan().cmp(&other.is_nan()))
+    }
+
+    /// Returns the largest of the given values for the
+    /// [`Max`][crate::ResamplingFunction::Max] resampling function, or NaN
+    /// if any of the values is NaN.
+    fn max_of<I: Iterator<Item = Self>>(values: I) -> Option<Self> {
+        select(values, Ordering::Greater)
+    }
+
+    /// Returns the smallest of the given values for the
+    /// [`Min`][crate::ResamplingFunction::Min] resampling function, or NaN
+    /// if any of the values is NaN.
+    fn min_of<I: Iterator<Item = Self>>(values: I) -> Option<Self> {
+        select(values, Ordering::Less)
+    }
+}
+
+/// Returns the first value that compares as the given ordering to all other
+/// values by the [total order][ResampleValue::total_cmp], or NaN if any of
+/// the values is NaN. NaN values are returned as `NaN` converted from `f64` if
+/// possible, so that the result doesn't depend on which of several NaN values,
+/// e.g. with different signs, comes first.
+fn select<T: ResampleValue, I: Iterator<Item = T>>(values: I, ordering: Ordering) -> Option<T> {
+    let mut selected: Option<T> = None;
+    for value in values {
+        if value.is_nan() {
+            let nan = T::from_scalar(f64::NAN).filter(T::is_nan);
+            return Some(nan.unwrap_or(value));
+        }
+        if selected
+            .as_ref()
+            .is_none_or(|selected| value.total_cmp(selected) == ordering)
+        {
+            selected = Some(value);
+        }
+    }
+    selected
+}
+
+/// The ArrayValue struct is a value with a fixed number of dimensions, e.g.
+/// the phases of a 3-phase measurement, that are resampled element-wise, so
+/// that one resampler produces one resampled sample with all dimensions per
+/// window.
+///
+/// `Min` and `Max` select the smallest or largest value of every dimension,
+/// or NaN if any of its values is NaN, so the result can combine the elements
+/// of different samples. A value is NaN if any of its elements is NaN.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct ArrayValue<const N: usize>(pub [f64; N]);
+
+impl<const N: usize> Default for ArrayValue<N> {
+    fn default() -> Self {
+        Self([0.0; N])
+    }
+}
+
+impl<const N: usize> From<[f64; N]> for ArrayValue<N> {
+    fn from(values: [f64; N]) -> Self {
+        Self(values)
+    }
+}
+
+impl<const N: usize> From<ArrayValue<N>> for [f64; N] {
+    fn from(value: ArrayValue<N>) -> Self {
+        value.0
+    }
+}
+
+impl<const N: usize> std::iter::Sum for ArrayValue<N> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), |sum, value| {
+            Self(std::array::from_fn(|i| sum.0[i] + value.0[i]))
+        })
+    }
+}
+
+impl<const N: usize> ArrayValue<N> {
+    /// Combines the given values element-wise with the given function.
+    fn combine<I: Iterator<Item = Self>>(mut values: I, f: fn(f64, f64) -> f64) -> Option<Self> {
+        let first = values.next()?;
+        Some(values.fold(first, |acc, value| {
+            Self(std::array::from_fn(|i| f(acc.0[i], value.0[i])))
+        }))
+    }
+}
+
+impl<const N: usize> ResampleValue for ArrayValue<N> {
+    fn from_scalar(value: f64) -> Option<Self> {
+        Some(Self([value; N]))
+    }
+
+    fn div_scalar(self, divisor: f64) -> Option<Self> {
+        Some(Self(self.0.map(|value| value / divisor)))
+    }
+
+    fn max_of<I: Iterator<Item = Self>>(values: I) -> Option<Self> {
+        Self::combine(values, |a, b| {
+            if a.is_nan() || b.is_nan() {
+                f64::NAN
+            } else {
+                a.max(b)
+            }
+        })
+    }
+
+    fn min_of<I: Iterator<Item = Self>>(values: I) -> Option<Self> {
+        Self::combine(values, |a, b| {
+            if a.is_nan() || b.is_nan() {
+                f64::NAN
+            } else {
+                a.min(b)
+            }
+        })
+    }
+}
+
+impl<T> ResampleValue for T
+where
+    T: Div<Output = T> + std::iter::Sum + PartialOrd + FromPrimitive + Default + Debug,
+{
+    fn from_scalar(value: f64) -> Option<Self> {
+        T::from_f64(value)
+    }
+
+    fn from_count(count: usize) -> Option<Self> {
+        T::from_usize(count)
+    }
+
+    fn div_scalar(self, divisor: f64) -> Option<Self> {
+        Some(self.div(T::from_f64(divisor)?))
+    }
+
+    fn div_count(self, count: usize) -> Option<Self> {
+        Some(self.div(T::from_usize(count)?))
+    }
+}
@@ -4,8 +4,18 @@
 """Tests to verify that the resampler can be used successfully from Python."""
 
 import datetime as dt
+import sys
 
-from frequenz.resampling import Resampler, ResamplingFunction
+import numpy as np
+import pandas as pd
+import pytest
+
+from frequenz.resampling import (
+    LabelPosition,
+    Resampler,
+    ResamplerF64,
+    ResamplingFunction,
+)
 
 
 def test_resampler_resampling_function_average() -> None:
@@ -355,3 +365,306 @@ def test_resampler_last_timestamp() -> None:
     resampled = resampler.resample(start + 20 * step)
 
     assert resampled == expected
+
+
+def test_resampler_f64() -> None:
+    """Test the resampler with 64-bit values."""
+    start = dt.datetime(1970, 1, 1, tzinfo=dt.timezone.utc)
+    step = dt.timedelta(seconds=1)
+    resampler = ResamplerF64(
+        dt.timedelta(seconds=5),
+        ResamplingFunction.Last,
+        max_age_in_intervals=1,
+        start=start,
+        first_timestamp=False,
+    )
+
+    for i in range(1, 11):
+        resampler.push_sample(timestamp=start + i * step, value=123456789.0 + i)
+
+    expected = [
+        (start + 5 * step, 123456794.0),
+        (start + 10 * step, 123456799.0),
+    ]
+
+    resampled = resampler.resample(start + 10 * step)
+
+    assert resampled == expected
+
+
+def test_resampler_custom_function() -> None:
+    """Test the resampler with a custom resampling function."""
+    start = dt.datetime(1970, 1, 1, tzinfo=dt.timezone.utc)
+    step = dt.timedelta(seconds=1)
+
+    def spread(samples: list[tuple[dt.datetime, float | None]]) -> float | None:
+        values = [value for _, value in samples if value is not None]
+        return max(values) - min(values) if values else None
+
+    resampler = Resampler(
+        dt.timedelta(seconds=5),
+        max_age_in_intervals=1,
+        start=start,
+        first_timestamp=False,
+        custom_function=spread,
+    )
+
+    for i in range(1, 11):
+        resampler.push_sample(timestamp=start + i * step, value=i * i)
+
+    expected = [
+        (start + 5 * step, 24.0),
+        (start + 10 * step, 64.0),
+    ]
+
+    resampled = resampler.resample(start + 10 * step)
+
+    assert resampled == expected
+
+
+def test_resampler_custom_function_error() -> None:
+    """Test that exceptions of a custom resampling function are raised."""
+    start = dt.datetime(1970, 1, 1, tzinfo=dt.timezone.utc)
+
+    def fail(_samples: list[tuple[dt.datetime, float | None]]) -> float | None:
+        raise RuntimeError("custom function failed")
+
+    resampler = ResamplerF64(
+        dt.timedelta(seconds=5),
+        max_age_in_intervals=1,
+        start=start,
+        custom_function=fail,
+    )
+    resampler.push_sample(timestamp=start + dt.timedelta(seconds=1), value=1.0)
+
+    with pytest.raises(RuntimeError, match="custom function failed"):
+        resampler.resample(start + dt.timedelta(seconds=5))
+
+
+def test_resampler_custom_function_conflict() -> None:
+    """Test that exactly one of the resampling functions must be given."""
+    start = dt.datetime(1970, 1, 1, tzinfo=dt.timezone.utc)
+
+    def last(samples: list[tuple[dt.datetime, float | None]]) -> float | None:
+        return samples[-1][1] if samples else None
+
+    with pytest.raises(ValueError, match="either resampling_function or"):
+        Resampler(
+            dt.timedelta(seconds=5),
+            ResamplingFunction.Average,
+            max_age_in_intervals=1,
+            start=start,
+            custom_function=last,
+        )
+    with pytest.raises(ValueError, match="either resampling_function or"):
+        Resampler(dt.timedelta(seconds=5), max_age_in_intervals=1, start=start)
+
+
+def test_resampler_label_position() -> None:
+    """Test the resampler with a label position."""
+    start = dt.datetime(1970, 1, 1, tzinfo=dt.timezone.utc)
+    step = dt.timedelta(seconds=1)
+    resampler = Resampler(
+        dt.timedelta(seconds=4),
+        ResamplingFunction.Average,
+        max_age_in_intervals=1,
+        start=start,
+        first_timestamp=False,
+        label_position=LabelPosition.Center,
+    )
+
+    for i in range(1, 9):
+        resampler.push_sample(timestamp=start + i * step, value=i)
+
+    expected = [
+        (start + 2 * step, 2.5),
+        (start + 6 * step, 6.5),
+    ]
+
+    resampled = resampler.resample(start + 8 * step)
+
+    assert resampled == expected
+
+
+def test_label_position_enum() -> None:
+    """Test the LabelPosition enum."""
+    assert LabelPosition.values() == [0, 1, 2]
+    assert LabelPosition.members() == [("Left", 0), ("Right", 1), ("Center", 2)]
+    assert LabelPosition(2) == LabelPosition.Center
+    assert str(LabelPosition.Left) == "LabelPosition.Left"
+    assert repr(LabelPosition.Right) == "<LabelPosition.Right: 1>"
+
+
+def test_resampler_push_samples() -> None:
+    """Test pushing many samples at once."""
+    start = dt.datetime(1970, 1, 1, tzinfo=dt.timezone.utc)
+    step = dt.timedelta(seconds=1)
+    resampler = ResamplerF64(
+        dt.timedelta(seconds=5),
+        ResamplingFunction.Sum,
+        max_age_in_intervals=1,
+        start=start,
+        first_timestamp=False,
+    )
+
+    resampler.push_samples([(start + i * step, float(i)) for i in range(1, 6)])
+    resampler.push_samples((start + i * step, None if i == 8 else i) for i in range(6, 11))
+
+    expected = [
+        (start + 5 * step, 15.0),
+        (start + 10 * step, 32.0),
+    ]
+
+    resampled = resampler.resample(start + 10 * step)
+
+    assert resampled == expected
+
+    with pytest.raises(TypeError):
+        resampler.push_samples([(start + 11 * step, 1.0), (start, "invalid")])
+    assert resampler.resample(start + 15 * step) == [(start + 15 * step, None)]
+
+
+def test_resampler_arrays() -> None:
+    """Test pushing and resampling NumPy arrays."""
+    start = dt.datetime(1970, 1, 1, tzinfo=dt.timezone.utc)
+    resampler = ResamplerF64(
+        dt.timedelta(seconds=5),
+        ResamplingFunction.Average,
+        max_age_in_intervals=1,
+        start=start,
+        first_timestamp=False,
+    )
+
+    timestamps = np.arange(1, 16, dtype=np.int64) * 1_000_000_000
+    values = np.arange(1, 16, dtype=np.float64)
+    values[3] = np.nan
+    values[10:] = np.nan
+    resampler.push_array(timestamps, values)
+
+    resampled_timestamps, resampled_values = resampler.resample_arrays(
+        start + dt.timedelta(seconds=15)
+    )
+
+    np.testing.assert_array_equal(
+        resampled_timestamps.view("datetime64[ns]"),
+        np.array(
+            ["1970-01-01T00:00:05", "1970-01-01T00:00:10", "1970-01-01T00:00:15"],
+            dtype="datetime64[ns]",
+        ),
+    )
+    np.testing.assert_array_equal(resampled_values, [2.75, 8.0, np.nan])
+
+    with pytest.raises(ValueError):
+        resampler.push_array(timestamps, values[:3])
+
+
+def test_resampler_datetime_arrays() -> None:
+    """Test pushing NumPy arrays of datetime64 timestamps."""
+    start = dt.datetime(1970, 1, 1, tzinfo=dt.timezone.utc)
+    resampler = Resampler(
+        dt.timedelta(seconds=5),
+        ResamplingFunction.Sum,
+        max_age_in_intervals=1,
+        start=start,
+        first_timestamp=False,
+    )
+
+    # timestamps of any unit are converted to nanoseconds
+    resampler.push_array(
+        np.array(["1970-01-01T00:00:01", "1970-01-01T00:00:04"], dtype="datetime64[s]"),
+        np.array([1.0, 2.0]),
+    )
+    resampler.push_array(
+        np.array(["1970-01-01T00:00:06.5"], dtype="datetime64[ms]"),
+        np.array([4.0]),
+    )
+
+    assert resampler.resample(start + dt.timedelta(seconds=10)) == [
+        (start + dt.timedelta(seconds=5), 3.0),
+        (start + dt.timedelta(seconds=10), 4.0),
+    ]
+
+
+def test_resampler_array_casts() -> None:
+    """Test pushing NumPy arrays of other types than int64 and float64."""
+    start = dt.datetime(1970, 1, 1, tzinfo=dt.timezone.utc)
+    resampler = Resampler(
+        dt.timedelta(seconds=5),
+        ResamplingFunction.Sum,
+        max_age_in_intervals=1,
+        start=start,
+        first_timestamp=False,
+    )
+
+    # the timestamps are cast to int64 and the values to float64, also from lists
+    resampler.push_array(
+        np.array([1, 4], dtype=np.uint64) * 1_000_000_000,
+        np.array([1.5, 2.0], dtype=np.float32),
+    )
+    resampler.push_array(
+        [6_000_000_000, 7_000_000_000],
+        np.array([4, 5], dtype=np.int16),
+    )
+
+    assert resampler.resample(start + dt.timedelta(seconds=10)) == [
+        (start + dt.timedelta(seconds=5), 3.5),
+        (start + dt.timedelta(seconds=10), 9.0),
+    ]
+
+    # NaN values are still missing after the cast
+    resampler.push_array(
+        np.array([11, 12], dtype=np.int64) * 1_000_000_000,
+        np.array([np.nan, 1.0], dtype=np.float32),
+    )
+    assert resampler.resample(start + dt.timedelta(seconds=15)) == [
+        (start + dt.timedelta(seconds=15), 1.0),
+    ]
+
+
+def test_resampler_pandas() -> None:
+    """Test pushing and resampling pandas Series."""
+    start = dt.datetime(1970, 1, 1, tzinfo=dt.timezone.utc)
+    resampler = Resampler(
+        dt.timedelta(seconds=5),
+        ResamplingFunction.Sum,
+        max_age_in_intervals=1,
+        start=start,
+        first_timestamp=False,
+    )
+
+    index = pd.date_range(start + dt.timedelta(seconds=1), periods=10, freq="1s")
+    series = pd.Series([float(i) for i in range(1, 11)], index=index)
+    series.iloc[1] = None
+    resampler.push_series(series)
+
+    resampled = resampler.resample_to_pandas(start + dt.timedelta(seconds=15))
+
+    expected = pd.Series(
+        [13.0, 40.0, np.nan],
+        index=pd.date_range(start + dt.timedelta(seconds=5), periods=3, freq="5s"),
+    )
+    pd.testing.assert_series_equal(resampled, expected, check_freq=False)
+
+    with pytest.raises(TypeError):
+        resampler.push_series(pd.Series([1.0, 2.0]))
+
+
+def test_resampler_pandas_not_installed(monkeypatch: pytest.MonkeyPatch) -> None:
+    """Test that resampling into a Series falls back to lists without pandas."""
+    start = dt.datetime(1970, 1, 1, tzinfo=dt.timezone.utc)
+    resampler = Resampler(
+        dt.timedelta(seconds=5),
+        ResamplingFunction.Sum,
+        max_age_in_intervals=1,
+        start=start,
+        first_timestamp=False,
+    )
+    resampler.push_sample(timestamp=start + dt.timedelta(seconds=1), value=1.0)
+    monkeypatch.setitem(sys.modules, "pandas", None)
+
+    resampled = resampler.resample_to_pandas(start + dt.timedelta(seconds=10))
+
+    assert resampled == (
+        [start + dt.timedelta(seconds=5), start + dt.timedelta(seconds=10)],
+        [1.0, None],
+    )